@@ -24,6 +24,7 @@ pub enum EntityType {
     BroadcastTransaction = 24,
     RequestCurrentTickInfo = 27,
     RespondCurrentTickInfo = 28,
+    RequestTickTransactions = 29,
     RequestEntity = 31,
     ResponseEntity = 32,
     ResponseEnd = 35,
@@ -58,6 +59,7 @@ impl EntityType {
             EntityType::BroadcastTransaction => 24,
             EntityType::RequestCurrentTickInfo => 27,
             EntityType::RespondCurrentTickInfo => 28,
+            EntityType::RequestTickTransactions => 29,
             EntityType::RequestEntity => 31,
             EntityType::ResponseEntity => 32,
             EntityType::ResponseEnd => 35,
@@ -144,6 +146,7 @@ impl RequestResponseHeader {
             24 => EntityType::BroadcastTransaction,
             27 => EntityType::RequestCurrentTickInfo,
             28 => EntityType::RespondCurrentTickInfo,
+            29 => EntityType::RequestTickTransactions,
             31 => EntityType::RequestEntity,
             32 => EntityType::ResponseEntity,
             35 => EntityType::ResponseEnd,
@@ -93,6 +93,24 @@ impl QubicApiPacket {
     }
     
     
+    //Transaction flags are all zero, so the peer sends back every transaction in the tick
+    pub fn request_tick_transactions(tick: u32) -> Self {
+        let mut header = RequestResponseHeader::new();
+        header.set_type(EntityType::RequestTickTransactions);
+
+        let mut data: Vec<u8> = tick.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0u8; 1024 / 8]);
+        let size = std::mem::size_of::<RequestResponseHeader>() + data.len();
+        header.set_size(size);
+        QubicApiPacket {
+            api_type: EntityType::RequestTickTransactions,
+            peer: None,
+            header,
+            data: data,
+            response_data: None
+        }
+    }
+
     pub fn request_owned_assets(pub_key: &[u8; 32]) -> Self {
         let mut header = RequestResponseHeader::new();
         header.set_type(EntityType::RequestOwnedAssets);
@@ -4,21 +4,30 @@ use std::ops::Index;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use consensus::computor::BroadcastComputors;
+use consensus::quorum_votes::QuorumReport;
+use consensus::tick_chain::TickDigests;
+use protocol::network_profile::get_network_profile;
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::QubicApiPacket;
 use crate::header::EntityType;
 use crate::response::exchange_peers::ExchangePeersEntity;
 use crate::response::response_entity::ResponseEntity;
 //use crate::response::broadcast_transaction::BroadcastTransactionEntity;
+use crate::response::current_tick_info::CurrentTickInfo;
+use store::sqlite::peer_tick;
+use protocol::tick_quorum::{is_tick_outlier, quorum_tick, TICK_REPORT_MAX_AGE_SECS};
 
 use store::get_db_path;
 use store::sqlite::response_entity::create_response_entity;
 use store::sqlite::peer::update_peer_last_responded;
 use store::sqlite::tick::insert_tick;
+use store::sqlite::quorum_report::QuorumReportRecord;
+use store::sqlite::tick_data::TickDataRecord;
 use crate::response::broadcast_transaction::BroadcastTransactionEntity;
 use consensus::tick::Tick;
 use consensus::tick_data::{TickData, TransactionDigest};
 use crypto::qubic_identities::get_identity;
+use crypto::encoding::bytes_to_hex;
 use uuid::Uuid;
 use logger::error;
 use smart_contract::qx::orderbook::{AssetOrdersRequest, OrderBook};
@@ -29,6 +38,7 @@ pub mod exchange_peers;
 pub mod response_entity;
 pub mod broadcast_transaction;
 pub mod request_tick_data;
+pub mod current_tick_info;
 mod tick;
 mod asset;
 mod asset_order;
@@ -45,6 +55,79 @@ fn delete_request_from_matcher(dejavu: u32, requests: Arc<Mutex<HashMap<u32, Qub
     }
 }
 
+fn quorum_report_record(report: &QuorumReport) -> QuorumReportRecord {
+    let join = |indices: &Vec<u16>| indices.iter().map(|index| index.to_string()).collect::<Vec<String>>().join(",");
+    QuorumReportRecord {
+        tick: report.tick,
+        epoch: report.epoch,
+        passed: report.passed,
+        required_votes: report.required_votes,
+        winning_digest: report.winning_digest.clone().unwrap_or_default(),
+        winning_votes: report.winning_votes() as u16,
+        vote_groups: report.vote_groups.iter().map(|group| format!("{}:{}", group.digest, group.computors.len())).collect::<Vec<String>>().join(","),
+        invalid_signatures: join(&report.invalid_signatures),
+        missing_computors: join(&report.missing_computors)
+    }
+}
+
+fn tick_data_record(data: &TickData) -> TickDataRecord {
+    TickDataRecord {
+        tick: data.tick,
+        epoch: data.epoch,
+        computor_index: data.computor_index,
+        timestamp: data.timestamp().unwrap_or_default(),
+        unix_ms: data.unix_millis().unwrap_or(0),
+        transaction_count: data.transaction_count() as u16,
+        total_contract_fees: data.total_contract_fees(),
+        contract_fees: data.contract_fee_entries()
+    }
+}
+
+//Store a transaction once it checks out against its signature and the verified digests for its tick,
+//and record it as incoming history if it pays one of our identities
+fn store_tick_transaction(tx: &BroadcastTransactionEntity) {
+    if !tx.verify() {
+        println!("Received Transaction With Invalid Signature For Tick {}", tx.tx._tick);
+        return;
+    }
+    let digest: Vec<u8> = tx.digest();
+    match store::sqlite::tick::tick_includes_transaction_digest(get_db_path().as_str(), tx.tx._tick, digest.as_slice()) {
+        Ok(true) => {
+            let txid: String = tx.txid();
+            let source: String = tx.source_identity();
+            let destination: String = tx.destination_identity();
+            match store::sqlite::tick_transaction::create_tick_transaction(
+                get_db_path().as_str(),
+                txid.as_str(),
+                tx.tx._tick,
+                source.as_str(),
+                destination.as_str(),
+                tx.tx._amount,
+                tx.tx._input_type,
+                tx.tx._input_size,
+                bytes_to_hex(&tx.input).as_str(),
+                bytes_to_hex(&tx.tx._signature).as_str()
+            ) {
+                Ok(_) => {},
+                Err(err) => error!("Failed To Store Transaction {}: {}", txid, err)
+            }
+            if tx.tx._amount < 1 || store::sqlite::identity::fetch_identity(get_db_path().as_str(), destination.as_str()).is_err() {
+                return;
+            }
+            match store::sqlite::incoming_transfer::create_incoming_transfer(get_db_path().as_str(), txid.as_str(), source.as_str(), destination.as_str(), tx.tx._amount, tx.tx._tick) {
+                Ok(_) => {
+                    store::sqlite::incoming_transfer::set_incoming_tick_indexed(get_db_path().as_str(), destination.as_str(), tx.tx._tick).ok();
+                },
+                Err(err) => error!("Failed To Record Incoming Transfer {}: {}", txid, err)
+            }
+        },
+        Ok(false) => {},
+        Err(_err) => {
+            //We don't have the digests for this tick (yet)
+        }
+    }
+}
+
 pub fn get_formatted_response_from_multiple(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>, response: &mut Vec<QubicApiPacket>) {
     let packet = response.first().unwrap();
     let peer = match &packet.peer {
@@ -179,9 +262,19 @@ pub fn get_formatted_response_from_multiple(requests: Arc<Mutex<HashMap<u32, Qub
                 Ok(bytes) => {
                     let bc: BroadcastComputors = BroadcastComputors::new(&bytes);
                     match consensus::quorum_votes::get_quorum_votes(&bc, &tick_data) {
-                        Ok(votes) => {
-                            //println!("Quorum Votes For Epoch {} Validated - {}", epoch, votes);
-                            if votes {
+                        Ok(report) => {
+                            //println!("Quorum Votes For Epoch {} Validated - {}", epoch, report.passed);
+                            if let Err(err) = store::sqlite::quorum_report::record_quorum_report(get_db_path().as_str(), &quorum_report_record(&report)) {
+                                eprintln!("Failed To Record Quorum Report Of Tick.({})\n\t({})\n", tick, err);
+                            }
+                            if report.passed {
+                                let _ = store::sqlite::epoch::delete_pending_tick_validation(get_db_path().as_str(), tick);
+                                if let Some(vote) = report.winning_vote.as_ref() {
+                                    let digests = bytes_to_hex(&TickDigests::from_tick(vote).as_bytes().to_vec());
+                                    if let Err(err) = store::sqlite::tick_chain::record_verified_tick(get_db_path().as_str(), tick, epoch, digests.as_str()) {
+                                        eprintln!("Failed To Record Digests Of Tick.({})\n\t({})\n", tick, err);
+                                    }
+                                }
                                 //In case we missed this tick, perhaps we weren't running when it executed
                                 match store::sqlite::tick::insert_tick(get_db_path().as_str(), peer.as_str(), tick) {
                                     Ok(_) => {
@@ -211,6 +304,10 @@ pub fn get_formatted_response_from_multiple(requests: Arc<Mutex<HashMap<u32, Qub
                 },
                 Err(err) => {
                     println!("Failed to fetch computor by epoch: {}", err);
+                    //Checked again once the epoch manager has this epoch's computor list
+                    if let Err(err) = store::sqlite::epoch::insert_pending_tick_validation(get_db_path().as_str(), tick, epoch) {
+                        println!("Failed To Queue Tick.({}) For Validation: {}", tick, err);
+                    }
                 }
 
             }
@@ -235,6 +332,7 @@ pub fn get_formatted_response(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>
                             match store::sqlite::computors::insert_computors_from_bytes(get_db_path().as_str(), peer.as_str(), &response.data) {
                                 Ok(_) => {
                                     //println!("Updating Computor List for Epoch {}.", bc.epoch);
+                                    let _ = store::sqlite::epoch::set_epoch_computors_validated(get_db_path().as_str(), bc.epoch);
                                 },
                                 Err(_) => {}
                             }
@@ -247,20 +345,33 @@ pub fn get_formatted_response(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>
             }
         },
         EntityType::RespondCurrentTickInfo => {
-            if let Some(peer_id) = &response.peer {
-                if response.data.len() < 12 {
-                    println!("Malformed Current Tick Response.");
-                } else {
-                    let mut data: [u8; 4] = [0; 4];
-                    data[0] = response.data[4];
-                    data[1] = response.data[5];
-                    data[2] = response.data[6];
-                    data[3] = response.data[7];
-                    let value = u32::from_le_bytes(data);
-                    match insert_tick(get_db_path().as_str(), peer_id.as_str(), value) {
-                        Ok(_) => {},
-                        Err(_err) => {}
-                    }
+            if let Some(peer_id) = response.peer.clone() {
+                match CurrentTickInfo::format_qubic_response_data_to_structure(response) {
+                    Some(info) => {
+                        if let Err(err) = peer_tick::record_peer_tick_info(get_db_path().as_str(), peer_id.as_str(), info.tick, info.epoch, info.tick_duration, info.number_of_aligned_votes, info.number_of_misaligned_votes, info.initial_tick) {
+                            error!("Failed To Record Tick Info From Peer {}: {}", peer_id, err);
+                            return;
+                        }
+                        //Only a tick most connected peers agree on becomes our latest tick, a peer far from it is scored as an outlier
+                        let reports: Vec<u32> = peer_tick::fetch_recent_peer_ticks(get_db_path().as_str(), TICK_REPORT_MAX_AGE_SECS)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|(_, tick)| tick)
+                            .collect();
+                        let connected_peers: usize = store::sqlite::peer::fetch_connected_peers(get_db_path().as_str()).map(|peers| peers.len()).unwrap_or(0);
+                        if let Some(tick) = quorum_tick(reports.as_slice(), connected_peers) {
+                            let outlier: bool = is_tick_outlier(info.tick, tick);
+                            if outlier {
+                                println!("Peer {} Reported Tick {}, {} Away From The Quorum Tick {}", peer_id, info.tick, info.tick.abs_diff(tick), tick);
+                            }
+                            let _ = peer_tick::score_peer_tick_report(get_db_path().as_str(), peer_id.as_str(), info.tick, outlier);
+                            match insert_tick(get_db_path().as_str(), peer_id.as_str(), tick) {
+                                Ok(_) => {},
+                                Err(_err) => {}
+                            }
+                        }
+                    },
+                    None => println!("Malformed Current Tick Response.")
                 }
             }
         },
@@ -271,7 +382,7 @@ pub fn get_formatted_response(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>
                     match update_peer_last_responded(path.as_str(), resp.peer.as_str(), SystemTime::now()) {
                         Ok(_) => {
                             for i in resp.ip_addresses {
-                                let address: String = format!("{}.{}.{}.{}:21841", i[0], i[1], i[2], i[3]);
+                                let address: String = format!("{}.{}.{}.{}:{}", i[0], i[1], i[2], i[3], get_network_profile().peer_port);
                                 //println!("Adding Peer to db {}", address.as_str());
                                 match std::net::SocketAddrV4::from_str(address.as_str()) {
                                     Ok(_) => {
@@ -311,9 +422,15 @@ pub fn get_formatted_response(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>
                             let bc = BroadcastComputors::new(&_bc);
                             let verified = resp.validate(&bc);
                             if verified {
+                                //Only a copy matching the transaction digest quorum agreed on is kept, see record_tick_data
+                                let data_hash: String = get_identity(&resp.hash_with_signature_bytes());
+                                if let Err(err) = store::sqlite::tick_data::record_tick_data(get_db_path().as_str(), &tick_data_record(&resp), data_hash.as_str()) {
+                                    println!("Failed To Record Tick Data For Tick {}! <{}>", resp.tick, err);
+                                }
                                 match store::sqlite::transfer::fetch_expired_and_broadcasted_transfers_with_unknown_status_and_specific_tick(get_db_path().as_str(), resp.tick) {
                                     Ok(transfers) => {
-                                        if transfers.len() > 0 {    //We have made at least 1 transfer that executes on this tick!
+                                        let incoming_pending: bool = store::sqlite::incoming_transfer::is_incoming_tick_pending(get_db_path().as_str(), resp.tick).unwrap_or(false);
+                                        if transfers.len() > 0 || incoming_pending {    //We have made at least 1 transfer that executes on this tick, or are indexing one sent to us!
                                             //Let's store the tx digests
                                             let digests: &[TransactionDigest] = resp.transaction_digests.as_slice();
                                             let mut dg: [u8; size_of::<TransactionDigest>()*1024] = [0u8; size_of::<TransactionDigest>()*1024];
@@ -379,6 +496,13 @@ pub fn get_formatted_response(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>
                     ) {
                         Ok(_) => {
                             update_peer_last_responded(path.as_str(), resp.peer.as_str(), SystemTime::now()).ok();
+                            //A new latest incoming tick means someone paid us, queue that tick to be indexed
+                            if resp.latest_incoming_transfer_tick > 0 {
+                                match store::sqlite::incoming_transfer::queue_incoming_tick(path.as_str(), resp.identity.as_str(), resp.latest_incoming_transfer_tick) {
+                                    Ok(_) => {},
+                                    Err(err) => error!("Failed To Queue Incoming Tick {} For {}: {}", resp.latest_incoming_transfer_tick, resp.identity.as_str(), err)
+                                }
+                            }
                         },
                         Err(err) => {
                             println!("Failed To Insert Response Entity: {}", err);
@@ -397,8 +521,14 @@ pub fn get_formatted_response(requests: Arc<Mutex<HashMap<u32, QubicApiPacket>>>
         },
         EntityType::BroadcastTransaction => {
             match BroadcastTransactionEntity::format_qubic_response_data_to_structure(response) {
-                Some(_) => {
-                    //TODO: Insert this tx into db and update status as succeeded
+                Some(tx) => {
+                    //A peer relaying one of our transfers back is our ack that it took it
+                    if let Some(peer) = response.peer.as_ref() {
+                        if let Err(err) = store::sqlite::transfer_broadcast::acknowledge_transfer_broadcast(get_db_path().as_str(), tx.txid().as_str(), peer.as_str()) {
+                            error!("Failed To Acknowledge Broadcast From Peer {}: {}", peer, err);
+                        }
+                    }
+                    store_tick_transaction(&tx);
                 },
                 None => {}
             }
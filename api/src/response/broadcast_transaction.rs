@@ -1,8 +1,8 @@
 use crate::QubicApiPacket;
 use crate::response::FormatQubicResponseDataToStructure;
-use protocol::transfer::TransferTransaction;
+use protocol::tick_transaction::TickTransaction;
 
-pub type BroadcastTransactionEntity = TransferTransaction;
+pub type BroadcastTransactionEntity = TickTransaction;
 
 impl FormatQubicResponseDataToStructure for BroadcastTransactionEntity {
     fn format_qubic_response_data_to_structure(response: &mut QubicApiPacket) -> Option<Self> {
@@ -18,5 +18,11 @@ pub fn handle_broadcast_transaction_entity(response: &mut QubicApiPacket) -> Opt
     if response.header.as_bytes().len() + response.data.len() < 8 + 80 {
         return None;
     }
-    Some(BroadcastTransactionEntity::from_bytes(response.data.as_slice()))
-}
\ No newline at end of file
+    match BroadcastTransactionEntity::from_bytes(response.data.as_slice()) {
+        Ok(tx) => Some(tx),
+        Err(_err) => {
+            //println!("Failed To Parse Transaction: {}", _err);
+            None
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::QubicApiPacket;
+use crate::response::FormatQubicResponseDataToStructure;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurrentTickInfo {
+    pub tick_duration: u16,
+    pub epoch: u16,
+    pub tick: u32,
+    pub number_of_aligned_votes: u16,
+    pub number_of_misaligned_votes: u16,
+    pub initial_tick: u32
+}
+
+impl CurrentTickInfo {
+    pub fn new(data: &[u8]) -> CurrentTickInfo {
+        CurrentTickInfo {
+            tick_duration: u16::from_le_bytes([data[0], data[1]]),
+            epoch: u16::from_le_bytes([data[2], data[3]]),
+            tick: u32::from_le_bytes([data[4], data[5], data[6], data[7]]),
+            number_of_aligned_votes: u16::from_le_bytes([data[8], data[9]]),
+            number_of_misaligned_votes: u16::from_le_bytes([data[10], data[11]]),
+            //Older nodes end the response before the initial tick
+            initial_tick: match data.len() >= 16 {
+                true => u32::from_le_bytes([data[12], data[13], data[14], data[15]]),
+                false => 0
+            }
+        }
+    }
+}
+
+impl FormatQubicResponseDataToStructure for CurrentTickInfo {
+    fn format_qubic_response_data_to_structure(response: &mut QubicApiPacket) -> Option<Self> { handle_current_tick_info(response) }
+}
+
+pub fn handle_current_tick_info(response: &mut QubicApiPacket) -> Option<CurrentTickInfo> {
+    if response.data.len() < 12 {
+        return None;
+    }
+    Some(CurrentTickInfo::new(&response.data))
+}
+
+
+#[test]
+fn parse_current_tick_info() {
+    let mut data: Vec<u8> = Vec::new();
+    data.extend_from_slice(&2000u16.to_le_bytes());
+    data.extend_from_slice(&150u16.to_le_bytes());
+    data.extend_from_slice(&17_000_123u32.to_le_bytes());
+    data.extend_from_slice(&451u16.to_le_bytes());
+    data.extend_from_slice(&3u16.to_le_bytes());
+    data.extend_from_slice(&17_000_000u32.to_le_bytes());
+    let info = CurrentTickInfo::new(&data);
+    assert_eq!(info, CurrentTickInfo {
+        tick_duration: 2000,
+        epoch: 150,
+        tick: 17_000_123,
+        number_of_aligned_votes: 451,
+        number_of_misaligned_votes: 3,
+        initial_tick: 17_000_000
+    });
+    assert_eq!(CurrentTickInfo::new(&data[..12]).initial_tick, 0);
+}
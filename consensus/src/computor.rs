@@ -1,7 +1,8 @@
 use std::convert::TryFrom;
 use crypto::hash::k12_bytes;
 use crypto::qubic_identities::{get_public_key_from_identity, verify};
-use crate::{ARBITRATOR, NUMBER_COMPUTORS};
+use protocol::network_profile::get_network_profile;
+use crate::NUMBER_COMPUTORS;
 
 pub type ComputorPubKey = [u8; 32];
 #[derive(Debug, Clone)]
@@ -34,7 +35,7 @@ impl BroadcastComputors {
     }
     pub fn validate(&self) -> bool {
         let sig: [u8; 64] = self.signature;
-        let arbitrator: [u8; 32] = get_public_key_from_identity(&String::from(ARBITRATOR)).unwrap();
+        let arbitrator: [u8; 32] = get_public_key_from_identity(&get_network_profile().arbitrator).unwrap();
         let hash: Vec<u8> = self.hash_without_signature();
         let message_digest: [u8; 32] = hash.as_slice().to_owned().try_into().unwrap();
         let verified: bool = verify(&arbitrator, &message_digest, &sig);
@@ -1,3 +1,4 @@
+//Mainnet values, the running network's arbitrator and quorum come from protocol::network_profile
 pub const NUMBER_COMPUTORS: usize = 676;
 pub const QUORUM_MINIMUM_VOTES: u16 = 451;
 pub const TICK_TYPE: u8= 3;
@@ -8,6 +9,7 @@ pub mod tick;
 pub mod quorum_votes;
 pub mod computor;
 pub mod tick_data;
+pub mod tick_chain;
 pub mod consensus_tests {
     #![allow(dead_code, unused)]
     use lazy_static::lazy_static;
@@ -52,6 +54,14 @@ pub mod consensus_tests {
         let mut v: Vec<Tick> = Vec::with_capacity(1);
         v.push(t);
         let result = get_quorum_votes(&computors, &v).unwrap(); //Won't pass, need >=451 ticks to pass Quorum
-        assert_eq!(result, false);
+        assert_eq!(result.passed, false);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn mainnet_profile_matches_consensus_constants() {
+        let mainnet = protocol::network_profile::NetworkProfile::mainnet();
+        assert_eq!(mainnet.arbitrator.as_str(), crate::ARBITRATOR);
+        assert_eq!(mainnet.number_computors, crate::NUMBER_COMPUTORS);
+        assert_eq!(mainnet.quorum_minimum_votes, crate::QUORUM_MINIMUM_VOTES);
+    }
+}
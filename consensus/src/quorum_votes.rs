@@ -1,98 +1,130 @@
-use crypto::qubic_identities::{get_public_key_from_identity, verify};
-use crate::{ARBITRATOR, NUMBER_COMPUTORS, TICK_TYPE};
+use crypto::encoding::bytes_to_hex;
+use crypto::hash::k12_bytes;
+use crypto::qubic_identities::{verify_batch, SignedDigest};
+use protocol::network_profile::get_network_profile;
+use crate::{NUMBER_COMPUTORS, TICK_TYPE};
 use crate::computor::BroadcastComputors;
 use crate::tick::Tick;
 
-pub fn get_quorum_votes(bc: &BroadcastComputors, ticks: &Vec<Tick>) -> Result<bool, String> {
-    const NUM_VOTE_FLAGS: usize = (NUMBER_COMPUTORS + 7) / 8;
-    let _arbitrator: [u8; 32] = get_public_key_from_identity(&String::from(ARBITRATOR)).unwrap();
-    let mut _vote_flags: [u8; NUM_VOTE_FLAGS] = [0; NUM_VOTE_FLAGS];
-    if ticks.len() == 0 as usize {
-        return Ok(false);
-    }
-    //println!("Getting Quorum Votes For Tick {}", ticks.first().unwrap().tick);
-    for (_, vote) in ticks.iter().enumerate() {
-        let mut tick = vote.clone();
-        tick.computor_index = tick.computor_index ^ TICK_TYPE as u16;
-        let digest: [u8; 32] = tick.hash();
-        tick.computor_index = tick.computor_index ^ TICK_TYPE as u16;
-        let pub_key = &bc.pub_keys[tick.computor_index as usize];
-        if !verify(&pub_key, &digest, &tick.signature) {
-            eprintln!("Signature of Computor.({}) is not correct\n", tick.computor_index);
-            return Ok(false);
-        }
+//Computors who signed the same tick contents, keyed by the hex digest of what they agreed on
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuorumVoteGroup {
+    pub digest: String,
+    pub computors: Vec<u16>
+}
+
+//Everything learned checking a quorum tick's votes, kept per tick to troubleshoot ticks that don't validate
+#[derive(Debug, Clone)]
+pub struct QuorumReport {
+    pub tick: u32,
+    pub epoch: u16,
+    pub required_votes: u16,
+    //Largest group first
+    pub vote_groups: Vec<QuorumVoteGroup>,
+    pub invalid_signatures: Vec<u16>,
+    //Computors with no valid vote in the response
+    pub missing_computors: Vec<u16>,
+    pub winning_digest: Option<String>,
+    pub winning_vote: Option<Tick>,
+    pub passed: bool
+}
+
+impl QuorumReport {
+    pub fn winning_votes(&self) -> usize {
+        self.vote_groups.first().map(|group| group.computors.len()).unwrap_or(0)
     }
-        //All Ticks Verified
-        let mut passed: bool = false;
-        let mut vote_indices: Vec<Vec<i32>> = Vec::new();
-        let mut unique_votes: Vec<Tick> = Vec::new();
-        get_unique_votes(ticks, &mut unique_votes, &mut vote_indices);
-        //println!("Number of unique votes: {}", unique_votes.len());
-        for (index, _) in unique_votes.iter().enumerate() {
-            if vote_indices[index].len() >= 451 {
-                passed = true;
-            } else {
-                passed = false;
-            }
-            //println!("Vote #{} (voted by {} computors ID) ", index, vote_indices[index].len());
-        }
-    //println!("Quorum Passed: {:?}", passed);
-    Ok(passed)
 }
 
-#[inline]
-fn compare_votes(a: &Tick, b: &Tick) -> bool {
-    a.epoch == b.epoch &&
-            a.year == b.year &&
-            a.month == b.month &&
-            a.day == b.day &&
-            a.hour == b.hour &&
-            a.minute == b.minute &&
-            a.second == b.second &&
-            a.millisecond == b.millisecond &&
-            a.prev_resource_testing_digest == b.prev_resource_testing_digest &&
-            a.prev_spectrum_digest.iter().zip(b.prev_spectrum_digest.iter()).all(|(a,b)| a == b) &&
-            a.prev_universe_digest.iter().zip(b.prev_universe_digest.iter()).all(|(a,b)| a == b) &&
-            a.prev_computer_digest.iter().zip(b.prev_computer_digest.iter()).all(|(a,b)| a == b) &&
-            a.prev_transaction_body_digest == b.prev_transaction_body_digest &&
-            a.transaction_digest.iter().zip(b.transaction_digest.iter()).all(|(a,b)| a == b) &&
-            a.expected_next_tick_transaction_digest.iter().zip(b.expected_next_tick_transaction_digest.iter()).all(|(a,b)| a == b)
+//K12 over the fields computors must agree on, leaving out who signed and their salted digests
+pub fn vote_digest(tick: &Tick) -> [u8; 32] {
+    let mut bytes = tick.as_bytes_without_signature();
+    bytes[0..2].fill(0);
+    bytes[20..24].fill(0);
+    bytes[28..32].fill(0);
+    bytes[128..224].fill(0);
+    <[u8; 32]>::try_from(k12_bytes(&bytes.to_vec())).unwrap()
 }
 
-fn get_unique_votes(votes: &Vec<Tick>, unique_votes: &mut Vec<Tick>, vote_indices: &mut Vec<Vec<i32>>) {
-    if votes.len() == 0 {
-        return;
+pub fn get_quorum_votes(bc: &BroadcastComputors, ticks: &Vec<Tick>) -> Result<QuorumReport, String> {
+    let first = ticks.first();
+    let mut report = QuorumReport {
+        tick: first.map(|tick| tick.tick).unwrap_or(0),
+        epoch: first.map(|tick| tick.epoch).unwrap_or(0),
+        required_votes: get_network_profile().quorum_minimum_votes,
+        vote_groups: Vec::new(),
+        invalid_signatures: Vec::new(),
+        missing_computors: Vec::new(),
+        winning_digest: None,
+        winning_vote: None,
+        passed: false
+    };
+    let mut voted: [bool; NUMBER_COMPUTORS] = [false; NUMBER_COMPUTORS];
+    let mut candidates: Vec<&Tick> = Vec::with_capacity(ticks.len());
+    let mut signing_digests: Vec<[u8; 32]> = Vec::with_capacity(ticks.len());
+    for vote in ticks.iter() {
+        if vote.tick != report.tick || vote.epoch != report.epoch {
+            return Err(format!("Quorum Response Mixes Tick {} Epoch {} With Tick {} Epoch {}", vote.tick, vote.epoch, report.tick, report.epoch));
+        }
+        let index: usize = vote.computor_index as usize;
+        if index >= NUMBER_COMPUTORS {
+            report.invalid_signatures.push(vote.computor_index);
+            continue;
+        }
+        //Repeats of the same signed vote are only checked once
+        if candidates.iter().any(|candidate| candidate.computor_index == vote.computor_index && candidate.signature == vote.signature) {
+            continue;
+        }
+        let mut tick = vote.clone();
+        tick.computor_index ^= TICK_TYPE as u16;
+        signing_digests.push(tick.hash());
+        candidates.push(vote);
     }
-    unique_votes.push(votes[0].clone());
-    vote_indices.resize(1, Vec::new());
-    vote_indices[0].push(votes[0].computor_index as i32);
-    for (i, _) in votes.iter().enumerate() {
-        if i == 0 {
+    //Up to 676 signatures, checked across all cores at once
+    let batch: Vec<SignedDigest> = candidates.iter().zip(signing_digests.iter()).map(|(vote, digest)| SignedDigest {
+        public_key: &bc.pub_keys[vote.computor_index as usize],
+        message_digest: *digest,
+        signature: &vote.signature
+    }).collect();
+    let verified: Vec<bool> = verify_batch(&batch);
+
+    let mut representatives: Vec<Tick> = Vec::new();
+    for (vote, valid) in candidates.into_iter().zip(verified) {
+        if !valid {
+            report.invalid_signatures.push(vote.computor_index);
             continue;
         }
-        let mut vote_index: i32 = -1;
-        for (j, _) in unique_votes.iter().enumerate() {
-            if compare_votes(&votes[i], &unique_votes[j]) {
-                vote_index = j as i32;
-                break;
-            }
+        //A computor's first valid vote is the one that counts
+        if voted[vote.computor_index as usize] {
+            continue;
         }
-        if vote_index != -1 {
-            vote_indices[vote_index as usize].push(votes[i].computor_index as i32);
-        } else {
-            unique_votes.push(votes[i].clone());
-            vote_indices.resize(vote_indices.len() + 1, Vec::new());
-            let m: usize = vote_indices.len() - 1;
-            vote_indices[m].push(votes[i].computor_index as i32);
+        voted[vote.computor_index as usize] = true;
+        let digest: String = bytes_to_hex(&vote_digest(vote).to_vec());
+        match report.vote_groups.iter_mut().position(|group| group.digest == digest) {
+            Some(position) => report.vote_groups[position].computors.push(vote.computor_index),
+            None => {
+                report.vote_groups.push(QuorumVoteGroup { digest, computors: vec![vote.computor_index] });
+                representatives.push(vote.clone());
+            }
         }
     }
+    report.missing_computors = (0..NUMBER_COMPUTORS).filter(|index| !voted[*index]).map(|index| index as u16).collect();
+
+    let mut groups: Vec<(QuorumVoteGroup, Tick)> = report.vote_groups.drain(..).zip(representatives).collect();
+    groups.sort_by(|(a, _), (b, _)| b.computors.len().cmp(&a.computors.len()));
+    if let Some((group, vote)) = groups.first() {
+        report.passed = group.computors.len() >= report.required_votes as usize;
+        report.winning_digest = Some(group.digest.clone());
+        report.winning_vote = Some(vote.clone());
+    }
+    report.vote_groups = groups.into_iter().map(|(group, _)| group).collect();
+    Ok(report)
 }
 
 mod test_quorum_votes {
     #![allow(dead_code, unused)]
     use crate::computor::BroadcastComputors;
     use crate::consensus_tests::epoch_163_computors;
-    use crate::quorum_votes::get_quorum_votes;
+    use crate::quorum_votes::{get_quorum_votes, QuorumVoteGroup};
     use crate::tick::Tick;
 
     #[test]
@@ -123,9 +155,41 @@ mod test_quorum_votes {
                         226, 32, 7, 43, 174, 90, 41, 163, 27, 137, 139, 254, 64, 135, 56, 117, 75,
                         147, 118, 31, 0];
         let t: Tick = Tick::new(&data);
+        let t_tick: u32 = t.tick;
         let mut v: Vec<Tick> = Vec::with_capacity(1);
         v.push(t);
-        let result = get_quorum_votes(&bc, &v).unwrap();
-        assert_eq!(result, false);
+        let report = get_quorum_votes(&bc, &v).unwrap();
+        assert_eq!(report.passed, false);
+        assert_eq!(report.tick, t_tick);
+        assert_eq!(report.required_votes, 451);
+        //The real vote from computor 480 verifies and is the only group
+        let digest: String = "196f30ebc21e35250d53fb26c0d8acb3624b7d3a0313e7d2ed7b9a75d0b6b5f7".to_string();
+        assert_eq!(report.vote_groups, vec![QuorumVoteGroup { digest: digest.clone(), computors: vec![480] }]);
+        assert!(report.invalid_signatures.is_empty());
+        assert_eq!(report.winning_digest, Some(digest.clone()));
+        assert_eq!(report.winning_vote.as_ref().unwrap().computor_index, 480);
+        assert_eq!(report.missing_computors.len(), 675);
+        assert!(!report.missing_computors.contains(&480));
+        //The same vote twice still counts once
+        v.push(v[0].clone());
+        let report = get_quorum_votes(&bc, &v).unwrap();
+        assert_eq!(report.vote_groups[0].computors, vec![480]);
+        //A copy with a broken signature is batch verified alongside the real one and reported by its index
+        let mut forged = v[0].clone();
+        forged.signature[0] ^= 1;
+        let report = get_quorum_votes(&bc, &vec![forged.clone(), v[0].clone()]).unwrap();
+        assert_eq!(report.invalid_signatures, vec![480]);
+        assert_eq!(report.vote_groups[0].computors, vec![480]);
+        assert_eq!(report.winning_digest, Some(digest));
+        let report = get_quorum_votes(&bc, &vec![forged]).unwrap();
+        assert_eq!(report.invalid_signatures, vec![480]);
+        assert!(report.vote_groups.is_empty());
+        assert_eq!(report.winning_digest, None);
+        assert_eq!(report.missing_computors.len(), 676);
+        //A vote for another tick can't be mixed in
+        let mut other = v[0].clone();
+        other.tick += 1;
+        v.push(other);
+        assert!(get_quorum_votes(&bc, &v).is_err());
     }
 }
\ No newline at end of file
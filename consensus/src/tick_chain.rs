@@ -0,0 +1,169 @@
+use crypto::hash::k12_bytes;
+use crate::tick::Tick;
+
+//The digests a computor's vote commits to. Each vote carries the state it started from (prev_*) and the state
+//it ended on salted with the computor's public key (salted_*), so the next tick's prev_* can be checked against it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickDigests {
+    pub computor_index: u16,
+    pub prev_resource_testing_digest: u32,
+    pub salted_resource_testing_digest: u32,
+    pub prev_transaction_body_digest: u32,
+    pub salted_transaction_body_digest: u32,
+    pub prev_spectrum_digest: [u8; 32],
+    pub prev_universe_digest: [u8; 32],
+    pub prev_computer_digest: [u8; 32],
+    pub salted_spectrum_digest: [u8; 32],
+    pub salted_universe_digest: [u8; 32],
+    pub salted_computer_digest: [u8; 32]
+}
+
+pub const TICK_DIGESTS_LEN: usize = 2 + 4 * 4 + 6 * 32;
+
+impl TickDigests {
+    pub fn from_tick(tick: &Tick) -> Self {
+        TickDigests {
+            computor_index: tick.computor_index,
+            prev_resource_testing_digest: tick.prev_resource_testing_digest,
+            salted_resource_testing_digest: tick.salted_resource_testing_digest,
+            prev_transaction_body_digest: tick.prev_transaction_body_digest,
+            salted_transaction_body_digest: tick.salted_transaction_body_digest,
+            prev_spectrum_digest: tick.prev_spectrum_digest,
+            prev_universe_digest: tick.prev_universe_digest,
+            prev_computer_digest: tick.prev_computer_digest,
+            salted_spectrum_digest: tick.salted_spectrum_digest,
+            salted_universe_digest: tick.salted_universe_digest,
+            salted_computer_digest: tick.salted_computer_digest
+        }
+    }
+
+    pub fn as_bytes(&self) -> [u8; TICK_DIGESTS_LEN] {
+        let mut bytes = [0u8; TICK_DIGESTS_LEN];
+        bytes[0..2].copy_from_slice(&self.computor_index.to_le_bytes());
+        bytes[2..6].copy_from_slice(&self.prev_resource_testing_digest.to_le_bytes());
+        bytes[6..10].copy_from_slice(&self.salted_resource_testing_digest.to_le_bytes());
+        bytes[10..14].copy_from_slice(&self.prev_transaction_body_digest.to_le_bytes());
+        bytes[14..18].copy_from_slice(&self.salted_transaction_body_digest.to_le_bytes());
+        bytes[18..50].copy_from_slice(&self.prev_spectrum_digest);
+        bytes[50..82].copy_from_slice(&self.prev_universe_digest);
+        bytes[82..114].copy_from_slice(&self.prev_computer_digest);
+        bytes[114..146].copy_from_slice(&self.salted_spectrum_digest);
+        bytes[146..178].copy_from_slice(&self.salted_universe_digest);
+        bytes[178..210].copy_from_slice(&self.salted_computer_digest);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != TICK_DIGESTS_LEN {
+            return Err(format!("Invalid Tick Digests Length {}, Expected {}", bytes.len(), TICK_DIGESTS_LEN));
+        }
+        let digest = |start: usize| -> [u8; 32] { bytes[start..start + 32].try_into().unwrap() };
+        let number = |start: usize| -> u32 { u32::from_le_bytes(bytes[start..start + 4].try_into().unwrap()) };
+        Ok(TickDigests {
+            computor_index: u16::from_le_bytes([bytes[0], bytes[1]]),
+            prev_resource_testing_digest: number(2),
+            salted_resource_testing_digest: number(6),
+            prev_transaction_body_digest: number(10),
+            salted_transaction_body_digest: number(14),
+            prev_spectrum_digest: digest(18),
+            prev_universe_digest: digest(50),
+            prev_computer_digest: digest(82),
+            salted_spectrum_digest: digest(114),
+            salted_universe_digest: digest(146),
+            salted_computer_digest: digest(178)
+        })
+    }
+}
+
+//K12 over the computor's public key followed by the digest
+pub fn salt_digest(pub_key: &[u8; 32], digest: &[u8; 32]) -> [u8; 32] {
+    let mut salted_data: Vec<u8> = Vec::with_capacity(64);
+    salted_data.extend_from_slice(pub_key);
+    salted_data.extend_from_slice(digest);
+    k12_bytes(&salted_data).as_slice().try_into().unwrap()
+}
+
+//Core hashes only the 4 digest bytes after the key, not a padded 32, and keeps 4 bytes of output
+pub fn salt_short_digest(pub_key: &[u8; 32], digest: u32) -> u32 {
+    let mut salted_data: Vec<u8> = Vec::with_capacity(36);
+    salted_data.extend_from_slice(pub_key);
+    salted_data.extend_from_slice(&digest.to_le_bytes());
+    let salted = k12_bytes(&salted_data);
+    u32::from_le_bytes([salted[0], salted[1], salted[2], salted[3]])
+}
+
+//Checks the state `next` starts from is the state the computor who signed `previous` ended on.
+//pub_key is that computor's key (previous.computor_index in the epoch's computor list).
+pub fn check_continuity(previous: &TickDigests, pub_key: &[u8; 32], next: &TickDigests) -> Result<(), String> {
+    if salt_short_digest(pub_key, next.prev_resource_testing_digest) != previous.salted_resource_testing_digest {
+        return Err("Resource Testing Digest Does Not Follow The Previous Tick".to_string());
+    }
+    if salt_short_digest(pub_key, next.prev_transaction_body_digest) != previous.salted_transaction_body_digest {
+        return Err("Transaction Body Digest Does Not Follow The Previous Tick".to_string());
+    }
+    if salt_digest(pub_key, &next.prev_spectrum_digest) != previous.salted_spectrum_digest {
+        return Err("Spectrum Digest Does Not Follow The Previous Tick".to_string());
+    }
+    if salt_digest(pub_key, &next.prev_universe_digest) != previous.salted_universe_digest {
+        return Err("Universe Digest Does Not Follow The Previous Tick".to_string());
+    }
+    if salt_digest(pub_key, &next.prev_computer_digest) != previous.salted_computer_digest {
+        return Err("Computer Digest Does Not Follow The Previous Tick".to_string());
+    }
+    Ok(())
+}
+
+
+#[test]
+fn check_digest_continuity_between_ticks() {
+    use crypto::encoding::from_hex_to_bytes;
+    use crate::computor::BroadcastComputors;
+    use crate::consensus_tests::epoch_163_computors;
+
+    //Known answers for computor 480 of epoch 163, so a change to either salting layout fails here.
+    //TODO: replace with prev_* of a real quorum tick N+1 against salted_* of tick N once such a pair is captured,
+    //only that proves the layout matches what computors sign.
+    let pub_key: [u8; 32] = BroadcastComputors::new(epoch_163_computors()).pub_keys[480];
+    let digest = |hex: &str| -> [u8; 32] { from_hex_to_bytes(hex).unwrap().try_into().unwrap() };
+    let next = TickDigests {
+        computor_index: 12,
+        prev_resource_testing_digest: 0xDEADBEEF,
+        salted_resource_testing_digest: 0,
+        prev_transaction_body_digest: 42,
+        salted_transaction_body_digest: 0,
+        prev_spectrum_digest: [1u8; 32],
+        prev_universe_digest: [2u8; 32],
+        prev_computer_digest: [3u8; 32],
+        salted_spectrum_digest: [0u8; 32],
+        salted_universe_digest: [0u8; 32],
+        salted_computer_digest: [0u8; 32]
+    };
+    let previous = TickDigests {
+        computor_index: 480,
+        prev_resource_testing_digest: 0,
+        salted_resource_testing_digest: 0x02b76370,
+        prev_transaction_body_digest: 0,
+        salted_transaction_body_digest: 0x41ee47b7,
+        prev_spectrum_digest: [0u8; 32],
+        prev_universe_digest: [0u8; 32],
+        prev_computer_digest: [0u8; 32],
+        salted_spectrum_digest: digest("681e5b641d8b3b5872501151a6682a963883d05b7a41acd91976491a8567f4b4"),
+        salted_universe_digest: digest("5e5ae3c442563687a2b19dd70beb95af81e3bb1224499a8135cb5492f865b415"),
+        salted_computer_digest: digest("ca1ed093dcf93089abfd1e14e8821856da02965616cf462f731c5b876833dd83")
+    };
+    assert!(check_continuity(&previous, &pub_key, &next).is_ok());
+    assert!(check_continuity(&previous, &[8u8; 32], &next).is_err());
+    //Salting a short digest as if it were padded to 32 bytes gives another value
+    let mut padded = [0u8; 32];
+    padded[0..4].copy_from_slice(&42u32.to_le_bytes());
+    let padded_salt = salt_digest(&pub_key, &padded);
+    assert_ne!(u32::from_le_bytes(padded_salt[0..4].try_into().unwrap()), previous.salted_transaction_body_digest);
+
+    let mut forked = next.clone();
+    forked.prev_universe_digest[31] ^= 1;
+    assert_eq!(check_continuity(&previous, &pub_key, &forked), Err("Universe Digest Does Not Follow The Previous Tick".to_string()));
+
+    assert_eq!(TickDigests::from_bytes(&previous.as_bytes()).unwrap(), previous);
+    assert!(TickDigests::from_bytes(&[0u8; 12]).is_err());
+}
+
@@ -1,5 +1,5 @@
 use crypto::hash::k12_bytes;
-use crypto::qubic_identities::{get_identity, verify};
+use crypto::qubic_identities::{get_identity, verify_batch, SignedDigest};
 use crate::computor::BroadcastComputors;
 
 pub type TransactionDigest = [u8; 32];
@@ -137,12 +137,80 @@ impl TickData {
         <[u8; 32]>::try_from(hash.as_slice()).unwrap()
     }
 
-    pub fn validate(&mut self, bc: &BroadcastComputors) -> bool {
+    //What the computor signed, its index is xored with the tick data message type first
+    pub fn signing_digest(&mut self) -> [u8; 32] {
         self.computor_index = self.computor_index ^ 8;
-        let _hash: [u8; 32] = self.hash();
+        let hash: [u8; 32] = self.hash();
         self.computor_index = self.computor_index ^ 8;
-        let hash: [u8; 32] = <[u8; 32]>::try_from(_hash.as_slice()).unwrap();
-        verify(&bc.pub_keys[self.computor_index as usize], &hash, &self.signature)
+        hash
+    }
+
+    pub fn validate(&mut self, bc: &BroadcastComputors) -> bool {
+        TickData::validate_batch(std::slice::from_mut(self), bc)[0]
+    }
+
+    //Checks each tick data's signature against its computor, in parallel
+    pub fn validate_batch(tick_data: &mut [TickData], bc: &BroadcastComputors) -> Vec<bool> {
+        let digests: Vec<[u8; 32]> = tick_data.iter_mut().map(|data| data.signing_digest()).collect();
+        let mut verified: Vec<bool> = vec![false; tick_data.len()];
+        let mut positions: Vec<usize> = Vec::with_capacity(tick_data.len());
+        let mut batch: Vec<SignedDigest> = Vec::with_capacity(tick_data.len());
+        for (position, data) in tick_data.iter().enumerate() {
+            if let Some(public_key) = bc.pub_keys.get(data.computor_index as usize) {
+                positions.push(position);
+                batch.push(SignedDigest { public_key, message_digest: digests[position], signature: &data.signature });
+            }
+        }
+        for (position, valid) in positions.into_iter().zip(verify_batch(&batch)) {
+            verified[position] = valid;
+        }
+        verified
+    }
+
+    //Wall clock time the tick leader stamped, UTC, in milliseconds since the unix epoch.
+    //None for empty tick data or fields that can't be a date.
+    pub fn unix_millis(&self) -> Option<u64> {
+        let year: i64 = 2000 + self.year as i64;
+        let (month, day) = (self.month as i64, self.day as i64);
+        let days_in_month: i64 = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None
+        };
+        if day < 1 || day > days_in_month || self.hour > 23 || self.minute > 59 || self.second > 59 || self.millisecond > 999 {
+            return None;
+        }
+        //Days from 1970-01-01, counting years from March so the leap day comes last
+        let shifted_year: i64 = if month <= 2 { year - 1 } else { year };
+        let era: i64 = shifted_year / 400;
+        let year_of_era: i64 = shifted_year - era * 400;
+        let day_of_year: i64 = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era: i64 = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days: i64 = era * 146097 + day_of_era - 719468;
+        let seconds: i64 = days * 86400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        Some(seconds as u64 * 1000 + self.millisecond as u64)
+    }
+
+    //The same time as sqlite's datetime text, e.g. 2025-05-30 19:52:35.000
+    pub fn timestamp(&self) -> Option<String> {
+        self.unix_millis().map(|_| format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+                                           2000 + self.year as u16, self.month, self.day, self.hour, self.minute, self.second, self.millisecond))
+    }
+
+    //Digests are packed from the front, unused slots are zero
+    pub fn transaction_count(&self) -> usize {
+        self.transaction_digests.iter().filter(|digest| digest.iter().any(|byte| *byte != 0)).count()
+    }
+
+    //(contract index, fee) for every contract charged a fee this tick
+    pub fn contract_fee_entries(&self) -> Vec<(u16, i64)> {
+        self.contract_fees.iter().enumerate().filter(|(_, fee)| **fee != 0).map(|(index, fee)| (index as u16, *fee)).collect()
+    }
+
+    pub fn total_contract_fees(&self) -> i64 {
+        self.contract_fees.iter().fold(0i64, |total, fee| total.saturating_add(*fee))
     }
 
     pub fn validate_vs_tick_tx_digests_hash(&mut self, hash: &String) -> bool {
@@ -184,5 +252,33 @@ mod tests_tick_data {
 
         let verified = tick_data.validate(&bc);
         assert_eq!(verified, true);
+
+        let mut tampered = tick_data.clone();
+        tampered.signature[0] ^= 1;
+        let mut out_of_range = tick_data.clone();
+        out_of_range.computor_index = 676;
+        let mut batch = vec![tick_data.clone(), tampered, out_of_range];
+        assert_eq!(TickData::validate_batch(&mut batch, &bc), vec![true, false, false]);
+
+        //Stamped 2025-05-30 19:52:35.000 UTC
+        assert_eq!(tick_data.timestamp().unwrap(), "2025-05-30 19:52:35.000");
+        assert_eq!(tick_data.unix_millis(), Some(1748634755000));
+        let mut empty = tick_data.clone();
+        empty.month = 0;
+        assert_eq!(empty.unix_millis(), None);
+        empty.month = 2;
+        empty.day = 29;
+        assert_eq!(empty.timestamp(), None);
+        empty.year = 24;
+        assert_eq!(empty.timestamp().unwrap(), "2024-02-29 19:52:35.000");
+
+        assert!(tick_data.transaction_count() <= 1024);
+        assert_eq!(tick_data.transaction_count(), tick_data.transaction_digests.iter().take_while(|digest| **digest != [0u8; 32]).count());
+        let mut fees = tick_data.clone();
+        fees.contract_fees = [0; 1024];
+        fees.contract_fees[1] = 1000;
+        fees.contract_fees[4] = 250;
+        assert_eq!(fees.contract_fee_entries(), vec![(1, 1000), (4, 250)]);
+        assert_eq!(fees.total_contract_fees(), 1250);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,57 @@
+//Times quorum sized signature checks, one at a time against verify_batch. Run with `cargo bench -p crypto`.
+use std::time::{Duration, Instant};
+use crypto::hash::k12_bytes;
+use crypto::qubic_identities::{get_private_key, get_public_key, get_subseed, sign_raw, verify, verify_all, verify_batch, SignedDigest};
+
+const ROUNDS: u32 = 5;
+
+fn seed(index: usize) -> String {
+    let mut seed: Vec<u8> = vec![b'a'; 55];
+    let mut value: usize = index;
+    for byte in seed.iter_mut().rev().take(4) {
+        *byte = b'a' + (value % 26) as u8;
+        value /= 26;
+    }
+    String::from_utf8(seed).unwrap()
+}
+
+fn time(rounds: u32, mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        f();
+    }
+    start.elapsed() / rounds
+}
+
+fn main() {
+    let mut keys: Vec<[u8; 32]> = Vec::with_capacity(676);
+    let mut digests: Vec<[u8; 32]> = Vec::with_capacity(676);
+    let mut signatures: Vec<[u8; 64]> = Vec::with_capacity(676);
+    for index in 0..676 {
+        let subseed = get_subseed(seed(index).as_str()).unwrap();
+        let public_key = get_public_key(&get_private_key(&subseed));
+        let digest: [u8; 32] = k12_bytes(&(index as u32).to_le_bytes().to_vec()).try_into().unwrap();
+        signatures.push(sign_raw(&subseed, &public_key, digest));
+        keys.push(public_key);
+        digests.push(digest);
+    }
+
+    for votes in [451usize, 676] {
+        let batch: Vec<SignedDigest> = (0..votes).map(|index| SignedDigest {
+            public_key: &keys[index],
+            message_digest: digests[index],
+            signature: &signatures[index]
+        }).collect();
+        let sequential = time(ROUNDS, || {
+            assert!((0..votes).all(|index| verify(&keys[index], &digests[index], &signatures[index])));
+        });
+        let batched = time(ROUNDS, || {
+            assert!(verify_batch(&batch).iter().all(|verified| *verified));
+        });
+        let all = time(ROUNDS, || {
+            assert!(verify_all(&batch));
+        });
+        println!("{} signatures: sequential {:?}, verify_batch {:?}, verify_all {:?} ({:.1}x)",
+                 votes, sequential, batched, all, sequential.as_secs_f64() / batched.as_secs_f64());
+    }
+}
@@ -1,70 +1,232 @@
-use argon2::Error::SaltTooLong;
 use base64::{Engine as _, engine::general_purpose};
 use crate::hash;
 use sha2::Sha256;
 use pbkdf2::pbkdf2_hmac;
+use argon2::{Algorithm, Argon2, Params, Version};
 use chacha20poly1305::{
     aead::{Aead, AeadCore, KeyInit},
     ChaCha20Poly1305, Nonce
 };
 use logger::error;
 use crate::random::random_bytes;
+use crate::secret::Secret;
 
 pub const SALTLENGTH: usize = 32;
 pub const NONCELENGTH: usize = 12;
 
-pub fn encrypt(plaintext: &str, password: &str) -> Option<([u8; SALTLENGTH + NONCELENGTH], Vec<u8>)> {
-    let salt: [u8; SALTLENGTH] = random_bytes(SALTLENGTH as u32).as_slice().try_into().unwrap();
-    let nonce_slice: [u8; NONCELENGTH] = random_bytes(NONCELENGTH as u32).as_slice().try_into().unwrap();
+//Versioned Envelope Stored Ahead Of Each Ciphertext
+// [version: 1][algorithm: 1][kdf: 1][kdf params: 12][salt: 32][nonce: 12]
+pub const ENVELOPE_VERSION: u8 = 1;
+pub const ENVELOPE_HEADER_LENGTH: usize = 3 + 12 + SALTLENGTH + NONCELENGTH;
+//Blobs written before the envelope existed are a bare salt || nonce
+pub const LEGACY_HEADER_LENGTH: usize = SALTLENGTH + NONCELENGTH;
+pub const LEGACY_PBKDF2_ITERATIONS: u32 = 600_000;
 
-    //pbkdf2 Key Derivation From Salt
-    // number of iterations
-    let n = 600_000;
-    let mut key: [u8; SALTLENGTH] = [0u8; SALTLENGTH];
-    pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, n, &mut key);
+pub const ALGORITHM_CHACHA20POLY1305: u8 = 1;
+pub const KDF_PBKDF2_SHA256: u8 = 1;
+pub const KDF_ARGON2ID: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kdf {
+    Pbkdf2Sha256 { iterations: u32 },
+    Argon2id { m_cost: u32, t_cost: u32, p_cost: u32 }
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Kdf::Argon2id {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST
+        }
+    }
+}
+
+impl Kdf {
+    pub fn id(&self) -> u8 {
+        match self {
+            Kdf::Pbkdf2Sha256 { .. } => KDF_PBKDF2_SHA256,
+            Kdf::Argon2id { .. } => KDF_ARGON2ID
+        }
+    }
+
+    fn params_as_bytes(&self) -> [u8; 12] {
+        let (a, b, c) = match self {
+            Kdf::Pbkdf2Sha256 { iterations } => (*iterations, 0, 0),
+            Kdf::Argon2id { m_cost, t_cost, p_cost } => (*m_cost, *t_cost, *p_cost)
+        };
+        let mut bytes: [u8; 12] = [0; 12];
+        bytes[0..4].copy_from_slice(&a.to_le_bytes());
+        bytes[4..8].copy_from_slice(&b.to_le_bytes());
+        bytes[8..12].copy_from_slice(&c.to_le_bytes());
+        bytes
+    }
+
+    fn from_id_and_params(id: u8, params: &[u8]) -> Result<Self, String> {
+        let a = u32::from_le_bytes(params[0..4].try_into().unwrap());
+        let b = u32::from_le_bytes(params[4..8].try_into().unwrap());
+        let c = u32::from_le_bytes(params[8..12].try_into().unwrap());
+        match id {
+            KDF_PBKDF2_SHA256 => Ok(Kdf::Pbkdf2Sha256 { iterations: a }),
+            KDF_ARGON2ID => Ok(Kdf::Argon2id { m_cost: a, t_cost: b, p_cost: c }),
+            _ => Err(format!("Unknown Kdf Id {}", id))
+        }
+    }
+
+    pub fn derive_key(&self, password: &str, salt: &[u8]) -> Result<Secret, String> {
+        let mut key = Secret::zeroed(32);
+        match self {
+            Kdf::Pbkdf2Sha256 { iterations } => {
+                pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, *iterations, key.expose_mut());
+                Ok(key)
+            },
+            Kdf::Argon2id { m_cost, t_cost, p_cost } => {
+                let params = match Params::new(*m_cost, *t_cost, *p_cost, Some(key.len())) {
+                    Ok(params) => params,
+                    Err(err) => return Err(format!("Invalid Argon2id Parameters: {}", err))
+                };
+                match Argon2::new(Algorithm::Argon2id, Version::V0x13, params).hash_password_into(password.as_bytes(), salt, key.expose_mut()) {
+                    Ok(_) => Ok(key),
+                    Err(err) => Err(format!("Failed To Derive Key: {}", err))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptionHeader {
+    pub version: u8,
+    pub algorithm: u8,
+    pub kdf: Kdf,
+    pub salt: [u8; SALTLENGTH],
+    pub nonce: [u8; NONCELENGTH]
+}
+
+impl EncryptionHeader {
+    pub fn new(kdf: Kdf) -> Self {
+        EncryptionHeader {
+            version: ENVELOPE_VERSION,
+            algorithm: ALGORITHM_CHACHA20POLY1305,
+            kdf,
+            salt: random_bytes(SALTLENGTH as u32).as_slice().try_into().unwrap(),
+            nonce: random_bytes(NONCELENGTH as u32).as_slice().try_into().unwrap()
+        }
+    }
+
+    pub fn is_legacy(&self) -> bool { self.version == 0 }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        if self.is_legacy() {
+            let mut bytes: Vec<u8> = Vec::with_capacity(LEGACY_HEADER_LENGTH);
+            bytes.extend_from_slice(&self.salt);
+            bytes.extend_from_slice(&self.nonce);
+            return bytes;
+        }
+        let mut bytes: Vec<u8> = Vec::with_capacity(ENVELOPE_HEADER_LENGTH);
+        bytes.push(self.version);
+        bytes.push(self.algorithm);
+        bytes.push(self.kdf.id());
+        bytes.extend_from_slice(&self.kdf.params_as_bytes());
+        bytes.extend_from_slice(&self.salt);
+        bytes.extend_from_slice(&self.nonce);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        match bytes.len() {
+            LEGACY_HEADER_LENGTH => {
+                Ok(EncryptionHeader {
+                    version: 0,
+                    algorithm: ALGORITHM_CHACHA20POLY1305,
+                    kdf: Kdf::Pbkdf2Sha256 { iterations: LEGACY_PBKDF2_ITERATIONS },
+                    salt: bytes[0..SALTLENGTH].try_into().unwrap(),
+                    nonce: bytes[SALTLENGTH..LEGACY_HEADER_LENGTH].try_into().unwrap()
+                })
+            },
+            ENVELOPE_HEADER_LENGTH => {
+                if bytes[0] != ENVELOPE_VERSION {
+                    return Err(format!("Unsupported Envelope Version {}", bytes[0]));
+                }
+                if bytes[1] != ALGORITHM_CHACHA20POLY1305 {
+                    return Err(format!("Unsupported Encryption Algorithm {}", bytes[1]));
+                }
+                let kdf = Kdf::from_id_and_params(bytes[2], &bytes[3..15])?;
+                Ok(EncryptionHeader {
+                    version: bytes[0],
+                    algorithm: bytes[1],
+                    kdf,
+                    salt: bytes[15..15 + SALTLENGTH].try_into().unwrap(),
+                    nonce: bytes[15 + SALTLENGTH..ENVELOPE_HEADER_LENGTH].try_into().unwrap()
+                })
+            },
+            _ => Err("Invalid Encryption Header Length".to_string())
+        }
+    }
+}
+
+//True if the header predates the envelope or uses different kdf parameters than requested
+pub fn needs_upgrade(header_bytes: &[u8], kdf: &Kdf) -> bool {
+    match EncryptionHeader::from_bytes(header_bytes) {
+        Ok(header) => header.is_legacy() || header.kdf != *kdf,
+        Err(_) => false
+    }
+}
+
+pub fn encrypt(plaintext: &str, password: &str) -> Option<(Vec<u8>, Vec<u8>)> {
+    encrypt_with_kdf(plaintext, password, &Kdf::default())
+}
+
+pub fn encrypt_with_kdf(plaintext: &str, password: &str, kdf: &Kdf) -> Option<(Vec<u8>, Vec<u8>)> {
+    let header = EncryptionHeader::new(*kdf);
+    let key = match kdf.derive_key(password, &header.salt) {
+        Ok(key) => key,
+        Err(err) => {
+            error!("Error Encrypting! {}", err);
+            return None;
+        }
+    };
 
     let cipher = ChaCha20Poly1305::new_from_slice(&key).unwrap();
-    let nonce: Nonce = Nonce::from(nonce_slice.clone());
-    let ciphertext = cipher.encrypt(&nonce, plaintext.as_bytes()).unwrap();
-    
-    let s: [u8; SALTLENGTH] = <[u8; SALTLENGTH]>::try_from(salt.as_ref()).unwrap();
-    let n: [u8; NONCELENGTH] = <[u8; NONCELENGTH]>::try_from(nonce_slice.as_ref()).unwrap();
-
-    let mut s_n: [u8; SALTLENGTH + NONCELENGTH] = [0u8; SALTLENGTH + NONCELENGTH];
-    s_n[0..SALTLENGTH].copy_from_slice(&s);
-    s_n[SALTLENGTH..(SALTLENGTH + NONCELENGTH)].copy_from_slice(&n);
-
-    let mut ret_val: Vec<u8> = Vec::with_capacity(ciphertext.len());
-    ret_val.resize(ciphertext.len(), 0);
-    ret_val.copy_from_slice(ciphertext.as_slice());
-    Some((s_n, ret_val))
+    let nonce: Nonce = Nonce::from(header.nonce);
+    match cipher.encrypt(&nonce, plaintext.as_bytes()) {
+        Ok(ciphertext) => Some((header.as_bytes(), ciphertext)),
+        Err(_) => {
+            error!("Error Encrypting!");
+            None
+        }
+    }
 }
 
 
-pub fn decrypt(salt_bytes: &[u8; SALTLENGTH + NONCELENGTH], encrypted: &Vec<u8>, password: &str) -> Result<String, ()> {
+pub fn decrypt(header_bytes: &[u8], encrypted: &Vec<u8>, password: &str) -> Result<Secret, ()> {
     if encrypted.len() < 1 {
         return Err(());
     }
-    let mut salt: [u8; SALTLENGTH] = [0; SALTLENGTH];
-    salt.clone_from_slice((&salt_bytes[0..SALTLENGTH]));
-
-    let mut nonce_slice: [u8; NONCELENGTH] = [0; NONCELENGTH];
-    nonce_slice.clone_from_slice((&salt_bytes[SALTLENGTH..SALTLENGTH + NONCELENGTH]));
-    
+    let header = match EncryptionHeader::from_bytes(header_bytes) {
+        Ok(header) => header,
+        Err(err) => {
+            error!("Error Decrypting! {}", err);
+            return Err(());
+        }
+    };
     let ct: &[u8] = &encrypted;
 
-    //pbkdf2 Key Derivation From Salt
-    // number of iterations
-    let n = 600_000;
-    let mut key: [u8; SALTLENGTH] = [0u8; SALTLENGTH];
-    pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, n, &mut key);
+    let key = match header.kdf.derive_key(password, &header.salt) {
+        Ok(key) => key,
+        Err(err) => {
+            error!("Error Decrypting! {}", err);
+            return Err(());
+        }
+    };
 
     let cipher = ChaCha20Poly1305::new_from_slice(&key).unwrap();
-    let nonce: Nonce = Nonce::from(nonce_slice.clone());
+    let nonce: Nonce = Nonce::from(header.nonce);
     match cipher.decrypt(&nonce, ct) {
         Ok(decrypted) => {
-            match std::str::from_utf8(decrypted.as_slice()) {
-                Ok(decrypted_text) => Ok(decrypted_text.to_string()),
+            let decrypted = Secret::from(decrypted);
+            match std::str::from_utf8(&decrypted) {
+                Ok(_) => Ok(decrypted),
                 Err(_) => Err(()),
             }
         },
@@ -78,12 +240,12 @@ pub fn decrypt(salt_bytes: &[u8; SALTLENGTH + NONCELENGTH], encrypted: &Vec<u8>,
 
 #[cfg(test)]
 pub mod encryption_tests {
-    use crate::encryption::{decrypt, encrypt, NONCELENGTH, SALTLENGTH};
+    use crate::encryption::{decrypt, encrypt, encrypt_with_kdf, needs_upgrade, EncryptionHeader, Kdf, ENVELOPE_HEADER_LENGTH, ENVELOPE_VERSION, LEGACY_PBKDF2_ITERATIONS};
 
     #[test]
     fn encrypt_a_plaintext() {
         let (salt, encrypted) = encrypt("hello", "thisisalongenoughkeytoencryptavalue").unwrap();
-        assert_eq!(salt.len(), SALTLENGTH + NONCELENGTH);
+        assert_eq!(salt.len(), ENVELOPE_HEADER_LENGTH);
         assert_ne!(encrypted.len(), "hello".len());
     }
 
@@ -101,8 +263,45 @@ pub mod encryption_tests {
             }
         }
     }
-    
- 
-}
 
- 
\ No newline at end of file
+    #[test]
+    fn encrypt_writes_a_versioned_argon2id_header() {
+        let (salt, _) = encrypt("hello", "thisisalongenoughkeytoencryptavalue").unwrap();
+        let header = EncryptionHeader::from_bytes(&salt).unwrap();
+        assert_eq!(header.version, ENVELOPE_VERSION);
+        assert_eq!(header.kdf, Kdf::default());
+        assert_eq!(header.as_bytes(), salt);
+        assert_eq!(needs_upgrade(&salt, &Kdf::default()), false);
+    }
+
+    #[test]
+    fn encrypt_and_decrypt_with_custom_kdf_params() {
+        let kdf = Kdf::Argon2id { m_cost: 8192, t_cost: 1, p_cost: 1 };
+        let (salt, encrypted) = encrypt_with_kdf("hello", "thisisalongenoughkeytoencryptavalue", &kdf).unwrap();
+        assert_eq!(EncryptionHeader::from_bytes(&salt).unwrap().kdf, kdf);
+        assert_eq!(decrypt(&salt, &encrypted, "thisisalongenoughkeytoencryptavalue").unwrap().as_str(), "hello");
+        assert!(decrypt(&salt, &encrypted, "wrongpassword").is_err());
+        assert_eq!(needs_upgrade(&salt, &Kdf::default()), true);
+    }
+
+    #[test]
+    fn decrypt_a_legacy_pbkdf2_blob() {
+        //Legacy blobs are a bare salt || nonce with 600k rounds of pbkdf2
+        let kdf = Kdf::Pbkdf2Sha256 { iterations: LEGACY_PBKDF2_ITERATIONS };
+        let (salt, encrypted) = encrypt_with_kdf("hello", "thisisalongenoughkeytoencryptavalue", &kdf).unwrap();
+        let legacy_salt: Vec<u8> = salt[15..].to_vec();
+        assert_eq!(legacy_salt.len(), 44);
+        assert!(EncryptionHeader::from_bytes(&legacy_salt).unwrap().is_legacy());
+        assert_eq!(needs_upgrade(&legacy_salt, &Kdf::default()), true);
+        assert_eq!(decrypt(&legacy_salt, &encrypted, "thisisalongenoughkeytoencryptavalue").unwrap().as_str(), "hello");
+    }
+
+    #[test]
+    fn reject_an_unknown_header() {
+        let (mut salt, encrypted) = encrypt("hello", "thisisalongenoughkeytoencryptavalue").unwrap();
+        salt[0] = 99;
+        assert!(EncryptionHeader::from_bytes(&salt).is_err());
+        assert!(decrypt(&salt, &encrypted, "thisisalongenoughkeytoencryptavalue").is_err());
+        assert!(EncryptionHeader::from_bytes(&salt[1..]).is_err());
+    }
+}
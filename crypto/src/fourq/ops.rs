@@ -278,21 +278,34 @@ pub fn fp2addsub1271(mut a: F2elmT, b: F2elmT, c: &mut F2elmT) {
 }
 
 /// Table lookup to extract a point represented as (x+y,y-x,2t) corresponding to extended twisted Edwards coordinates (X:Y:Z:T) with Z=1
+/// Every point of the digit's 16 point block is read and selected with masks, the digits are secret when signing
 #[inline]
 pub fn table_lookup_fixed_base(p: &mut PointPrecomp, digit: u64, sign: u64) {
-    unsafe {
-        let digit = digit as isize;
-        if sign != 0 {
-            p.xy.copy_from_slice(&(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).yx);
-            p.yx.copy_from_slice(&(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).xy);
-            p.t2[0][0] = !(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).t2[0][0];
-            p.t2[0][1] = 0x7FFFFFFFFFFFFFFF - (*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).t2[0][1];
-            p.t2[1][0] = !(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).t2[1][0];
-            p.t2[1][1] = 0x7FFFFFFFFFFFFFFF - (*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).t2[1][1];
-        } else {
-            p.xy.copy_from_slice(&(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).xy);
-            p.yx.copy_from_slice(&(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).yx);
-            p.t2.copy_from_slice(&(*(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp).offset(digit)).t2);
+    let table: &[PointPrecomp] = unsafe { core::slice::from_raw_parts(FIXED_BASE_TABLE.as_ptr() as *const PointPrecomp, FIXED_BASE_TABLE.len() / 12) };
+    let block = (digit & !15) as usize;
+    let mut point = PointPrecomp::default();
+
+    for (i, candidate) in table[block..block + 16].iter().enumerate() {
+        // mask = all ones when i == digit within the block, zero otherwise
+        let mask = ((((i as u64) ^ (digit & 15)).wrapping_sub(1)) >> 63).wrapping_neg();
+        for j in 0..2 {
+            for l in 0..2 {
+                point.xy[j][l] |= candidate.xy[j][l] & mask;
+                point.yx[j][l] |= candidate.yx[j][l] & mask;
+                point.t2[j][l] |= candidate.t2[j][l] & mask;
+            }
+        }
+    }
+
+    // A negative digit swaps x+y with y-x and negates 2t
+    let mask = 0u64.wrapping_sub((sign != 0) as u64);
+    let mut negated = point.t2;
+    fp2neg1271(&mut negated);
+    for j in 0..2 {
+        for l in 0..2 {
+            p.xy[j][l] = (point.xy[j][l] & !mask) | (point.yx[j][l] & mask);
+            p.yx[j][l] = (point.yx[j][l] & !mask) | (point.xy[j][l] & mask);
+            p.t2[j][l] = (point.t2[j][l] & !mask) | (negated[j][l] & mask);
         }
     }
 }
@@ -331,7 +344,7 @@ pub fn montgomery_multiply_mod_order(ma: &[u64], mb: &[u64], mc: &mut [u64]) {
     let mut temp = [0u64; 8];
 
     unsafe {
-        if mb[0] == 1 && !mb[1] != 0 && !mb[2] != 0 && !mb[3] != 0 {
+        if mb[0] == 1 && mb[1] == 0 && mb[2] == 0 && mb[3] == 0 {
             copy_nonoverlapping(ma.as_ptr(), p.as_mut_ptr(), 4);
         } else {
             multiply(ma, mb, &mut p);
@@ -506,7 +519,7 @@ pub fn ecc_point_validate(p: &PointExtproj) -> bool {
     fp2add1271(t2, t1, &mut t2);                                 // 1 + dx^2*y^2
     fp2sub1271(t3, t2, &mut t1);                                 // -x^2 + y^2 - 1 - dx^2*y^2
 
-    ((t1[0][0] | t1[0][1]) == 0 || ((t1[0][0] + 1) | (t1[0][1] + 1)) == 0) && ((t1[1][0] | t1[1][1]) == 0|| ((t1[1][0] + 1) | (t1[1][1] + 1)) == 0)
+    ((t1[0][0] | t1[0][1]) == 0 || (t1[0][0].wrapping_add(1) | t1[0][1].wrapping_add(1)) == 0) && ((t1[1][0] | t1[1][1]) == 0|| (t1[1][0].wrapping_add(1) | t1[1][1].wrapping_add(1)) == 0)
 }
 
 /// Mixed point addition P = P+Q or P = P+P
@@ -574,12 +587,10 @@ pub fn ecc_mul_fixed(k: &[u64], q: &mut PointAffine) {
 
             let temp = (0u64.wrapping_sub(digits[i - (i / 50) * 50])) & digits[i];
 
-            scalar[0] += temp;
-            let mut carry = if scalar[0] != 0 { 0 } else { temp & 1};
-            scalar[1] += carry;
-            carry = if scalar[1] != 0 { 0 } else { carry & 1 };
-            scalar[2] += carry;
-            scalar[3] += if scalar[2] != 0 { 0 } else { carry & 1 };
+            let mut carry = addcarry_u64(0, scalar[0], temp, &mut scalar[0]);
+            carry = addcarry_u64(carry, scalar[1], 0, &mut scalar[1]);
+            carry = addcarry_u64(carry, scalar[2], 0, &mut scalar[2]);
+            addcarry_u64(carry, scalar[3], 0, &mut scalar[3]);
         }
 
         let mut r = PointExtproj::default();
@@ -894,13 +905,18 @@ pub fn mul_truncate(s: &[u64], c: &[u64]) -> u64 {
     low11 = _umul128(s[1], c[1], &mut high11);
     t9 = addcarry_u64(addcarry_u64(0, t7, low11, &mut t0), t8, high11, &mut t10) as u64;
     low03 = _umul128(s[0], c[3], &mut high03);
-    addcarry_u64(addcarry_u64(0, t10, low03, &mut t11), t6 + t9, high03, &mut t12);
+    addcarry_u64(addcarry_u64(0, t10, low03, &mut t11), t6.wrapping_add(t9), high03, &mut t12);
     low30 = _umul128(s[3], c[0], &mut high30);
     addcarry_u64(addcarry_u64(0, t11, low30, &mut t13), t12, high30, &mut t14);
     low12 = _umul128(s[1], c[2], &mut high12);
     addcarry_u64(addcarry_u64(0, t13, low12, &mut t15), t14, high12, &mut t16);
 
-    addcarry_u64(0, t15, _umul128(s[2], c[1], &mut high21), &mut t0) as u64 + t16 + high21 + s[1] * c[3] + s[2] * c[2] + s[3] * c[1]
+    (addcarry_u64(0, t15, _umul128(s[2], c[1], &mut high21), &mut t0) as u64)
+        .wrapping_add(t16)
+        .wrapping_add(high21)
+        .wrapping_add(s[1].wrapping_mul(c[3]))
+        .wrapping_add(s[2].wrapping_mul(c[2]))
+        .wrapping_add(s[3].wrapping_mul(c[1]))
 }
 
 
@@ -912,16 +928,17 @@ pub fn decompose(k: &[u64], scalars: &mut [u64]) {
     let a3 = mul_truncate(k, &ELL_3);
     let a4 = mul_truncate(k, &ELL_4);
 
-    scalars[0] = a1 * B11 + a2 * B21 + a3 * B31 + a4 * B41 + C1 + k[0];
-    scalars[1] = a1 * B12 + a2 * B22 + a3 * B32 + a4 * B42 + C2;
-    scalars[2] = a1 * B13 + a2 * B23 + a3 * B33 + a4 * B43 + C3;
-    scalars[3] = a1 * B14 + a2 * B24 + a3 * B34 + a4 * B44 + C4;
+    //Mod 2^64, the reference relies on unsigned wraparound here
+    scalars[0] = a1.wrapping_mul(B11).wrapping_add(a2.wrapping_mul(B21)).wrapping_add(a3.wrapping_mul(B31)).wrapping_add(a4.wrapping_mul(B41)).wrapping_add(C1).wrapping_add(k[0]);
+    scalars[1] = a1.wrapping_mul(B12).wrapping_add(a2.wrapping_mul(B22)).wrapping_add(a3.wrapping_mul(B32)).wrapping_add(a4.wrapping_mul(B42)).wrapping_add(C2);
+    scalars[2] = a1.wrapping_mul(B13).wrapping_add(a2.wrapping_mul(B23)).wrapping_add(a3.wrapping_mul(B33)).wrapping_add(a4.wrapping_mul(B43)).wrapping_add(C3);
+    scalars[3] = a1.wrapping_mul(B14).wrapping_add(a2.wrapping_mul(B24)).wrapping_add(a3.wrapping_mul(B34)).wrapping_add(a4.wrapping_mul(B44)).wrapping_add(C4);
 
     if scalars[0] & 1 == 0 {
-        scalars[0] -= B41;
-        scalars[1] -= B42;
-        scalars[2] -= B43;
-        scalars[3] -= B44;
+        scalars[0] = scalars[0].wrapping_sub(B41);
+        scalars[1] = scalars[1].wrapping_sub(B42);
+        scalars[2] = scalars[2].wrapping_sub(B43);
+        scalars[3] = scalars[3].wrapping_sub(B44);
     }
 }
 
@@ -1288,7 +1305,7 @@ pub fn decode(pencoded: &[u8], p: &mut PointAffine) -> bool {
         fpmul1271(t0, t1, &mut t1);                          // t1 = t0*x0^2
 
         let mut temp = [0u64; 2];
-        let mask = 0 - (1 & p.x[0][0]);
+        let mask = 0u64.wrapping_sub(1 & p.x[0][0]);
         addcarry_u64(addcarry_u64(0, p.x[0][0], mask, &mut temp[0]), p.x[0][1], mask >> 1, &mut temp[1]);
         p.x[0][0] = __shiftright128(temp[0], temp[1], 1);
         p.x[0][1] = temp[1] >> 1;
@@ -1330,4 +1347,168 @@ pub fn decode(pencoded: &[u8], p: &mut PointAffine) -> bool {
     }
 
     true
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub mod fourq_ops_tests {
+    use rand::prelude::*;
+    use crate::fourq::consts::{CURVE_ORDER, MONTGOMERY_R_PRIME, ONE};
+    use crate::fourq::ops::{addcarry_u64, decode, ecc_mul, ecc_mul_double, ecc_mul_fixed, encode, montgomery_multiply_mod_order, subborrow_u64};
+    use crate::fourq::types::PointAffine;
+
+    const ROUNDS: usize = 64;
+
+    //FourQ generator from the reference implementation
+    const GENERATOR_X: [[u64; 2]; 2] = [[0x286592AD7B3833AA, 0x1A3472237C2FB305], [0x96869FB360AC77F6, 0x1E1F553F2878AA9C]];
+    const GENERATOR_Y: [[u64; 2]; 2] = [[0xB924A2462BCBB287, 0x0E3FEE9BA120785A], [0x49A7C344844C8B5C, 0x6E1C4AF8630E0242]];
+
+    //Below 2^244, so the sum of two is still below the curve order
+    fn random_scalar(rng: &mut StdRng) -> [u64; 4] {
+        let mut scalar: [u64; 4] = rng.random();
+        scalar[3] &= 0x000FFFFFFFFFFFFF;
+        scalar
+    }
+
+    fn add(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+        let mut c = [0u64; 4];
+        let mut carry = addcarry_u64(0, a[0], b[0], &mut c[0]);
+        carry = addcarry_u64(carry, a[1], b[1], &mut c[1]);
+        carry = addcarry_u64(carry, a[2], b[2], &mut c[2]);
+        addcarry_u64(carry, a[3], b[3], &mut c[3]);
+        c
+    }
+
+    fn to_montgomery(a: &[u64; 4]) -> [u64; 4] {
+        let mut c = [0u64; 4];
+        montgomery_multiply_mod_order(a, &MONTGOMERY_R_PRIME, &mut c);
+        c
+    }
+
+    fn from_montgomery(a: &[u64; 4]) -> [u64; 4] {
+        let mut c = [0u64; 4];
+        montgomery_multiply_mod_order(a, &ONE, &mut c);
+        c
+    }
+
+    fn mul_fixed(k: &[u64; 4]) -> PointAffine {
+        let mut p = PointAffine::default();
+        ecc_mul_fixed(k, &mut p);
+        p
+    }
+
+    fn encoded(p: &PointAffine) -> [u8; 32] {
+        let mut p = *p;
+        let mut bytes = [0u8; 32];
+        encode(&mut p, &mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn multiply_the_generator_by_known_scalars() {
+        let g = mul_fixed(&ONE);
+        assert_eq!(g.x, GENERATOR_X);
+        assert_eq!(g.y, GENERATOR_Y);
+
+        //(N - 1)G = -G, same y and x negated mod 2^127 - 1
+        let mut n_minus_one = CURVE_ORDER;
+        n_minus_one[0] -= 1;
+        let minus_g = mul_fixed(&n_minus_one);
+        assert_eq!(minus_g.y, GENERATOR_Y);
+        for i in 0..2 {
+            assert_eq!(minus_g.x[i], [!GENERATOR_X[i][0], 0x7FFFFFFFFFFFFFFF - GENERATOR_X[i][1]]);
+        }
+
+        //NG is the neutral point (0, 1)
+        let neutral = mul_fixed(&CURVE_ORDER);
+        assert_eq!(neutral.x, [[0, 0], [0, 0]]);
+        assert_eq!(neutral.y, [[1, 0], [0, 0]]);
+
+        let mut decoded = PointAffine::default();
+        assert!(decode(&encoded(&g), &mut decoded));
+        assert_eq!(decoded.x, GENERATOR_X);
+        assert_eq!(decoded.y, GENERATOR_Y);
+    }
+
+    #[test]
+    fn montgomery_multiplication_mod_order() {
+        let mut rng = StdRng::seed_from_u64(47);
+        for _ in 0..ROUNDS {
+            let a = random_scalar(&mut rng);
+            let b = random_scalar(&mut rng);
+            let c = random_scalar(&mut rng);
+            assert_eq!(from_montgomery(&to_montgomery(&a)), a);
+
+            let (mut ab, mut ba) = ([0u64; 4], [0u64; 4]);
+            montgomery_multiply_mod_order(&a, &b, &mut ab);
+            montgomery_multiply_mod_order(&b, &a, &mut ba);
+            assert_eq!(ab, ba);
+
+            let (mut ab_c, mut a_bc, mut bc) = ([0u64; 4], [0u64; 4], [0u64; 4]);
+            montgomery_multiply_mod_order(&ab, &c, &mut ab_c);
+            montgomery_multiply_mod_order(&b, &c, &mut bc);
+            montgomery_multiply_mod_order(&a, &bc, &mut a_bc);
+            assert_eq!(ab_c, a_bc);
+
+            //Only exactly one skips the multiplication, not any value with a low limb of 1
+            let one_low = [1, b[1] | 1, b[2], b[3]];
+            let (mut a_one_low, mut one_low_a) = ([0u64; 4], [0u64; 4]);
+            montgomery_multiply_mod_order(&a, &one_low, &mut a_one_low);
+            montgomery_multiply_mod_order(&one_low, &a, &mut one_low_a);
+            assert_eq!(a_one_low, one_low_a);
+
+            //Results are fully reduced
+            let mut difference = [0u64; 4];
+            let mut borrow = subborrow_u64(0, ab[0], CURVE_ORDER[0], &mut difference[0]);
+            borrow = subborrow_u64(borrow, ab[1], CURVE_ORDER[1], &mut difference[1]);
+            borrow = subborrow_u64(borrow, ab[2], CURVE_ORDER[2], &mut difference[2]);
+            borrow = subborrow_u64(borrow, ab[3], CURVE_ORDER[3], &mut difference[3]);
+            assert_eq!(borrow, 1);
+        }
+    }
+
+    #[test]
+    fn fixed_base_multiplication_is_linear() {
+        let mut rng = StdRng::seed_from_u64(4747);
+        for _ in 0..ROUNDS {
+            let a = random_scalar(&mut rng);
+            let b = random_scalar(&mut rng);
+
+            //aG + bG through the double scalar path
+            let (mut k, mut l) = ([0u64; 4], [0u64; 4]);
+            k.copy_from_slice(&a);
+            l.copy_from_slice(&ONE);
+            let mut sum = mul_fixed(&b);
+            assert!(ecc_mul_double(&mut k, &mut l, &mut sum));
+            assert_eq!(encoded(&sum), encoded(&mul_fixed(&add(&a, &b))));
+
+            //ecc_mul clears the cofactor, so P = aG gives 392aG
+            let mut p = mul_fixed(&a);
+            let mut q = PointAffine::default();
+            assert!(ecc_mul(&mut p, &b, &mut q));
+            let (mut ab, mut scalar) = ([0u64; 4], [0u64; 4]);
+            montgomery_multiply_mod_order(&to_montgomery(&a), &b, &mut ab);
+            montgomery_multiply_mod_order(&to_montgomery(&ab), &[392, 0, 0, 0], &mut scalar);
+            assert_eq!(encoded(&q), encoded(&mul_fixed(&scalar)));
+        }
+    }
+
+    #[test]
+    fn encode_and_decode_points() {
+        let mut rng = StdRng::seed_from_u64(474747);
+        for _ in 0..ROUNDS {
+            let p = mul_fixed(&random_scalar(&mut rng));
+            let bytes = encoded(&p);
+            let mut decoded = PointAffine::default();
+            assert!(decode(&bytes, &mut decoded));
+            assert_eq!(decoded.x, p.x);
+            assert_eq!(decoded.y, p.y);
+
+            //Random bytes either aren't a point or encode back to themselves
+            let bytes: [u8; 32] = rng.random();
+            let mut decoded = PointAffine::default();
+            if decode(&bytes, &mut decoded) {
+                assert_eq!(encoded(&decoded), bytes);
+            }
+        }
+    }
+}
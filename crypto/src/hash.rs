@@ -1,5 +1,6 @@
 use hex::encode;
 use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
+use crate::secret::Secret;
 
 pub fn k12(input: &str) -> String {
     let ret_val = k12_bytes(&input.as_bytes().to_vec());
@@ -14,6 +15,15 @@ pub fn k12_bytes(input: &Vec<u8>) -> Vec<u8> {
     Vec::from(digest)
 }
 
+//k12 of secret material, digest is written straight into locked memory
+pub fn k12_secret(input: &[u8]) -> Secret {
+    let mut digest = Secret::zeroed(32);
+    let mut kangaroo = KangarooTwelve::new(b"");
+    kangaroo.update(input);
+    kangaroo.finalize(digest.expose_mut());
+    digest
+}
+
 pub fn k12_64(input: &Vec<u8>) -> Vec<u8> {
     let mut output = [0u8; 64];
     let mut hasher = KangarooTwelve::new(b"");
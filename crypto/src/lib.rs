@@ -5,32 +5,39 @@ pub mod hash;
 pub mod random;
 pub mod passwords;
 pub mod encryption;
+pub mod secret;
+pub mod vanity;
 const A_LOWERCASE_ASCII: u8 = 97u8;
 
 pub mod qubic_identities {
     use core::ptr::copy_nonoverlapping;
+    use rayon::prelude::*;
     use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
     use crate::{A_LOWERCASE_ASCII, hash};
     use hash::k12_bytes;
     use crate::fourq::consts::{CURVE_ORDER_0, CURVE_ORDER_1, CURVE_ORDER_2, CURVE_ORDER_3, MONTGOMERY_R_PRIME, ONE};
     use crate::fourq::ops::{addcarry_u64, decode, ecc_mul_double, ecc_mul_fixed, encode, montgomery_multiply_mod_order, subborrow_u64};
     use crate::fourq::types::{PointAffine};
-    pub fn get_subseed(seed: &str) -> Result<Vec<u8>, String> {
+    use crate::secret::{zeroize, Secret};
+    pub fn get_subseed(seed: &str) -> Result<Secret, String> {
         let mut seed_bytes: [u8; 55] = [0; 55];
         if seed.len() != 55 {
             return Err(String::from("Invalid Seed Length!"))
         }
         for (index, el) in &mut seed.chars().enumerate() {
             if !el.is_alphabetic() {
+                zeroize(&mut seed_bytes);
                 return Err(String::from("Invalid Seed!"));
             }
             seed_bytes[index] = el.to_ascii_lowercase() as u8 - A_LOWERCASE_ASCII;
 
         }
-        Ok(k12_bytes(&seed_bytes.to_vec()))
+        let subseed = hash::k12_secret(&seed_bytes);
+        zeroize(&mut seed_bytes);
+        Ok(subseed)
     }
-    pub fn get_private_key(subseed: &Vec<u8>) -> Vec<u8> {
-        k12_bytes(subseed)
+    pub fn get_private_key(subseed: &[u8]) -> Secret {
+        hash::k12_secret(subseed)
     }
 
     /*
@@ -46,7 +53,7 @@ pub mod qubic_identities {
     }
      */
 
-    pub fn get_public_key(private_key: &Vec<u8>) -> [u8; 32] {
+    pub fn get_public_key(private_key: &[u8]) -> [u8; 32] {
         let mut ret_val: [u8; 32] = [0; 32];
         let mut p = PointAffine::default();
         let private_key = private_key.chunks_exact(8).map(|c| u64::from_le_bytes(c.try_into().unwrap())).collect::<Vec<_>>();
@@ -82,6 +89,9 @@ pub mod qubic_identities {
 
     pub fn get_public_key_from_identity(identity: &String) -> Result<[u8; 32], bool> {
         let id: &[u8] = identity.as_bytes();
+        if id.len() != 60 {
+            return Err(false);
+        }
         let mut public_key: [u8; 32] = [0; 32];
         for i in 0..4 {
             public_key[i << 3..((i<<3) + 8)].copy_from_slice(&u64::to_le_bytes(0u64));
@@ -91,8 +101,12 @@ pub mod qubic_identities {
                   return Err(false);
                  }
                 let _bytes: [u8; 8] = public_key[i << 3..((i << 3) + 8)].try_into().unwrap();
-                let temp: u64 = u64::from_le_bytes(_bytes) * 26u64 +
-                    ((id[i * 14 + index] - b'A') as u64);
+                //14 letters can exceed 2^64, no public key encodes to those
+                let temp: u64 = match u64::from_le_bytes(_bytes).checked_mul(26u64)
+                    .and_then(|value| value.checked_add((id[i * 14 + index] - b'A') as u64)) {
+                    Some(value) => value,
+                    None => return Err(false)
+                };
                 public_key[i << 3..((i<<3) + 8)].copy_from_slice(&u64::to_le_bytes(temp));
 
             }
@@ -158,16 +172,35 @@ pub mod qubic_identities {
         
         a_bytes[..32].iter().zip(signature[..32].iter()).all(|(a,b)| a == b)
     }
+
+    //One signature to check in a batch
+    pub struct SignedDigest<'a> {
+        pub public_key: &'a [u8; 32],
+        pub message_digest: [u8; 32],
+        pub signature: &'a [u8; 64]
+    }
+
+    //Verifies every signature across rayon's thread pool, results in the same order
+    pub fn verify_batch(batch: &[SignedDigest]) -> Vec<bool> {
+        batch.par_iter()
+            .map(|signed| verify(signed.public_key, &signed.message_digest, signed.signature))
+            .collect()
+    }
+
+    //True only if every signature verifies, stops at the first that doesn't
+    pub fn verify_all(batch: &[SignedDigest]) -> bool {
+        batch.par_iter().all(|signed| verify(signed.public_key, &signed.message_digest, signed.signature))
+    }
+
     
-    
-    pub fn sign_raw(subseed: &Vec<u8>, public_key: &[u8; 32], message_digest: [u8; 32]) -> [u8; 64] {
+    pub fn sign_raw(subseed: &[u8], public_key: &[u8; 32], message_digest: [u8; 32]) -> [u8; 64] {
         let mut r_a = PointAffine::default();
         let (mut k, mut h, mut temp) = ([0u8; 64], [0u8; 64], [0u8; 96]);
         let mut r = [0u8; 64];
 
 
         let mut kg = KangarooTwelve::new(b"");
-        kg.update(subseed.as_slice());
+        kg.update(subseed);
         kg.into_xof().squeeze(&mut k);
 
         let mut signature = [0u8; 64];
@@ -222,20 +255,45 @@ pub mod qubic_identities {
 
             signature = signature_i.into_iter().flat_map(u64::to_le_bytes).collect::<Vec<_>>().try_into().unwrap();
         }
+        zeroize(&mut k);
+        zeroize(&mut temp);
         signature
     }
 
 
+    //Signed messages are K12(prefix || decimal length || "\n" || message). A transaction starts with its source public key
+    //and a tick with a computor index below 676, neither can begin with the prefix, so a message signature never signs either
+    pub const SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Qubic Signed Message:\n";
+
+    pub fn message_digest(message: &[u8]) -> [u8; 32] {
+        let length: String = message.len().to_string();
+        let mut prefixed: Vec<u8> = Vec::with_capacity(SIGNED_MESSAGE_PREFIX.len() + length.len() + 1 + message.len());
+        prefixed.extend_from_slice(SIGNED_MESSAGE_PREFIX);
+        prefixed.extend_from_slice(length.as_bytes());
+        prefixed.push(b'\n');
+        prefixed.extend_from_slice(message);
+        <[u8; 32]>::try_from(k12_bytes(&prefixed)).unwrap()
+    }
+
+    pub fn sign_message(subseed: &[u8], public_key: &[u8; 32], message: &[u8]) -> [u8; 64] {
+        sign_raw(subseed, public_key, message_digest(message))
+    }
+
+    pub fn verify_message(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+        verify(public_key, &message_digest(message), signature)
+    }
+
+
     #[cfg(test)]
     pub mod qubic_identity_primitive_tests {
         use crate::hash::k12_bytes;
         use crate::encoding::bytes_to_hex;
-        use crate::qubic_identities::{get_identity, get_private_key, get_public_key, get_public_key_from_identity, get_subseed, sign_raw};
+        use crate::qubic_identities::{get_identity, get_private_key, get_public_key, get_public_key_from_identity, get_subseed, message_digest, sign_message, sign_raw, verify, verify_all, verify_batch, verify_message, SignedDigest};
         #[test]
         fn get_a_subseed() {
             let seed = "lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf";
             let subseed = get_subseed(seed).unwrap();
-            let encoded = bytes_to_hex(&subseed);
+            let encoded = bytes_to_hex(&subseed.to_vec());
             assert_eq!(encoded, "d3420abb5f3e0527b588b361fa0a513335833af8b4a4aae23a2958195c3209dc".to_string())
         }
         #[test]
@@ -243,7 +301,7 @@ pub mod qubic_identities {
             let seed = "lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf";
             let subseed = get_subseed(seed).unwrap();
             let private_key = get_private_key(&subseed);
-            let encoded = bytes_to_hex(&private_key);
+            let encoded = bytes_to_hex(&private_key.to_vec());
             assert_eq!(encoded, "11531fcea5e11a4a384e211165ff8bcf458595b32c5374ec76cfa1b1da102238".to_string())
         }
         #[test]
@@ -273,7 +331,18 @@ pub mod qubic_identities {
             let identity = get_identity(&public_key);
             let pub_key_from_id = get_public_key_from_identity(&identity).unwrap();
 
-            assert_eq!(public_key, pub_key_from_id)
+            assert_eq!(public_key, pub_key_from_id);
+            assert!(get_public_key_from_identity(&"Z".repeat(60)).is_err());
+            assert!(get_public_key_from_identity(&identity[..59].to_string()).is_err());
+            assert!(get_public_key_from_identity(&String::new()).is_err());
+            //The first limb plus 2^64 still fits 14 letters and wrapped back to the same key and checksum
+            let mut limb: u128 = u64::from_le_bytes(public_key[0..8].try_into().unwrap()) as u128 + (1u128 << 64);
+            let mut alias: Vec<u8> = identity.as_bytes().to_vec();
+            for letter in alias[0..14].iter_mut() {
+                *letter = b'A' + (limb % 26) as u8;
+                limb /= 26;
+            }
+            assert!(get_public_key_from_identity(&String::from_utf8(alias).unwrap()).is_err());
         }
 
         #[test]
@@ -290,6 +359,58 @@ pub mod qubic_identities {
             assert_eq!(public_key, pub_key_from_id)
         }
 
+        #[test]
+        fn verify_a_batch_of_signatures() {
+            let seeds = ["lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"];
+            let mut keys: Vec<[u8; 32]> = Vec::new();
+            let mut digests: Vec<[u8; 32]> = Vec::new();
+            let mut signatures: Vec<[u8; 64]> = Vec::new();
+            for (index, seed) in seeds.iter().enumerate() {
+                let subseed = get_subseed(seed).unwrap();
+                let public_key = get_public_key(&get_private_key(&subseed));
+                let digest: [u8; 32] = k12_bytes(&vec![index as u8; 32]).try_into().unwrap();
+                signatures.push(sign_raw(&subseed, &public_key, digest));
+                keys.push(public_key);
+                digests.push(digest);
+            }
+            let batch: Vec<SignedDigest> = (0..seeds.len()).map(|index| SignedDigest {
+                public_key: &keys[index],
+                message_digest: digests[index],
+                signature: &signatures[index]
+            }).collect();
+            assert!(verify(&keys[0], &digests[0], &signatures[0]));
+            assert_eq!(verify_batch(&batch), vec![true, true]);
+            assert!(verify_all(&batch));
+
+            //Swap the signatures so neither matches its key
+            let swapped: Vec<SignedDigest> = (0..seeds.len()).map(|index| SignedDigest {
+                public_key: &keys[index],
+                message_digest: digests[index],
+                signature: &signatures[1 - index]
+            }).collect();
+            assert_eq!(verify_batch(&swapped), vec![false, false]);
+            assert!(!verify_all(&swapped));
+            assert!(verify_all(&[]));
+        }
 
+        #[test]
+        fn sign_and_verify_a_message() {
+            let subseed = get_subseed("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf").unwrap();
+            let public_key = get_public_key(&get_private_key(&subseed));
+            let message = b"I control this identity";
+            let signature = sign_message(&subseed, &public_key, message);
+            assert!(verify_message(&public_key, message, &signature));
+            assert!(!verify_message(&public_key, b"I control this identity!", &signature));
+
+            let other_subseed = get_subseed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+            let other_key = get_public_key(&get_private_key(&other_subseed));
+            assert!(!verify_message(&other_key, message, &signature));
+
+            //The prefix keeps message signatures and raw digest signatures apart in both directions
+            let raw_digest: [u8; 32] = k12_bytes(&message.to_vec()).try_into().unwrap();
+            assert!(!verify(&public_key, &raw_digest, &signature));
+            assert!(!verify_message(&public_key, message, &sign_raw(&subseed, &public_key, raw_digest)));
+            assert_ne!(message_digest(b""), message_digest(b"0"));
+        }
     }
 }
\ No newline at end of file
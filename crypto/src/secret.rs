@@ -0,0 +1,141 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+//Overwrite a buffer in a way the optimizer is not allowed to elide
+pub fn zeroize(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        unsafe { std::ptr::write_volatile(b, 0); }
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+fn lock_memory(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+    unsafe { libc::mlock(bytes.as_ptr() as *const libc::c_void, bytes.len()) == 0 }
+}
+
+#[cfg(unix)]
+fn unlock_memory(bytes: &[u8]) {
+    unsafe { libc::munlock(bytes.as_ptr() as *const libc::c_void, bytes.len()); }
+}
+
+#[cfg(not(unix))]
+fn lock_memory(_bytes: &[u8]) -> bool { false }
+
+#[cfg(not(unix))]
+fn unlock_memory(_bytes: &[u8]) {}
+
+//Fixed size heap buffer for passwords, seeds and keys.
+//Memory locked where the OS allows it, zeroed on drop and never printed.
+pub struct Secret {
+    bytes: Box<[u8]>,
+    locked: bool
+}
+
+impl Secret {
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut secret = Secret::zeroed(bytes.len());
+        secret.bytes.copy_from_slice(bytes);
+        secret
+    }
+
+    pub fn zeroed(len: usize) -> Self {
+        let bytes: Box<[u8]> = vec![0u8; len].into_boxed_slice();
+        let locked = lock_memory(&bytes);
+        Secret { bytes, locked }
+    }
+
+    pub fn expose_mut(&mut self) -> &mut [u8] { &mut self.bytes }
+
+    //Empty if the secret does not hold valid utf8
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes).unwrap_or("")
+    }
+
+    pub fn is_locked(&self) -> bool { self.locked }
+
+    pub fn zero(&mut self) { zeroize(&mut self.bytes); }
+}
+
+impl Deref for Secret {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] { &self.bytes }
+}
+
+impl Clone for Secret {
+    fn clone(&self) -> Self { Secret::new(&self.bytes) }
+}
+
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        if self.bytes.len() != other.bytes.len() {
+            return false;
+        }
+        self.bytes.iter().zip(other.bytes.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret([REDACTED])")
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self { Secret::new(value.as_bytes()) }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self { Secret::from(value.into_bytes()) }
+}
+
+impl From<Vec<u8>> for Secret {
+    fn from(mut value: Vec<u8>) -> Self {
+        let secret = Secret::new(&value);
+        zeroize(&mut value);
+        secret
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        zeroize(&mut self.bytes);
+        if self.locked {
+            unlock_memory(&self.bytes);
+        }
+    }
+}
+
+
+#[cfg(test)]
+pub mod secret_tests {
+    use crate::secret::{zeroize, Secret};
+
+    #[test]
+    fn create_a_secret_and_read_it() {
+        let secret = Secret::from("password");
+        assert_eq!(secret.as_str(), "password");
+        assert_eq!(secret.len(), 8);
+        assert_eq!(secret.clone(), secret);
+    }
+
+    #[test]
+    fn secret_is_redacted_from_debug() {
+        let secret = Secret::from(String::from("password"));
+        assert!(!format!("{:?}", secret).contains("password"));
+    }
+
+    #[test]
+    fn zero_a_secret() {
+        let mut secret = Secret::from(vec![1u8, 2, 3]);
+        secret.zero();
+        assert_eq!(&secret[..], &[0u8, 0, 0]);
+        let mut bytes = [7u8; 4];
+        zeroize(&mut bytes);
+        assert_eq!(bytes, [0u8; 4]);
+    }
+}
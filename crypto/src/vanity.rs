@@ -0,0 +1,159 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use rand::prelude::*;
+use crate::qubic_identities::{get_identity, get_private_key, get_public_key, get_subseed};
+use crate::secret::{zeroize, Secret};
+
+//Letters an identity must start and end with, uppercase like the identities themselves
+#[derive(Debug, Clone, PartialEq)]
+pub struct VanityPattern {
+    pub prefix: String,
+    pub suffix: String
+}
+
+//A seed whose identity matches, the attempts count is across every thread
+pub struct VanityMatch {
+    pub seed: Secret,
+    pub identity: String,
+    pub attempts: u64
+}
+
+//Chance a random identity has `letter` at `position`. Letters are base 26 digits, 14 per 64 bit limb of the
+//public key then 4 of an 18 bit checksum, so the last letter of each group can't take every value.
+pub fn letter_probability(position: usize, letter: u8) -> f64 {
+    let (range, place): (u128, u128) = if position < 56 {
+        (1u128 << 64, 26u128.pow((position % 14) as u32))
+    } else {
+        (1u128 << 18, 26u128.pow((position - 56) as u32))
+    };
+    let value: u128 = (letter - b'A') as u128;
+    let block: u128 = place * 26;
+    let partial: u128 = (range % block).saturating_sub(value * place).min(place);
+    ((range / block) * place + partial) as f64 / range as f64
+}
+
+impl VanityPattern {
+    pub fn new(prefix: &str, suffix: &str) -> Result<Self, String> {
+        let pattern = VanityPattern { prefix: prefix.to_ascii_uppercase(), suffix: suffix.to_ascii_uppercase() };
+        if pattern.prefix.is_empty() && pattern.suffix.is_empty() {
+            return Err("Empty Vanity Pattern!".to_string());
+        }
+        if pattern.prefix.len() + pattern.suffix.len() > 60 {
+            return Err("Vanity Pattern Is Longer Than An Identity!".to_string());
+        }
+        for (position, letter) in pattern.letters() {
+            if !letter.is_ascii_uppercase() {
+                return Err(format!("Identities Only Contain A-Z, Not '{}'", letter as char));
+            }
+            if letter_probability(position, letter) == 0.0 {
+                return Err(format!("No Identity Has '{}' At Position {}", letter as char, position + 1));
+            }
+        }
+        Ok(pattern)
+    }
+
+    fn letters(&self) -> Vec<(usize, u8)> {
+        let suffix_start: usize = 60 - self.suffix.len();
+        self.prefix.bytes().enumerate()
+            .chain(self.suffix.bytes().enumerate().map(|(index, letter)| (suffix_start + index, letter)))
+            .collect()
+    }
+
+    pub fn matches(&self, identity: &str) -> bool {
+        identity.starts_with(self.prefix.as_str()) && identity.ends_with(self.suffix.as_str())
+    }
+
+    //Per attempt, treating letters as independent
+    pub fn probability(&self) -> f64 {
+        self.letters().iter().map(|(position, letter)| letter_probability(*position, *letter)).product()
+    }
+
+    pub fn expected_attempts(&self) -> f64 {
+        1.0 / self.probability()
+    }
+
+    //Chance a match would have turned up within this many attempts
+    pub fn chance_within(&self, attempts: u64) -> f64 {
+        1.0 - (1.0 - self.probability()).powf(attempts as f64)
+    }
+}
+
+fn random_seed(rng: &mut StdRng, seed: &mut [u8; 55]) {
+    for letter in seed.iter_mut() {
+        *letter = rng.random_range(b'a'..=b'z');
+    }
+}
+
+//Draws random seeds on every thread until one matches or cancel is set. attempts is shared so callers can
+//report progress while the search runs.
+pub fn search(pattern: &VanityPattern, threads: usize, attempts: &AtomicU64, cancel: &AtomicBool) -> Option<VanityMatch> {
+    let found: AtomicBool = AtomicBool::new(false);
+    let result: Mutex<Option<VanityMatch>> = Mutex::new(None);
+    std::thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let mut rng = StdRng::from_os_rng();
+                let mut seed: [u8; 55] = [0; 55];
+                while !cancel.load(Ordering::Relaxed) && !found.load(Ordering::Relaxed) {
+                    random_seed(&mut rng, &mut seed);
+                    let seed_str: &str = std::str::from_utf8(&seed).unwrap();
+                    let subseed = get_subseed(seed_str).unwrap();
+                    let identity: String = get_identity(&get_public_key(&get_private_key(&subseed)));
+                    let attempt: u64 = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+                    if pattern.matches(identity.as_str()) && !found.swap(true, Ordering::SeqCst) {
+                        *result.lock().unwrap() = Some(VanityMatch { seed: Secret::from(seed_str), identity, attempts: attempt });
+                    }
+                }
+                zeroize(&mut seed);
+            });
+        }
+    });
+    result.into_inner().unwrap()
+}
+
+
+#[cfg(test)]
+pub mod vanity_tests {
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use crate::qubic_identities::{get_identity, get_private_key, get_public_key, get_subseed};
+    use crate::vanity::{letter_probability, search, VanityPattern};
+
+    #[test]
+    fn estimate_letter_odds() {
+        assert!((letter_probability(0, b'Q') - 1.0 / 26.0).abs() < 1e-12);
+        //26^13 fits 7.4 times in 2^64, so the 14th letter of a group is A-H
+        assert!(letter_probability(13, b'A') > 0.13);
+        assert_eq!(letter_probability(13, b'I'), 0.0);
+        //The checksum is 18 bits, 26^3 fits 14.9 times, so the last letter is A-O
+        assert!(letter_probability(59, b'O') > 0.0);
+        assert_eq!(letter_probability(59, b'P'), 0.0);
+        for position in [0, 13, 41, 55, 56, 59] {
+            let total: f64 = (b'A'..=b'Z').map(|letter| letter_probability(position, letter)).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+
+        let pattern = VanityPattern::new("ab", "").unwrap();
+        assert!((pattern.expected_attempts() - 676.0).abs() < 1e-6);
+        assert!(pattern.chance_within(676) > 0.6);
+        assert!(VanityPattern::new("AAAAAAAAAAAAAZ", "").is_err());
+        assert!(VanityPattern::new("", "Z").is_err());
+        assert!(VanityPattern::new("A1", "").is_err());
+        assert!(VanityPattern::new("", "").is_err());
+    }
+
+    #[test]
+    fn find_a_vanity_identity() {
+        let pattern = VanityPattern::new("b", "").unwrap();
+        let attempts = AtomicU64::new(0);
+        let found = search(&pattern, 2, &attempts, &AtomicBool::new(false)).unwrap();
+        assert!(found.identity.starts_with('B'));
+        assert!(found.attempts >= 1 && found.attempts <= attempts.load(Ordering::Relaxed));
+        let subseed = get_subseed(found.seed.as_str()).unwrap();
+        assert_eq!(get_identity(&get_public_key(&get_private_key(&subseed))), found.identity);
+
+        //Cancelled before it starts, nothing is searched
+        let attempts = AtomicU64::new(0);
+        assert!(search(&pattern, 2, &attempts, &AtomicBool::new(true)).is_none());
+        assert_eq!(attempts.load(Ordering::Relaxed), 0);
+    }
+}
@@ -1,7 +1,7 @@
 use std::io::prelude::*;
 use std::collections::HashMap;
 use std::net::{SocketAddr, TcpStream};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use api::request::QubicApiPacket;
 use logger::{ debug, error };
 use std::time::{Duration};
@@ -15,7 +15,8 @@ use crate::peer::Peer;
 
 pub struct PeerSet {
   peers: Vec<Peer>,
-  req_channel: (spmc::Sender<QubicApiPacket>, spmc::Receiver<QubicApiPacket>),
+  //Each worker owns its peer's stream, so requests go down that peer's own channel
+  req_channels: HashMap<String, mpsc::Sender<QubicApiPacket>>,
   request_matcher: Arc<Mutex<HashMap<u32, QubicApiPacket>>>,
   threads: HashMap<String, std::thread::JoinHandle<()>>
 }
@@ -30,7 +31,7 @@ impl PeerSet {
             peers: vec![],
             threads: HashMap::new(),
             request_matcher: Arc::new(Mutex::new(HashMap::new())),
-            req_channel: spmc::channel::<QubicApiPacket>(),
+            req_channels: HashMap::new(),
         };
         peer_set
     }
@@ -65,7 +66,8 @@ impl PeerSet {
                 {
                     let mut peer = new_peer.clone();
                     peer.set_stream(stream);
-                    let rx = self.req_channel.1.clone();
+                    let (tx, rx) = mpsc::channel::<QubicApiPacket>();
+                    self.req_channels.insert(id.clone(), tx);
                     let id = id.clone();
                     let copied_id = id.to_owned();
                     let t = std::thread::spawn(move || worker::handle_new_peer(id.to_owned(), request_matcher, peer, rx));
@@ -94,7 +96,9 @@ impl PeerSet {
                 match stream.peer_addr() {
                     Ok(conn) => {
                         if Ok(conn) == ip.parse() {
+                            let id = connection.get_id().to_owned();
                             self.peers.remove(index);
+                            self.req_channels.remove(&id);
                             return true;
                         }
                     },
@@ -122,6 +126,7 @@ impl PeerSet {
                     Ok(_) => {
                         //println!("Removed Peer {}", id);
                         self.peers.remove(index);
+                        self.req_channels.remove(id);
                         return true;
                     },
                     Err(err) => {
@@ -163,9 +168,11 @@ impl PeerSet {
         
         
         let spam_all: bool = match request.api_type {
-            api::header::EntityType::RequestCurrentTickInfo => false,
+            //Every peer is asked, the latest tick is a quorum of their answers
+            api::header::EntityType::RequestCurrentTickInfo => true,
             api::header::EntityType::RequestedQuorumTick => false,
             api::header::EntityType::RequestTickData => false,
+            api::header::EntityType::RequestTickTransactions => false,
             api::header::EntityType::RequestContractFunction => false,
             api::header::EntityType::RequestAssets => false,
             _ => true
@@ -198,7 +205,11 @@ impl PeerSet {
             }
             request.peer = Some(peer.get_id().to_owned());
 
-            match self.req_channel.0.send(request.clone()) {
+            let sent = match self.req_channels.get(peer.get_id()) {
+                Some(channel) => channel.send(request.clone()).map_err(|err| err.to_string()),
+                None => Err("No Worker For Peer".to_string())
+            };
+            match sent {
                 Ok(_) => { 
                     if !spam_all {
                         break;
@@ -214,6 +225,21 @@ impl PeerSet {
         }
         Ok(())
     }
+
+    //Sends to one peer only, for retrying a request a particular peer didn't get
+    pub fn make_request_to_peer(&mut self, peer_id: &str, mut request: QubicApiPacket) -> Result<(), String> {
+        request.peer = Some(peer_id.to_string());
+        match self.req_channels.get(peer_id) {
+            Some(channel) => match channel.send(request) {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    self.delete_peer_by_id(peer_id);
+                    Err(format!("Failed To Send Request To Peer {}: {}", peer_id, err))
+                }
+            },
+            None => Err(format!("Unknown Peer {}", peer_id))
+        }
+    }
 }
 
 
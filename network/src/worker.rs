@@ -1,15 +1,29 @@
 use std::collections::HashMap;
 use std::io::prelude::*;
 use std::io::ErrorKind;
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::Duration;
 use crate::peer::Peer;
+use api::header::EntityType;
 use api::request::QubicApiPacket;
+use crypto::hash::k12_bytes;
+use crypto::qubic_identities::get_identity;
 use store::get_db_path;
 use store::sqlite::peer::set_peer_disconnected;
+use store::sqlite::transfer_broadcast::set_transfer_broadcast_status;
 use crate::tcp_recv::qubic_tcp_receive_data;
 
-pub fn handle_new_peer(_id: String, request_matcher: Arc<Mutex<HashMap<u32, QubicApiPacket>>>, peer: Peer, rx: spmc::Receiver<QubicApiPacket>) {
+//Our own transfers carry a broadcast record per peer, the data is the signed transaction
+fn record_broadcast(peer: &Peer, request: &QubicApiPacket, written: bool) {
+    let digest: [u8; 32] = k12_bytes(&request.data).try_into().unwrap();
+    let txid: String = get_identity(&digest).to_lowercase();
+    let status: &str = if written { "sent" } else { "failed" };
+    if let Err(err) = set_transfer_broadcast_status(get_db_path().as_str(), txid.as_str(), peer.get_id().as_str(), status) {
+        println!("Failed To Record Broadcast Of <{}> To Peer {}: {}", txid, peer.get_id(), err);
+    }
+}
+
+pub fn handle_new_peer(_id: String, request_matcher: Arc<Mutex<HashMap<u32, QubicApiPacket>>>, peer: Peer, rx: mpsc::Receiver<QubicApiPacket>) {
     if peer.get_stream().is_none() {
        println!("Peer {} Missing TcpStream! Shutting Down Worker Thread.", peer.get_id());
         return;
@@ -18,7 +32,7 @@ pub fn handle_new_peer(_id: String, request_matcher: Arc<Mutex<HashMap<u32, Qubi
     loop {
         std::thread::sleep(Duration::from_millis(100));
         //Block until we receive work
-        match rx.clone().recv() {
+        match rx.recv() {
             Ok(mut request) => {
                 match request_matcher.lock() {
                     Ok(mut matcher) => {
@@ -26,7 +40,11 @@ pub fn handle_new_peer(_id: String, request_matcher: Arc<Mutex<HashMap<u32, Qubi
                     },
                     Err(_) => {}
                 }
-                match stream.write(request.as_bytes().as_slice()) {
+                let written = stream.write(request.as_bytes().as_slice());
+                if let EntityType::BroadcastTransaction = request.api_type {
+                    record_broadcast(&peer, &request, written.is_ok());
+                }
+                match written {
                     Ok(_) => {
                         stream.flush().unwrap();
                         //let response = ["Peer ", id.as_str(), " Responded At Time ", Utc::now().to_string().as_str()].join("");
@@ -65,8 +83,9 @@ pub fn handle_new_peer(_id: String, request_matcher: Arc<Mutex<HashMap<u32, Qubi
                     }
                 }
             },
-            Err(err) => {
-                println!("Failed To Receive Work In Thread! {}", err.to_string());
+            Err(_) => {
+                //The peer set dropped our channel, this peer was removed
+                break;
             }
         }
     }
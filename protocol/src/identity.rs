@@ -3,8 +3,8 @@
 use crypto;
 use core::str::Utf8Error;
 use hex;
-use crypto::encryption::{NONCELENGTH, SALTLENGTH};
-use crate::wallet_unlock::{is_wallet_unlocked, PLAINTEXT_DECRYPT_PASSWORD};
+use crypto::secret::Secret;
+use crate::wallet_unlock::{is_wallet_unlocked, get_plaintext_password, peek_plaintext_password};
 
 fn identity_to_address(identity: &Vec<u8>) -> Result<String, Utf8Error> {
     match  std::str::from_utf8(identity.as_slice()) {
@@ -15,7 +15,7 @@ fn identity_to_address(identity: &Vec<u8>) -> Result<String, Utf8Error> {
 
 #[derive(Debug)]
 pub struct Identity {
-    pub seed: String,
+    pub seed: Secret,
     pub hash: String,
     pub salt: String,
     pub identity: String,
@@ -27,19 +27,15 @@ impl Identity {
         if !self.contains_seed() {
             Err("Invalid Seed! Can't Get Public Key!".to_string())
         } else {
-            let mut own_subseed: Vec<u8> = vec![];
-            let mut private_key: Vec<u8> = vec![];
-            let mut public_key: [u8; 32] = [0; 32];
-            own_subseed = crypto::qubic_identities::get_subseed(self.seed.as_str()).expect("Failed To Get SubSeed!");
-            private_key = crypto::qubic_identities::get_private_key(&own_subseed);
-            public_key = crypto::qubic_identities::get_public_key(&private_key);
-            own_subseed = crypto::qubic_identities::get_subseed(self.seed.as_str()).expect("Failed To Get SubSeed!");
+            let own_subseed: Secret = crypto::qubic_identities::get_subseed(self.seed.as_str()).expect("Failed To Get SubSeed!");
+            let private_key: Secret = crypto::qubic_identities::get_private_key(&own_subseed);
+            let public_key: [u8; 32] = crypto::qubic_identities::get_public_key(&private_key);
             Ok(Vec::from(public_key))
         }
     }
     pub fn from_vars(seed: &str, hash: &str, salt: &str, identity: &str, is_encrypted: bool) -> Self {
         Identity {
-            seed: String::from(seed),
+            seed: Secret::from(seed),
             hash: String::from(hash),
             salt: String::from(salt),
             identity: String::from(identity),
@@ -53,7 +49,7 @@ impl Identity {
         let public_key = crypto::qubic_identities::get_public_key(&private_key);
         let id = crypto::qubic_identities::get_identity(&public_key);
         Identity {
-            seed: String::from(seed),
+            seed: Secret::from(seed),
             hash: String::from(""),
             salt: String::from(""),
             identity: id,
@@ -71,7 +67,7 @@ impl Identity {
                         let _salt: String = hex::encode(&salt);
                         let _seed: String = hex::encode(&encrypted);
                         Ok(Identity {
-                            seed: _seed,
+                            seed: Secret::from(_seed),
                             hash: hashed_password,
                             salt: _salt,
                             identity: self.identity.to_owned(),
@@ -87,16 +83,25 @@ impl Identity {
         }
     }
 
+    pub fn needs_encryption_upgrade(&self) -> bool {
+        if !self.encrypted {
+            return false;
+        }
+        match hex::decode(&self.salt) {
+            Ok(salt_bytes) => crypto::encryption::needs_upgrade(&salt_bytes, &crypto::encryption::Kdf::default()),
+            Err(_) => false
+        }
+    }
+
     pub fn decrypt_identity(&mut self, password: &str) -> Result<Self, String> {
         if !self.encrypted {
             return Err("Unable To Decrypt an Unencrypted Identity!".to_string());
         }
         match crypto::passwords::verify_password(password, self.hash.as_str()) {
             Ok(_) => {
-                let salt: Vec<u8> = hex::decode(&self.salt).unwrap();
-                match <[u8; SALTLENGTH + NONCELENGTH]>::try_from(salt.as_slice()) {
+                match hex::decode(&self.salt) {
                     Ok(salt_bytes) => {
-                        let seed_bytes: Vec<u8> = hex::decode(&self.seed).unwrap();
+                        let seed_bytes: Vec<u8> = hex::decode(self.seed.as_str()).unwrap();
                         match crypto::encryption::decrypt(&salt_bytes, &seed_bytes, password) {
                             Ok(seed) => {
                                 Ok(Identity {
@@ -122,25 +127,30 @@ impl Identity {
     }
 
     pub fn decrypt_identity_unlocked_wallet(&mut self) -> Result<Self, String> {
+        self.decrypt_identity_with_session_password(get_plaintext_password())
+    }
+
+    //For background workers, decrypting here doesn't count as activity that keeps the wallet unlocked
+    pub fn decrypt_identity_unlocked_wallet_in_background(&mut self) -> Result<Self, String> {
+        self.decrypt_identity_with_session_password(peek_plaintext_password())
+    }
+
+    fn decrypt_identity_with_session_password(&mut self, session_password: Result<Secret, ()>) -> Result<Self, String> {
         if !self.encrypted {
             return Err("Unable To Decrypt an Unencrypted Identity!".to_string());
         }
         if !is_wallet_unlocked().unwrap() {
             return Err("Cannot Decrypt Identity, Wallet Locked.".to_string());
         }
-        match PLAINTEXT_DECRYPT_PASSWORD.clone().lock() {
-            Ok(secret) => unsafe {
-                //let s = secret.borrow();
-                let s = secret;
-                let password = std::str::from_utf8_unchecked(&*s).to_string();
+        match session_password {
+            Ok(password) => {
                 match crypto::passwords::verify_password(password.as_str(), self.hash.as_str()) {
                     Ok(_) => { logger::info("Password Verified!"); },
                     Err(_) => { return Err("Unlocked Wallet but Stored Password Failed To Decrypt Stored Identity.".to_string());}
                 }
-                let salt: Vec<u8> = hex::decode(&self.salt).unwrap();
-                match <[u8; SALTLENGTH + NONCELENGTH]>::try_from(salt.as_slice()) {
+                match hex::decode(&self.salt) {
                     Ok(salt_bytes) => {
-                        let seed_bytes: Vec<u8> = hex::decode(&self.seed).unwrap();
+                        let seed_bytes: Vec<u8> = hex::decode(self.seed.as_str()).unwrap();
                         match crypto::encryption::decrypt(&salt_bytes, &seed_bytes, password.as_str()) {
                             Ok(seed) => {
                                 Ok(Identity {
@@ -159,7 +169,7 @@ impl Identity {
                     Err(_) => Err("Error Decrypting Identity! (Db Corruption?)".to_string())
                 }
             },
-            Err(_) => Err("Failed To Acquire Lock on Stored Password.".to_string())
+            Err(_) => Err("Failed To Acquire Stored Password.".to_string())
         }
     }
 }
@@ -212,5 +222,23 @@ mod create_identity {
             assert_eq!(id.encrypted, false);
             assert_eq!(id.seed.as_str(), "lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
         }
+
+        #[test]
+        fn decrypt_a_legacy_identity_and_upgrade() {
+            let kdf = crypto::encryption::Kdf::Pbkdf2Sha256 { iterations: crypto::encryption::LEGACY_PBKDF2_ITERATIONS };
+            let (salt, encrypted) = crypto::encryption::encrypt_with_kdf("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf", "password", &kdf).unwrap();
+            let mut id: Identity = Identity::from_vars(
+                hex::encode(&encrypted).as_str(),
+                crypto::passwords::hash_password("password").unwrap().as_str(),
+                hex::encode(&salt[15..]).as_str(),
+                "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON",
+                true
+            );
+            assert_eq!(id.needs_encryption_upgrade(), true);
+            let mut decrypted = id.decrypt_identity("password").unwrap();
+            assert_eq!(decrypted.seed.as_str(), "lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+            let upgraded = decrypted.encrypt_identity("password").unwrap();
+            assert_eq!(upgraded.needs_encryption_upgrade(), false);
+        }
     }
 }
\ No newline at end of file
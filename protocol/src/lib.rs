@@ -4,4 +4,9 @@ pub trait AsBytes {
 
 pub mod identity;
 pub mod transfer;
-pub mod wallet_unlock;
\ No newline at end of file
+pub mod tick_transaction;
+pub mod tick_rate;
+pub mod wallet_unlock;
+pub mod transfer_lifecycle;
+pub mod tick_quorum;
+pub mod network_profile;
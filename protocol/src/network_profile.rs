@@ -0,0 +1,163 @@
+use dotenv::dotenv;
+use crypto::qubic_identities::get_public_key_from_identity;
+use once_cell::sync::Lazy;
+
+pub const MAINNET: &str = "mainnet";
+pub const TESTNET: &str = "testnet";
+
+//Everything that differs between the network we talk to, chosen by RUBIC_NETWORK and overridable field by field from the env
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub arbitrator: String,
+    //Sizes the computor list on the wire, so a profile can only ever match the compiled in count
+    pub number_computors: usize,
+    pub quorum_minimum_votes: u16,
+    pub qx_address: String,
+    pub peer_port: u16,
+    pub bootstrap_peers: Vec<String>
+}
+
+impl NetworkProfile {
+    pub fn mainnet() -> Self {
+        NetworkProfile {
+            name: MAINNET.to_string(),
+            arbitrator: "AFZPUAIYVPNUYGJRQVLUKOPPVLHAZQTGLYAAUUNBXFTVTAMSBKQBLEIEPCVJ".to_string(),
+            number_computors: 676,
+            quorum_minimum_votes: 451,
+            qx_address: "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID".to_string(),
+            peer_port: 21841,
+            bootstrap_peers: vec![
+                "31.204.159.155:21841".to_string(),
+                "138.68.105.178:21841".to_string(),
+                "164.90.210.6:21841".to_string()
+            ]
+        }
+    }
+
+    //Same consensus parameters as mainnet with no public peers, a private testnet supplies its own through the env
+    pub fn testnet() -> Self {
+        NetworkProfile {
+            name: TESTNET.to_string(),
+            bootstrap_peers: vec![],
+            ..NetworkProfile::mainnet()
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            MAINNET => Ok(NetworkProfile::mainnet()),
+            TESTNET => Ok(NetworkProfile::testnet()),
+            _ => Err(format!("Unknown Network Profile <{}>, Expected {} Or {}", name, MAINNET, TESTNET))
+        }
+    }
+
+    //Applies RUBIC_ARBITRATOR, RUBIC_QUORUM_MINIMUM_VOTES, RUBIC_QX_ADDRESS, RUBIC_PEER_PORT and RUBIC_BOOTSTRAP_PEERS (comma separated ip:port)
+    pub fn with_overrides(mut self, get_var: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        //Identities are decoded with unwrap deeper in, so a bad one has to stop the profile from loading
+        if let Some(arbitrator) = get_var("RUBIC_ARBITRATOR") {
+            if get_public_key_from_identity(&arbitrator).is_err() {
+                return Err(format!("Invalid RUBIC_ARBITRATOR <{}>", arbitrator));
+            }
+            self.arbitrator = arbitrator;
+        }
+        if let Some(votes) = get_var("RUBIC_QUORUM_MINIMUM_VOTES") {
+            //Mainnet may only ask for more votes than its own quorum, never fewer
+            let floor: u16 = if self.name == MAINNET { NetworkProfile::mainnet().quorum_minimum_votes } else { 1 };
+            self.quorum_minimum_votes = match votes.parse::<u16>() {
+                Ok(votes) if votes >= floor && (votes as usize) <= self.number_computors => votes,
+                _ => return Err(format!("Invalid RUBIC_QUORUM_MINIMUM_VOTES <{}>, Expected {} To {}", votes, floor, self.number_computors))
+            };
+        }
+        if let Some(qx_address) = get_var("RUBIC_QX_ADDRESS") {
+            if get_public_key_from_identity(&qx_address).is_err() {
+                return Err(format!("Invalid RUBIC_QX_ADDRESS <{}>", qx_address));
+            }
+            self.qx_address = qx_address;
+        }
+        if let Some(port) = get_var("RUBIC_PEER_PORT") {
+            self.peer_port = match port.parse::<u16>() {
+                Ok(port) => port,
+                Err(_) => return Err(format!("Invalid RUBIC_PEER_PORT <{}>", port))
+            };
+        }
+        if let Some(peers) = get_var("RUBIC_BOOTSTRAP_PEERS") {
+            self.bootstrap_peers = peers.split(',')
+                .map(|peer| peer.trim())
+                .filter(|peer| !peer.is_empty())
+                .map(|peer| match peer.contains(':') {
+                    true => peer.to_string(),
+                    false => format!("{}:{}", peer, self.peer_port)
+                })
+                .collect();
+        }
+        Ok(self)
+    }
+
+    //Mainnet keeps the plain db file so existing wallets open unchanged, any other network gets its own file next to it
+    pub fn namespace_db_path(&self, path: &str) -> String {
+        if self.name == MAINNET {
+            return path.to_string();
+        }
+        let (dir, file) = match path.rfind('/') {
+            Some(index) => path.split_at(index + 1),
+            None => ("", path)
+        };
+        match file.rfind('.') {
+            Some(index) => format!("{}{}.{}{}", dir, &file[..index], self.name, &file[index..]),
+            None => format!("{}{}.{}", dir, file, self.name)
+        }
+    }
+}
+
+static NETWORK_PROFILE: Lazy<NetworkProfile> = Lazy::new(|| {
+    dotenv().ok();
+    let name: String = std::env::var("RUBIC_NETWORK").unwrap_or(MAINNET.to_string());
+    let profile = NetworkProfile::from_name(name.as_str())
+        .and_then(|profile| profile.with_overrides(|key| std::env::var(key).ok()));
+    match profile {
+        Ok(profile) => profile,
+        Err(err) => panic!("{}", err)
+    }
+});
+
+pub fn get_network_profile() -> &'static NetworkProfile {
+    &NETWORK_PROFILE
+}
+
+
+#[test]
+fn build_network_profiles() {
+    assert_eq!(NetworkProfile::from_name("MainNet").unwrap(), NetworkProfile::mainnet());
+    assert!(NetworkProfile::from_name("devnet").is_err());
+    let testnet = NetworkProfile::from_name(TESTNET).unwrap();
+    assert!(testnet.bootstrap_peers.is_empty());
+    assert_eq!(testnet.arbitrator, NetworkProfile::mainnet().arbitrator);
+
+    let custom = testnet.clone().with_overrides(|key| match key {
+        "RUBIC_PEER_PORT" => Some("31841".to_string()),
+        "RUBIC_BOOTSTRAP_PEERS" => Some("10.0.0.1, 10.0.0.2:21841,".to_string()),
+        "RUBIC_QUORUM_MINIMUM_VOTES" => Some("2".to_string()),
+        _ => None
+    }).unwrap();
+    assert_eq!(custom.peer_port, 31841);
+    assert_eq!(custom.bootstrap_peers, vec!["10.0.0.1:31841".to_string(), "10.0.0.2:21841".to_string()]);
+    assert_eq!(custom.quorum_minimum_votes, 2);
+    assert!(testnet.clone().with_overrides(|key| (key == "RUBIC_QUORUM_MINIMUM_VOTES").then(|| "677".to_string())).is_err());
+    assert!(testnet.clone().with_overrides(|key| (key == "RUBIC_ARBITRATOR").then(|| "SHORT".to_string())).is_err());
+    assert!(testnet.clone().with_overrides(|key| (key == "RUBIC_QUORUM_MINIMUM_VOTES").then(|| "0".to_string())).is_err());
+    let mainnet = NetworkProfile::mainnet();
+    assert!(mainnet.clone().with_overrides(|key| (key == "RUBIC_QUORUM_MINIMUM_VOTES").then(|| "450".to_string())).is_err());
+    assert_eq!(mainnet.clone().with_overrides(|key| (key == "RUBIC_QUORUM_MINIMUM_VOTES").then(|| "500".to_string())).unwrap().quorum_minimum_votes, 500);
+    //60 characters but not identities, lowercase or with a checksum that doesn't match
+    let lowercase: String = mainnet.arbitrator.to_lowercase();
+    let mut bad_checksum: String = mainnet.qx_address.clone();
+    bad_checksum.replace_range(59..60, if bad_checksum.ends_with('A') { "B" } else { "A" });
+    assert!(testnet.clone().with_overrides(|key| (key == "RUBIC_ARBITRATOR").then(|| lowercase.clone())).is_err());
+    assert!(testnet.clone().with_overrides(|key| (key == "RUBIC_QX_ADDRESS").then(|| bad_checksum.clone())).is_err());
+    assert_eq!(testnet.clone().with_overrides(|key| (key == "RUBIC_QX_ADDRESS").then(|| mainnet.arbitrator.clone())).unwrap().qx_address, mainnet.arbitrator);
+
+    assert_eq!(NetworkProfile::mainnet().namespace_db_path("/home/me/.rubic/rubic.sqlite"), "/home/me/.rubic/rubic.sqlite");
+    assert_eq!(testnet.namespace_db_path("/home/me/.rubic/rubic.sqlite"), "/home/me/.rubic/rubic.testnet.sqlite");
+    assert_eq!(testnet.namespace_db_path("C:/data.v1/wallet"), "C:/data.v1/wallet.testnet");
+}
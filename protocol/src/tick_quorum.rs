@@ -0,0 +1,44 @@
+//Fewest fresh peer reports needed before a latest tick is trusted, fewer if we aren't connected to that many
+pub const MIN_TICK_REPORTS: usize = 3;
+//A peer reporting a tick this far from the median is flagged as an outlier
+pub const MAX_TICK_DEVIATION: u32 = 20;
+//Reports older than this no longer count towards the quorum
+pub const TICK_REPORT_MAX_AGE_SECS: u32 = 30;
+
+//The lower median of the reported ticks, so a single peer running ahead can never move it forward.
+//None until enough of the connected peers have reported.
+pub fn quorum_tick(reports: &[u32], connected_peers: usize) -> Option<u32> {
+    let required: usize = MIN_TICK_REPORTS.min(connected_peers).max(1);
+    if reports.len() < required {
+        return None;
+    }
+    let mut sorted: Vec<u32> = reports.to_vec();
+    sorted.sort_unstable();
+    Some(sorted[(sorted.len() - 1) / 2])
+}
+
+//The same lower median over reported epochs, so a single peer can't announce a rollover
+pub fn quorum_epoch(reports: &[u16], connected_peers: usize) -> Option<u16> {
+    let reports: Vec<u32> = reports.iter().map(|epoch| *epoch as u32).collect();
+    quorum_tick(reports.as_slice(), connected_peers).map(|epoch| epoch as u16)
+}
+
+pub fn is_tick_outlier(tick: u32, quorum: u32) -> bool {
+    tick.abs_diff(quorum) > MAX_TICK_DEVIATION
+}
+
+
+#[test]
+fn take_the_median_tick_across_peers() {
+    assert_eq!(quorum_tick(&[], 0), None);
+    assert_eq!(quorum_tick(&[100], 1), Some(100));
+    assert_eq!(quorum_tick(&[100, 101], 5), None);
+    //One peer far in the future doesn't move the quorum
+    assert_eq!(quorum_tick(&[100, 4_000_000, 101], 5), Some(101));
+    assert_eq!(quorum_tick(&[100, 4_000_000, 101, 99], 5), Some(100));
+    assert!(is_tick_outlier(4_000_000, 101));
+    assert!(is_tick_outlier(50, 101));
+    assert!(!is_tick_outlier(99, 101));
+    assert_eq!(quorum_epoch(&[150, 151, 150], 3), Some(150));
+    assert_eq!(quorum_epoch(&[150, 151, 151], 3), Some(151));
+}
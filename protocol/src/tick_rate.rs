@@ -0,0 +1,69 @@
+//Fallback when we haven't seen enough ticks to measure the network's speed
+pub const DEFAULT_TICK_OFFSET: u32 = 15;
+pub const MIN_TICK_OFFSET: u32 = 5;
+pub const MAX_TICK_OFFSET: u32 = 60;
+//Roughly how long a transaction takes to reach the computors once we broadcast it
+pub const PROPAGATION_DELAY_MS: u64 = 15_000;
+pub const SAFETY_MARGIN_TICKS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickRateEstimate {
+    pub ms_per_tick: u64,
+    pub samples: usize
+}
+
+//samples are (tick, unix seconds we first saw it). Ticks backfilled after a newer tick was seen
+//carry the time we fetched them rather than when they happened, so they are skipped.
+pub fn estimate_tick_rate(samples: &[(u32, u64)]) -> Option<TickRateEstimate> {
+    let mut sorted: Vec<(u32, u64)> = samples.to_vec();
+    sorted.sort_by(|a, b| b.0.cmp(&a.0));
+    let mut live: Vec<(u32, u64)> = Vec::with_capacity(sorted.len());
+    for sample in sorted {
+        match live.last() {
+            Some(newer) if sample.1 > newer.1 => continue,
+            _ => live.push(sample)
+        }
+    }
+    if live.len() < 2 {
+        return None;
+    }
+    let (newest, oldest) = (live.first().unwrap(), live.last().unwrap());
+    let ticks: u64 = (newest.0 - oldest.0) as u64;
+    let seconds: u64 = newest.1 - oldest.1;
+    if ticks == 0 || seconds == 0 {
+        return None;
+    }
+    Some(TickRateEstimate {
+        ms_per_tick: (seconds * 1000 / ticks).max(1),
+        samples: live.len()
+    })
+}
+
+//How many ticks past the latest known tick a new transaction should target
+pub fn target_tick_offset(estimate: Option<&TickRateEstimate>) -> u32 {
+    match estimate {
+        Some(estimate) => {
+            let ticks: u64 = PROPAGATION_DELAY_MS.div_ceil(estimate.ms_per_tick);
+            (ticks.min(MAX_TICK_OFFSET as u64) as u32 + SAFETY_MARGIN_TICKS).clamp(MIN_TICK_OFFSET, MAX_TICK_OFFSET)
+        },
+        None => DEFAULT_TICK_OFFSET
+    }
+}
+
+
+#[test]
+fn estimate_tick_rate_and_offset() {
+    assert_eq!(estimate_tick_rate(&[]), None);
+    assert_eq!(estimate_tick_rate(&[(100, 1000)]), None);
+    assert_eq!(target_tick_offset(None), DEFAULT_TICK_OFFSET);
+
+    //100 ticks over 250 seconds, plus an old tick backfilled at the end which must be ignored
+    let samples = [(200, 1250), (150, 1125), (100, 1000), (50, 1300)];
+    let estimate = estimate_tick_rate(&samples).unwrap();
+    assert_eq!(estimate, TickRateEstimate { ms_per_tick: 2500, samples: 3 });
+    assert_eq!(target_tick_offset(Some(&estimate)), 6 + SAFETY_MARGIN_TICKS);
+
+    //Very fast and very slow networks stay within bounds
+    assert_eq!(target_tick_offset(Some(&TickRateEstimate { ms_per_tick: 10, samples: 2 })), MAX_TICK_OFFSET);
+    assert_eq!(target_tick_offset(Some(&TickRateEstimate { ms_per_tick: 60_000, samples: 2 })), MIN_TICK_OFFSET);
+}
@@ -0,0 +1,105 @@
+use crate::transfer::TransferTransaction;
+use crypto::hash::k12_bytes;
+use crypto::qubic_identities::{get_identity, verify};
+use crate::AsBytes;
+
+//A transaction as a peer relays it: the transfer header, its contract input payload and the signature over both
+#[derive(Debug, Clone)]
+pub struct TickTransaction {
+    pub tx: TransferTransaction,
+    pub input: Vec<u8>
+}
+
+impl TickTransaction {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 80 {
+            return Err("Transaction Too Short!".to_string());
+        }
+        let tx: TransferTransaction = TransferTransaction::from_bytes(bytes);
+        let input_end: usize = 80 + tx._input_size as usize;
+        if bytes.len() != input_end + 64 {
+            return Err(format!("Transaction Size Mismatch! Expected {} Bytes, Got {}", input_end + 64, bytes.len()));
+        }
+        Ok(TickTransaction {
+            input: bytes[80..input_end].to_vec(),
+            tx
+        })
+    }
+
+    pub fn as_bytes_without_signature(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.tx.as_bytes_without_signature();
+        bytes.extend_from_slice(self.input.as_slice());
+        bytes
+    }
+
+    pub fn verify(&self) -> bool {
+        let public_key: [u8; 32] = match self.tx._source_public_key.as_slice().try_into() {
+            Ok(key) => key,
+            Err(_) => return false
+        };
+        let signature: [u8; 64] = match self.tx._signature.as_slice().try_into() {
+            Ok(sig) => sig,
+            Err(_) => return false
+        };
+        let digest: [u8; 32] = k12_bytes(&self.as_bytes_without_signature()).try_into().unwrap();
+        verify(&public_key, &digest, &signature)
+    }
+
+    pub fn digest(&self) -> Vec<u8> {
+        k12_bytes(&self.as_bytes())
+    }
+
+    pub fn txid(&self) -> String {
+        let digest: [u8; 32] = self.digest().try_into().unwrap();
+        get_identity(&digest).to_lowercase()
+    }
+
+    pub fn source_identity(&self) -> String {
+        get_identity(&<[u8; 32]>::try_from(self.tx._source_public_key.as_slice()).unwrap())
+    }
+
+    pub fn destination_identity(&self) -> String {
+        get_identity(&<[u8; 32]>::try_from(self.tx._source_destination_public_key.as_slice()).unwrap())
+    }
+}
+
+impl AsBytes for TickTransaction {
+    fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.as_bytes_without_signature();
+        bytes.extend_from_slice(self.tx._signature.as_slice());
+        bytes
+    }
+}
+
+
+#[test]
+fn parse_and_verify_a_tick_transaction() {
+    use crate::identity::Identity;
+    use crypto::qubic_identities::{get_public_key_from_identity, get_subseed, sign_raw};
+
+    let id: Identity = Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+    let transfer: TransferTransaction = TransferTransaction::from_vars(&id, "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID", 100, 80);
+    let tx = TickTransaction::from_bytes(&transfer.as_bytes()).unwrap();
+    assert!(tx.input.is_empty());
+    assert!(tx.verify());
+    assert_eq!(tx.txid(), transfer.txid());
+    assert_eq!(tx.source_identity(), id.identity);
+
+    //Same transfer, carrying a contract input that the signature has to cover
+    let mut with_input: TickTransaction = TickTransaction { tx: transfer.clone(), input: vec![1, 2, 3, 4] };
+    with_input.tx._input_type = 1;
+    with_input.tx._input_size = 4;
+    let sub_seed = get_subseed(id.seed.as_str()).unwrap();
+    let pub_key = get_public_key_from_identity(&id.identity).unwrap();
+    let digest: [u8; 32] = k12_bytes(&with_input.as_bytes_without_signature()).try_into().unwrap();
+    with_input.tx._signature = sign_raw(&sub_seed, &pub_key, digest).to_vec();
+    let parsed = TickTransaction::from_bytes(&with_input.as_bytes()).unwrap();
+    assert_eq!(parsed.tx._input_type, 1);
+    assert_eq!(parsed.input, vec![1, 2, 3, 4]);
+    assert!(parsed.verify());
+
+    let mut tampered = parsed.as_bytes();
+    tampered[64] ^= 1;
+    assert!(!TickTransaction::from_bytes(&tampered).unwrap().verify());
+    assert!(TickTransaction::from_bytes(&tampered[..100]).is_err());
+}
@@ -1,8 +1,10 @@
 use crate::identity::Identity;
 use crypto::hash::k12_bytes;
+use crypto::secret::Secret;
 use crypto::qubic_identities::{get_subseed, get_public_key_from_identity, sign_raw, get_identity};
 use logger::info;
 use crate::AsBytes;
+use crate::tick_rate::DEFAULT_TICK_OFFSET;
 /*
     Helper Functions
 */
@@ -41,7 +43,6 @@ pub struct TransferTransaction {
     pub _signature: Vec<u8>
 }
 
-static TICK_OFFSET: u32 = 15;
 
 impl TransferTransaction {
 
@@ -65,6 +66,11 @@ impl TransferTransaction {
         }
     }
     pub fn from_vars(source_identity: &Identity, dest: &str, amount: u64, tick: u32) -> Self {
+        Self::from_vars_with_offset(source_identity, dest, amount, tick, DEFAULT_TICK_OFFSET)
+    }
+
+    //Targets tick + tick_offset, see tick_rate::target_tick_offset for choosing the offset
+    pub fn from_vars_with_offset(source_identity: &Identity, dest: &str, amount: u64, tick: u32, tick_offset: u32) -> Self {
         if source_identity.encrypted {
             panic!("Trying to Transfer From Encrypted Wallet!");
         }
@@ -83,15 +89,15 @@ impl TransferTransaction {
             _source_public_key: pub_key_src.to_vec(),
             _source_destination_public_key: pub_key_dest.to_vec(),
             _amount: amount,
-            _tick: tick + TICK_OFFSET,
+            _tick: tick + tick_offset,
             _input_type: 0,
             _input_size: 0,
             _signature: Vec::with_capacity(64)
         };
-        info!("Setting Expiration Tick For Transaction To {}", tick + TICK_OFFSET);
+        info!("Setting Expiration Tick For Transaction To {}", tick + tick_offset);
         let digest: Vec<u8> = k12_bytes(&t.as_bytes_without_signature());
         //let mut sub_seed: [u8; 32] = [0; 32];
-        let sub_seed: Vec<u8> = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
+        let sub_seed: Secret = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
         #[allow(unused_assignments)]
         let mut sig: [u8; 64] = [0; 64];
         sig = sign_raw(&sub_seed, &pub_key_src, digest.as_slice().try_into().unwrap());
@@ -102,9 +108,13 @@ impl TransferTransaction {
     pub fn digest(&self) -> Vec<u8> {
         k12_bytes(&self.as_bytes())
     }
+    //The signature follows any input payload, which is left for the caller to read
     pub fn from_bytes(bytes: &[u8]) -> Self {
-        let _signature = match bytes.len() > 80 {
-            true => { bytes[80..].to_vec() },
+        let _input_type = read_le_u16(&mut &bytes[76..]);
+        let _input_size = read_le_u16(&mut &bytes[78..]);
+        let signature_start: usize = 80 + _input_size as usize;
+        let _signature = match bytes.len() >= signature_start + 64 {
+            true => { bytes[signature_start..signature_start + 64].to_vec() },
             false => { Vec::<u8>::with_capacity(64) }
         };
 
@@ -113,8 +123,8 @@ impl TransferTransaction {
             _source_destination_public_key: bytes[32..64].to_vec(),
             _amount: read_le_u64(&mut &bytes[64..]),
             _tick: read_le_u32(&mut &bytes[72..]),
-            _input_size: read_le_u16(&mut &bytes[76..]),
-            _input_type: read_le_u16(&mut &bytes[78..]),
+            _input_type,
+            _input_size,
             _signature
         }
     }
@@ -0,0 +1,53 @@
+//Where a transfer we created is in its life, derived from its stored broadcast flag, status and target tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferLifecycle {
+    //Signed and stored, not yet taken by enough peers
+    Created,
+    //Taken by enough peers, its tick hasn't arrived
+    Broadcast,
+    //Its tick has arrived, waiting on tick data to check inclusion
+    PendingTick,
+    Included,
+    FailedNotIncluded,
+    //Never broadcast before its tick, or too old to check
+    Expired
+}
+
+impl TransferLifecycle {
+    //status is the transfer table's -1 unknown, 0 included, 1 not included
+    pub fn from_state(broadcast: bool, status: i32, target_tick: u32, latest_tick: u32, expired: bool) -> Self {
+        match status {
+            0 => TransferLifecycle::Included,
+            1 if expired => TransferLifecycle::Expired,
+            1 => TransferLifecycle::FailedNotIncluded,
+            _ if !broadcast && latest_tick >= target_tick => TransferLifecycle::Expired,
+            _ if !broadcast => TransferLifecycle::Created,
+            _ if latest_tick >= target_tick => TransferLifecycle::PendingTick,
+            _ => TransferLifecycle::Broadcast
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferLifecycle::Created => "created",
+            TransferLifecycle::Broadcast => "broadcast",
+            TransferLifecycle::PendingTick => "pending_tick",
+            TransferLifecycle::Included => "included",
+            TransferLifecycle::FailedNotIncluded => "failed_not_included",
+            TransferLifecycle::Expired => "expired"
+        }
+    }
+}
+
+
+#[test]
+fn derive_transfer_lifecycle() {
+    assert_eq!(TransferLifecycle::from_state(false, -1, 100, 90, false), TransferLifecycle::Created);
+    assert_eq!(TransferLifecycle::from_state(false, -1, 100, 100, false), TransferLifecycle::Expired);
+    assert_eq!(TransferLifecycle::from_state(true, -1, 100, 90, false), TransferLifecycle::Broadcast);
+    assert_eq!(TransferLifecycle::from_state(true, -1, 100, 101, false), TransferLifecycle::PendingTick);
+    assert_eq!(TransferLifecycle::from_state(true, 0, 100, 150, false), TransferLifecycle::Included);
+    assert_eq!(TransferLifecycle::from_state(true, 1, 100, 150, false), TransferLifecycle::FailedNotIncluded);
+    assert_eq!(TransferLifecycle::from_state(true, 1, 100, 50000, true), TransferLifecycle::Expired);
+    assert_eq!(TransferLifecycle::PendingTick.as_str(), "pending_tick");
+}
@@ -1,63 +1,137 @@
-use std::ptr::copy_nonoverlapping;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use once_cell::sync::Lazy;
-//use secrets;
-//use secrets::traits::Zeroable;
+use crypto::secret::Secret;
 use logger::{error, info};
 
-//Convenient Secret stored in secure memory for temporary unlock of the db
-/*pub static PLAINTEXT_DECRYPT_PASSWORD: Lazy<Arc<Mutex<secrets::SecretVec<u8>>>> = Lazy::new(|| {
-    Arc::new(Mutex::new(secrets::SecretVec::zero(64)))
-});*/
-pub static PLAINTEXT_DECRYPT_PASSWORD: Lazy<Arc<Mutex<Vec<u8>>>> = Lazy::new(|| {
-    Arc::new(Mutex::new([0u8; 64].to_vec()))
-});
+//Unlocked password plus its expiry. Every unlock bumps the generation so that
+//lock timers left over from an earlier session know to exit without locking.
+pub struct UnlockSession {
+    password: Secret,
+    timeout: Duration,
+    expires_at: Instant,
+    generation: u64
+}
 
-fn do_vecs_match<T: PartialEq>(a: &Vec<T>, b: &Vec<T>) -> bool {
-    let matching = a.iter().zip(b.iter()).filter(|&(a, b)| a == b).count();
-    matching == a.len() && matching == b.len()
+impl UnlockSession {
+    pub fn remaining(&self) -> Duration { self.expires_at.saturating_duration_since(Instant::now()) }
+    pub fn timeout(&self) -> Duration { self.timeout }
 }
 
+//Convenient Secret stored in locked memory for temporary unlock of the db, None while locked
+pub static WALLET_SESSION: Lazy<Arc<Mutex<Option<UnlockSession>>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(None))
+});
+static SESSION_GENERATION: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
+
 pub fn is_wallet_unlocked() -> Result<bool, ()> {
-    let plaintext = PLAINTEXT_DECRYPT_PASSWORD.clone();
-    let ret_val = match plaintext.lock() {
-        Ok(pass_guard) => {
-            let zero_pass: [u8; 64] = [0u8; 64];
-            if do_vecs_match(&pass_guard, &zero_pass.to_vec()) {
-                Ok(false)
-            } else {
-                Ok(true)
-            }
-            /*
-            let current_pass = pass_guard.borrow();
-            if *current_pass != zero_pass {
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+    match WALLET_SESSION.lock() {
+        Ok(session) => Ok(session.as_ref().is_some_and(|s| s.expires_at > Instant::now())),
+        Err(_) => Err(())
+    }
+}
 
-             */
+//Fetching the password counts as activity and slides the session expiry forward
+pub fn get_plaintext_password() -> Result<Secret, ()> {
+    match WALLET_SESSION.lock() {
+        Ok(mut session) => match session.as_mut() {
+            Some(s) if s.expires_at > Instant::now() => {
+                s.expires_at = Instant::now() + s.timeout;
+                Ok(s.password.clone())
+            },
+            _ => Err(())
         },
         Err(_) => Err(())
-    };
-    ret_val
+    }
 }
 
-pub fn get_plaintext_password() -> Result<String, ()> {
-    if !is_wallet_unlocked().unwrap() {
-        return Err(());
+//Same as get_plaintext_password without sliding the expiry, so background work can't keep an idle wallet unlocked
+pub fn peek_plaintext_password() -> Result<Secret, ()> {
+    match WALLET_SESSION.lock() {
+        Ok(session) => match session.as_ref() {
+            Some(s) if s.expires_at > Instant::now() => Ok(s.password.clone()),
+            _ => Err(())
+        },
+        Err(_) => Err(())
     }
-    match PLAINTEXT_DECRYPT_PASSWORD.clone().lock() {
-        Ok(secret) => unsafe {
-            //let s = secret.borrow();
-            let s = secret;
-            let pass = std::str::from_utf8_unchecked(& *s);
-            Ok(pass.to_string())                           
+}
+
+//Remaining time and configured timeout of the current session, None while locked
+pub fn get_session_expiry() -> Result<Option<(Duration, Duration)>, ()> {
+    match WALLET_SESSION.lock() {
+        Ok(session) => match session.as_ref() {
+            Some(s) if s.expires_at > Instant::now() => Ok(Some((s.remaining(), s.timeout()))),
+            _ => Ok(None)
         },
         Err(_) => Err(())
     }
 }
+
+pub fn extend_session() -> Result<Duration, String> {
+    match WALLET_SESSION.lock() {
+        Ok(mut session) => match session.as_mut() {
+            Some(s) if s.expires_at > Instant::now() => {
+                s.expires_at = Instant::now() + s.timeout;
+                Ok(s.timeout)
+            },
+            _ => Err("Wallet Locked.".to_string())
+        },
+        Err(e) => Err(format!("Failed To Extend Wallet Session: {}", e))
+    }
+}
+
+pub fn lock_wallet() -> Result<String, String> {
+    match WALLET_SESSION.lock() {
+        Ok(mut session) => {
+            //Dropping the session zeroes the password, the pending timer sees it is gone and exits
+            match session.take() {
+                Some(_) => {
+                    info!("Wallet Locked.");
+                    Ok("Wallet Locked".to_string())
+                },
+                None => Ok("Wallet Already Locked".to_string())
+            }
+        },
+        Err(e) => {
+            error!("Failed to Lock Wallet: {}", e);
+            Err(format!("Failed to Lock Wallet: {}", e))
+        }
+    }
+}
+
+fn next_generation() -> u64 {
+    let mut g = SESSION_GENERATION.lock().unwrap();
+    *g += 1;
+    *g
+}
+
+//name is what gets logged as locked, "Wallet" or "Scheduler"
+fn spawn_lock_timer(sessions: &'static Mutex<Option<UnlockSession>>, generation: u64, name: &'static str) {
+    std::thread::spawn(move || {
+        loop {
+            let wait = match sessions.lock() {
+                Ok(mut session) => match session.as_ref() {
+                    Some(s) if s.generation == generation => {
+                        if s.expires_at <= Instant::now() {
+                            *session = None;
+                            info!("{} Locked.", name);
+                            return;
+                        }
+                        s.remaining()
+                    },
+                    //Locked early or superseded by a newer unlock
+                    _ => return
+                },
+                Err(e) => {
+                    error!("Failed to Lock {}: {}", name, e);
+                    return;
+                }
+            };
+            std::thread::sleep(wait);
+        }
+    });
+}
+
 pub fn unlock_wallet(master_password: &str, password: &str, timeout_ms: Duration) -> Result<String, String> {
     match crypto::passwords::verify_password(password, master_password) {
         Ok(verified) => {
@@ -65,47 +139,23 @@ pub fn unlock_wallet(master_password: &str, password: &str, timeout_ms: Duration
                 error!("Failed To Unlock Wallet. Invalid Password");
                 Err("Failed To Unlock Wallet. Invalid Password".to_string())
             } else {
-                let plaintext = PLAINTEXT_DECRYPT_PASSWORD.clone();
-                match plaintext.lock() {
-                    Ok(pass_guard) => {
-                        let zero_pass: [u8; 64] = [0u8; 64];
-                        //let mut current_pass = pass_guard.borrow_mut();
-                        let mut current_pass = pass_guard;
-                        if *current_pass != zero_pass {
-                            error!("Failed To Unlock Wallet. Wallet Already Unlocked.");
-                            return Err("Failed To Unlock Wallet. Wallet Already Unlocked".to_string());
-                        }
-                        unsafe {
-                            //current_pass.zero();
-                            copy_nonoverlapping(zero_pass.as_ptr(), current_pass.as_mut_ptr(), 64);
-                            //copy_nonoverlapping(password.as_ptr(), current_pass.as_mut().as_mut_ptr(), password.len());
-                            copy_nonoverlapping(password.as_ptr(), current_pass.as_mut_ptr(), password.len());
-                        }
+                let generation = next_generation();
+                match WALLET_SESSION.lock() {
+                    Ok(mut session) => {
+                        //Re-unlocking replaces the running session and restarts its timeout
+                        *session = Some(UnlockSession {
+                            password: Secret::from(password),
+                            timeout: timeout_ms,
+                            expires_at: Instant::now() + timeout_ms,
+                            generation
+                        });
                     },
                     Err(e) => {
                         error!("Failed to Unlock Wallet: {}", e);
                         return Err(format!("Failed to Unlock Wallet: {}", e));
                     }
                 }
-                std::thread::spawn(move || {
-                    std::thread::sleep(timeout_ms);
-                    let p = PLAINTEXT_DECRYPT_PASSWORD.clone();
-                    match p.lock() {
-                        Ok(pass) => {
-                            //let mut _pass = pass.borrow_mut();
-                            let mut _pass = pass;
-                            let zero_pass: [u8; 64] = [0u8; 64];
-                            //_pass.zero();
-                            unsafe {
-                                copy_nonoverlapping(zero_pass.as_ptr(), _pass.as_mut_ptr(), 64);
-                            }
-                            info!("Wallet Locked.");
-                        },
-                        Err(e) => {
-                            error!("Failed to Unlock Wallet: {}", e);
-                        }
-                    };
-                });
+                spawn_lock_timer(&WALLET_SESSION, generation, "Wallet");
                 info!("Wallet Unlocked For {} ms", timeout_ms.as_millis());
                 Ok("Wallet Unlocked".to_string())
             }
@@ -115,4 +165,117 @@ pub fn unlock_wallet(master_password: &str, password: &str, timeout_ms: Duration
             Err("Failed To Unlock Wallet. Incorrect Password!".to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+//Password for the dedicated keys on scheduled payments. It is separate from the wallet session, so schedules keep
+//paying while the wallet stays locked, and it can only decrypt the keys made for schedules. It times out like the
+//wallet session, but the scheduler using it doesn't count as activity.
+static SCHEDULER_SESSION: Lazy<Mutex<Option<UnlockSession>>> = Lazy::new(|| Mutex::new(None));
+
+//key_hashes are the password hashes of the dedicated keys, the password must open at least one of them
+pub fn unlock_scheduler(key_hashes: &[String], password: &str, timeout_ms: Duration) -> Result<String, String> {
+    if key_hashes.is_empty() {
+        return Err("No Scheduled Payment Has A Dedicated Key!".to_string());
+    }
+    if !key_hashes.iter().any(|hash| crypto::passwords::verify_password(password, hash.as_str()).unwrap_or(false)) {
+        error!("Failed To Unlock Scheduler. Invalid Password");
+        return Err("Failed To Unlock Scheduler. Invalid Password".to_string());
+    }
+    let generation = next_generation();
+    match SCHEDULER_SESSION.lock() {
+        Ok(mut scheduler) => {
+            *scheduler = Some(UnlockSession {
+                password: Secret::from(password),
+                timeout: timeout_ms,
+                expires_at: Instant::now() + timeout_ms,
+                generation
+            });
+        },
+        Err(e) => return Err(format!("Failed to Unlock Scheduler: {}", e))
+    }
+    spawn_lock_timer(&SCHEDULER_SESSION, generation, "Scheduler");
+    info!("Scheduler Unlocked For {} ms", timeout_ms.as_millis());
+    Ok("Scheduler Unlocked".to_string())
+}
+
+pub fn lock_scheduler() -> Result<(), String> {
+    match SCHEDULER_SESSION.lock() {
+        Ok(mut scheduler) => {
+            if scheduler.take().is_some() {
+                info!("Scheduler Locked.");
+            }
+            Ok(())
+        },
+        Err(e) => Err(format!("Failed to Lock Scheduler: {}", e))
+    }
+}
+
+pub fn get_scheduler_password() -> Result<Secret, ()> {
+    match SCHEDULER_SESSION.lock() {
+        Ok(scheduler) => match scheduler.as_ref() {
+            Some(s) if s.expires_at > Instant::now() => Ok(s.password.clone()),
+            _ => Err(())
+        },
+        Err(_) => Err(())
+    }
+}
+
+#[cfg(test)]
+mod wallet_unlock_tests {
+    use std::time::Duration;
+    use crate::wallet_unlock::{extend_session, get_plaintext_password, get_scheduler_password, get_session_expiry, is_wallet_unlocked, lock_scheduler, lock_wallet, peek_plaintext_password, unlock_scheduler, unlock_wallet};
+
+    //Session state is global, so everything runs in one test
+    #[test]
+    fn unlock_relock_extend_and_lock_a_wallet() {
+        let master = crypto::passwords::hash_password("password").unwrap();
+        assert!(unlock_wallet(master.as_str(), "wrongpassword", Duration::from_millis(1000)).is_err());
+        assert_eq!(is_wallet_unlocked().unwrap(), false);
+
+        unlock_wallet(master.as_str(), "password", Duration::from_millis(300)).unwrap();
+        assert_eq!(is_wallet_unlocked().unwrap(), true);
+        assert_eq!(get_plaintext_password().unwrap().as_str(), "password");
+
+        //Unlocking again replaces the session instead of failing
+        unlock_wallet(master.as_str(), "password", Duration::from_millis(60_000)).unwrap();
+        let (remaining, timeout) = get_session_expiry().unwrap().unwrap();
+        assert_eq!(timeout, Duration::from_millis(60_000));
+        assert!(remaining > Duration::from_millis(30_000));
+        //The first session's timer must not lock the second session
+        std::thread::sleep(Duration::from_millis(500));
+        assert_eq!(is_wallet_unlocked().unwrap(), true);
+        assert_eq!(extend_session().unwrap(), Duration::from_millis(60_000));
+
+        assert_eq!(lock_wallet().unwrap().as_str(), "Wallet Locked");
+        assert_eq!(is_wallet_unlocked().unwrap(), false);
+        assert!(get_plaintext_password().is_err());
+        assert!(get_session_expiry().unwrap().is_none());
+        assert!(extend_session().is_err());
+
+        //Peeking doesn't slide the expiry, so the session still times out
+        unlock_wallet(master.as_str(), "password", Duration::from_millis(200)).unwrap();
+        assert_eq!(peek_plaintext_password().unwrap().as_str(), "password");
+        std::thread::sleep(Duration::from_millis(600));
+        assert_eq!(is_wallet_unlocked().unwrap(), false);
+        assert!(peek_plaintext_password().is_err());
+    }
+
+    #[test]
+    fn unlock_and_time_out_the_scheduler() {
+        let key_hashes = vec![crypto::passwords::hash_password("schedules").unwrap()];
+        assert!(unlock_scheduler(&[], "schedules", Duration::from_millis(1000)).is_err());
+        //A typo is refused up front instead of failing every scheduled payment later
+        assert!(unlock_scheduler(&key_hashes, "schedulez", Duration::from_millis(1000)).is_err());
+        assert!(get_scheduler_password().is_err());
+
+        unlock_scheduler(&key_hashes, "schedules", Duration::from_millis(200)).unwrap();
+        assert_eq!(get_scheduler_password().unwrap().as_str(), "schedules");
+        //Using it doesn't keep it unlocked
+        std::thread::sleep(Duration::from_millis(600));
+        assert!(get_scheduler_password().is_err());
+
+        unlock_scheduler(&key_hashes, "schedules", Duration::from_millis(60_000)).unwrap();
+        lock_scheduler().unwrap();
+        assert!(get_scheduler_password().is_err());
+    }
+}
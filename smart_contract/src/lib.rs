@@ -1 +1,2 @@
-pub mod qx;
\ No newline at end of file
+pub mod qx;
+pub mod qutil;
@@ -0,0 +1,14 @@
+pub const QUTIL_ADDRESS: &str = "EAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAVWRF";
+
+pub const QUTIL_CONTRACT_INDEX: u32 = 4;
+
+//Recipients one SendToManyV1 call can pay, and the fee the contract keeps per call
+pub const SEND_TO_MANY_MAX_RECIPIENTS: usize = 25;
+pub const SEND_TO_MANY_FEE: u64 = 10;
+
+#[derive(Debug)]
+pub enum QutilProcedure {
+    SendToManyV1 = 1
+}
+
+pub mod send_many;
@@ -0,0 +1,209 @@
+use protocol::AsBytes;
+use protocol::transfer::TransferTransaction;
+use crypto::hash::k12_bytes;
+use crypto::secret::Secret;
+use crypto::qubic_identities::{get_identity, get_public_key_from_identity, get_subseed, sign_raw};
+use protocol::identity::Identity;
+pub use crate::qutil::{QutilProcedure, QUTIL_ADDRESS, SEND_TO_MANY_FEE, SEND_TO_MANY_MAX_RECIPIENTS};
+
+
+//IMPL
+//All destinations first, then all amounts. Unused slots stay zeroed.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct SendManyInput {
+    pub destinations: [[u8; 32]; SEND_TO_MANY_MAX_RECIPIENTS],
+    pub amounts: [i64; SEND_TO_MANY_MAX_RECIPIENTS]
+}
+
+impl SendManyInput {
+    pub fn from_recipients(recipients: &[(String, u64)]) -> Result<Self, String> {
+        if recipients.is_empty() {
+            return Err("No Recipients!".to_string());
+        }
+        if recipients.len() > SEND_TO_MANY_MAX_RECIPIENTS {
+            return Err(format!("Too Many Recipients! A Send Many Call Takes At Most {}", SEND_TO_MANY_MAX_RECIPIENTS));
+        }
+        let mut input = SendManyInput {
+            destinations: [[0u8; 32]; SEND_TO_MANY_MAX_RECIPIENTS],
+            amounts: [0i64; SEND_TO_MANY_MAX_RECIPIENTS]
+        };
+        for (index, (destination, amount)) in recipients.iter().enumerate() {
+            input.destinations[index] = match get_public_key_from_identity(destination) {
+                Ok(pub_key) => pub_key,
+                Err(_) => return Err(format!("Invalid Recipient Identity {}", destination))
+            };
+            if *amount == 0 || *amount > i64::MAX as u64 {
+                return Err(format!("Invalid Amount For Recipient {}", destination));
+            }
+            input.amounts[index] = *amount as i64;
+        }
+        Ok(input)
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        for destination in self.destinations.iter() {
+            bytes.extend_from_slice(destination);
+        }
+        for amount in self.amounts.iter() {
+            bytes.extend_from_slice(&amount.to_le_bytes());
+        }
+        bytes
+    }
+
+    //None when the amounts don't fit a u64 together
+    pub fn total(&self) -> Option<u64> {
+        self.amounts.iter().try_fold(0u64, |total, amount| total.checked_add(*amount as u64))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct SendManyTransaction {
+    pub tx: TransferTransaction,
+    pub send_many_tx: SendManyInput,
+    pub _signature: Vec<u8>
+}
+
+impl SendManyTransaction {
+    pub fn from_signed_data(tx: TransferTransaction, recipients: &[(String, u64)], sig: &[u8]) -> Result<Self, String> {
+        Ok(SendManyTransaction {
+            tx,
+            send_many_tx: SendManyInput::from_recipients(recipients)?,
+            _signature: sig.to_vec()
+        })
+    }
+
+    //The transfer amount is what the recipients get plus the contract's fee
+    pub fn from_vars(source_identity: &Identity, recipients: &[(String, u64)], tick: u32, tick_offset: u32) -> Result<Self, String> {
+        if source_identity.encrypted {
+            return Err("Trying to Transfer From Encrypted Wallet!".to_string());
+        }
+        if source_identity.seed.len() != 55 {
+            return Err("Trying To Transfer From Corrupted Identity!".to_string());
+        }
+        let pub_key_src = match get_public_key_from_identity(&source_identity.identity) {
+            Ok(pub_key) => pub_key,
+            Err(err) => return Err(format!("{:?}", err))
+        };
+        let input = SendManyInput::from_recipients(recipients)?;
+        let tx_amount: u64 = match input.total().and_then(|total| total.checked_add(SEND_TO_MANY_FEE)) {
+            Some(amount) => amount,
+            None => return Err("Payout Total Overflows!".to_string())
+        };
+        let mut tx: TransferTransaction = TransferTransaction::from_vars_with_offset(
+            source_identity,
+            QUTIL_ADDRESS,
+            tx_amount,
+            tick,
+            tick_offset
+        );
+        tx._input_type = QutilProcedure::SendToManyV1 as u16;
+        tx._input_size = size_of::<SendManyInput>() as u16;
+
+        let mut pre_image: Vec<u8> = tx.as_bytes_without_signature();
+        pre_image.append(&mut input.as_bytes());
+        let hash = k12_bytes(&pre_image);
+
+        let sub_seed: Secret = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
+        let sig: [u8; 64] = sign_raw(&sub_seed, &pub_key_src, hash.as_slice().try_into().unwrap());
+        Ok(SendManyTransaction {
+            tx,
+            send_many_tx: input,
+            _signature: sig.to_vec()
+        })
+    }
+
+    pub fn digest(&self) -> Vec<u8> {
+        k12_bytes(&self.as_bytes())
+    }
+
+    pub fn as_bytes_without_signature(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.tx.as_bytes_without_signature();
+        bytes.extend_from_slice(self.send_many_tx.as_bytes().as_slice());
+        bytes
+    }
+
+    pub fn txid(&self) -> String {
+        let digest: [u8; 32] = k12_bytes(&self.as_bytes()).try_into().unwrap();
+        get_identity(&digest).to_lowercase()
+    }
+}
+
+impl AsBytes for SendManyTransaction {
+    fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.as_bytes_without_signature();
+        bytes.extend_from_slice(self._signature.as_slice());
+        bytes
+    }
+}
+
+//One "identity,amount" pair per line. Blank lines, # comments and a header row are skipped.
+pub fn parse_recipients_csv(csv: &str) -> Result<Vec<(String, u64)>, String> {
+    let mut recipients: Vec<(String, u64)> = Vec::new();
+    for (line_number, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if fields.len() != 2 {
+            return Err(format!("Line {}: Expected identity,amount", line_number + 1));
+        }
+        let amount: u64 = match fields[1].parse() {
+            Ok(amount) => amount,
+            Err(_) if line_number == 0 => continue,
+            Err(_) => return Err(format!("Line {}: Invalid Amount {}", line_number + 1, fields[1]))
+        };
+        let identity: String = fields[0].to_uppercase();
+        if identity.len() != 60 || get_public_key_from_identity(&identity).is_err() {
+            return Err(format!("Line {}: Invalid Identity {}", line_number + 1, fields[0]));
+        }
+        if amount == 0 {
+            return Err(format!("Line {}: Amount Must Be Greater Than 0", line_number + 1));
+        }
+        recipients.push((identity, amount));
+    }
+    if recipients.is_empty() {
+        return Err("No Recipients Found!".to_string());
+    }
+    Ok(recipients)
+}
+
+
+#[test]
+fn build_a_send_many_call_from_csv() {
+    use crate::qutil::QUTIL_CONTRACT_INDEX;
+    use protocol::tick_transaction::TickTransaction;
+
+    let mut contract_key: [u8; 32] = [0u8; 32];
+    contract_key[0] = QUTIL_CONTRACT_INDEX as u8;
+    assert_eq!(get_identity(&contract_key), QUTIL_ADDRESS);
+
+    let csv = "identity,amount\n\
+        EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON, 100\n\
+        \n\
+        # second payee\n\
+        BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID,250\n";
+    let recipients = parse_recipients_csv(csv).unwrap();
+    assert_eq!(recipients.len(), 2);
+    assert_eq!(recipients[1].1, 250);
+    assert!(parse_recipients_csv("EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWOM,1").is_err());
+    assert!(parse_recipients_csv("identity,amount\nEPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON,abc").is_err());
+
+    let id: Identity = Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+    let send_many = SendManyTransaction::from_vars(&id, &recipients, 1000, 10).unwrap();
+    assert_eq!(send_many.tx._amount, 350 + SEND_TO_MANY_FEE);
+    assert_eq!(send_many.tx._input_size as usize, 25 * 32 + 25 * 8);
+
+    //Peers relay it as a plain transaction with an input, which must parse and verify the same way
+    let relayed = TickTransaction::from_bytes(&send_many.as_bytes()).unwrap();
+    assert!(relayed.verify());
+    assert_eq!(relayed.txid(), send_many.txid());
+
+    let too_many: Vec<(String, u64)> = vec![(QUTIL_ADDRESS.to_string(), 1); SEND_TO_MANY_MAX_RECIPIENTS + 1];
+    assert!(SendManyTransaction::from_vars(&id, &too_many, 1000, 10).is_err());
+    let overflowing: Vec<(String, u64)> = vec![(QUTIL_ADDRESS.to_string(), i64::MAX as u64); 3];
+    assert!(SendManyTransaction::from_vars(&id, &overflowing, 1000, 10).is_err());
+}
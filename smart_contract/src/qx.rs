@@ -1,3 +1,4 @@
+//Mainnet address, transactions are built against protocol::network_profile
 pub const QX_ADDRESS: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
 
 pub const QX_CONTRACT_INDEX: u32 = 1;
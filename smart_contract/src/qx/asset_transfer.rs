@@ -1,11 +1,14 @@
 use protocol::identity::Identity;
 use crypto::hash::k12_bytes;
+use crypto::secret::Secret;
 use crypto::qubic_identities::{get_subseed, get_public_key_from_identity, sign_raw, get_identity};
 use protocol::AsBytes;
 use protocol::transfer::TransferTransaction;
-use crate::qx::QX_ADDRESS;
+use protocol::network_profile::get_network_profile;
 
 pub const QX_TRANSFER_SHARE: u16 = 2;
+//QU paid to QX with every share transfer
+pub const QX_TRANSFER_SHARE_FEE: u64 = 1000000;
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
@@ -72,7 +75,7 @@ impl AssetTransferTransaction {
         }
     }
 
-    pub fn from_vars(source_identity: &Identity, asset_name: &str, issuer: &str, dest: &str, amount: i64, tick: u32) -> Self {
+    pub fn from_vars(source_identity: &Identity, asset_name: &str, issuer: &str, dest: &str, amount: i64, tick: u32, tick_offset: u32) -> Self {
         if source_identity.encrypted {
             panic!("Trying to Transfer From Encrypted Wallet!");
         }
@@ -83,11 +86,12 @@ impl AssetTransferTransaction {
             Ok(pub_key) => pub_key,
             Err(err) => panic!("{:?}", err)
         };
-        let mut tx: TransferTransaction = TransferTransaction::from_vars(
+        let mut tx: TransferTransaction = TransferTransaction::from_vars_with_offset(
             source_identity,
-            QX_ADDRESS,
-            1000000u64,
-            tick
+            get_network_profile().qx_address.as_str(),
+            QX_TRANSFER_SHARE_FEE,
+            tick,
+            tick_offset
         );
 
         tx._input_type = QX_TRANSFER_SHARE;
@@ -112,7 +116,7 @@ impl AssetTransferTransaction {
 
         let hash = k12_bytes(&pre_image);
 
-        let sub_seed: Vec<u8> = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
+        let sub_seed: Secret = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
         #[allow(unused_assignments)]
         let mut sig: [u8; 64] = [0; 64];
         sig = sign_raw(&sub_seed, &pub_key_src, hash.as_slice().try_into().unwrap());
@@ -1,10 +1,20 @@
 use protocol::AsBytes;
 use protocol::transfer::TransferTransaction;
 use crypto::hash::k12_bytes;
+use crypto::secret::Secret;
 use crypto::qubic_identities::{get_identity, get_public_key_from_identity, get_subseed, sign_raw};
 use protocol::identity::Identity;
+use protocol::network_profile::get_network_profile;
 pub use crate::qx::{QxProcedure, QX_ADDRESS};
 
+//QU sent with an order, a bid escrows price * shares, anything else pays QX a fee of 1. None on overflow.
+pub fn order_amount(procedure: &QxProcedure, price: u64, shares: u64) -> Option<u64> {
+    match procedure {
+        QxProcedure::QxAddBidOrder => price.checked_mul(shares),
+        _ => Some(1u64)
+    }
+}
+
 
 
 
@@ -72,7 +82,7 @@ impl QxOrderTransaction {
         }
     }
 
-    pub fn from_vars(procedure: QxProcedure, source_identity: &Identity, asset_name: &str, issuer: &str, price: u64, amount: u64, tick: u32) -> Self {
+    pub fn from_vars(procedure: QxProcedure, source_identity: &Identity, asset_name: &str, issuer: &str, price: u64, amount: u64, tick: u32, tick_offset: u32) -> Self {
         if source_identity.encrypted {
             panic!("Trying to Transfer From Encrypted Wallet!");
         }
@@ -83,15 +93,13 @@ impl QxOrderTransaction {
             Ok(pub_key) => pub_key,
             Err(err) => panic!("{:?}", err)
         };
-        let tx_amount: u64 = match procedure {
-            QxProcedure::QxAddBidOrder  => price * amount,
-            _ => 1u64
-        };
-        let mut tx: TransferTransaction = TransferTransaction::from_vars(
+        let tx_amount: u64 = order_amount(&procedure, price, amount).expect("QX Bid Escrow Overflows!");
+        let mut tx: TransferTransaction = TransferTransaction::from_vars_with_offset(
             source_identity,
-            QX_ADDRESS,
+            get_network_profile().qx_address.as_str(),
             tx_amount,
-            tick
+            tick,
+            tick_offset
         );
         tx._input_type = procedure as u16;
         tx._input_size = size_of::<QxOrderActionInput>() as u16;
@@ -115,7 +123,7 @@ impl QxOrderTransaction {
 
         let hash = k12_bytes(&pre_image);
 
-        let sub_seed: Vec<u8> = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
+        let sub_seed: Secret = get_subseed(source_identity.seed.as_str()).expect("Failed To Get SubSeed!");
         #[allow(unused_assignments)]
         let mut sig: [u8; 64] = [0; 64];
         sig = sign_raw(&sub_seed, &pub_key_src, hash.as_slice().try_into().unwrap());
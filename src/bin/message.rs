@@ -0,0 +1,58 @@
+//Sign or verify identity ownership messages against the wallet database, without starting the server.
+//  rubic-message sign <identity> <password> <message>
+//  rubic-message verify <identity> <signature> <message>
+//The password may be empty ("") for an unencrypted wallet. A message of "-" is read from stdin.
+use std::io::Read;
+use rubic::routes::message::{sign_message_with_identity, unlock_signing_identity, verify_identity_message};
+
+fn usage() -> ! {
+    eprintln!("Usage:\n  rubic-message sign <identity> <password> <message>\n  rubic-message verify <identity> <signature> <message>");
+    std::process::exit(2);
+}
+
+fn read_message(arg: &str) -> String {
+    if arg != "-" {
+        return arg.to_string();
+    }
+    let mut message = String::new();
+    if let Err(err) = std::io::stdin().read_to_string(&mut message) {
+        eprintln!("Failed To Read Message From Stdin: {}", err);
+        std::process::exit(1);
+    }
+    message
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 5 {
+        usage();
+    }
+    let message = read_message(args[4].as_str());
+    match args[1].as_str() {
+        "sign" => {
+            let signature = unlock_signing_identity(args[2].as_str(), args[3].as_str())
+                .and_then(|identity| sign_message_with_identity(&identity, message.as_bytes()));
+            match signature {
+                Ok(signature) => println!("{}", signature),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        },
+        "verify" => {
+            match verify_identity_message(args[2].as_str(), message.as_bytes(), args[3].as_str()) {
+                Ok(true) => println!("Valid"),
+                Ok(false) => {
+                    println!("Invalid");
+                    std::process::exit(1);
+                },
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        },
+        _ => usage()
+    }
+}
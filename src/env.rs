@@ -67,4 +67,41 @@ pub fn get_max_peers() -> usize {
             return default_max_peers;
         }
     }
-}
\ No newline at end of file
+}
+
+//Light client sync walks and verifies the tick chain, off unless RUBIC_LIGHT_SYNC=true
+pub fn get_light_sync() -> bool {
+    dotenv().ok();
+    match std::env::var("RUBIC_LIGHT_SYNC") {
+        Ok(v) => {
+            match v.to_lowercase().parse::<bool>() {
+                Ok(value) => value,
+                Err(err) => {
+                    println!("Invalid RUBIC_LIGHT_SYNC in env vars. ({}) -> {:?} Defaulting...", v.as_str(), err);
+                    false
+                }
+            }
+        },
+        Err(_) => {
+            debug!("RUBIC_LIGHT_SYNC not found in env vars! Defaulting...");
+            false
+        }
+    }
+}
+
+//Tick the light client sync starts from, the current epoch's initial tick when not set
+pub fn get_light_sync_start() -> Option<u32> {
+    dotenv().ok();
+    match std::env::var("RUBIC_LIGHT_SYNC_START") {
+        Ok(v) => {
+            match v.parse::<u32>() {
+                Ok(value) => Some(value),
+                Err(err) => {
+                    println!("Invalid RUBIC_LIGHT_SYNC_START in env vars. ({}) -> {:?} Ignoring...", v.as_str(), err);
+                    None
+                }
+            }
+        },
+        Err(_) => None
+    }
+}
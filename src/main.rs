@@ -42,6 +42,13 @@ async fn main() {
   println!("Warning! This software comes with no warranty, real or implied. Secure storage of seeds and passwords is paramount; total loss of funds may ensue otherwise.");
   info("Warning! This software comes with no warranty, real or implied. Secure storage of seeds and passwords is paramount; total loss of funds may ensue otherwise.");
     
+  let network = protocol::network_profile::get_network_profile();
+  println!("Network: {} ({} Bootstrap Peers On Port {})", network.name, network.bootstrap_peers.len(), network.peer_port);
+  info(format!("Network Profile: {:?}", network).as_str());
+  if network.bootstrap_peers.is_empty() {
+      println!("Warning! No Bootstrap Peers For Network {}, Set RUBIC_BOOTSTRAP_PEERS", network.name);
+  }
+    
   let path = store::get_db_path();
   match sqlite::create::open_database(path.as_str(), true) {
       Ok(_) => info!("Database successfully opened"),
@@ -130,6 +137,7 @@ async fn main() {
         routes::asset::transfer,
 
         routes::identity::balance,
+        routes::identity::available_balance,
         routes::identity::add_identity,
         routes::identity::add_identity_with_password,
         routes::identity::create_random_identity,
@@ -137,12 +145,28 @@ async fn main() {
         routes::identity::get_identities,
         routes::identity::get_identity_from_seed,
 
+        routes::vanity::start_vanity_search,
+        routes::vanity::fetch_vanity_searches,
+        routes::vanity::fetch_vanity_search,
+        routes::vanity::cancel_vanity_search,
+
+        routes::message::sign_message,
+        routes::message::verify_message,
+
         routes::info::info,
         routes::info::latest_tick,
+        routes::info::tick_transactions,
+        routes::info::tick_rate,
+        routes::info::epochs,
+        routes::info::light_sync,
+        routes::info::tick_quorum,
+        routes::info::tick_data,
+        routes::info::tick_fees,
 
         routes::peer::peers,
         routes::peer::add_peer,
         routes::peer::delete_peer,
+        routes::peer::peer_ticks,
         routes::peer::get_peer_limit,
         routes::peer::set_peer_limit,
           
@@ -150,7 +174,37 @@ async fn main() {
         routes::qx::get_orderbook,  
         routes::qx::place_order,  
 
+        routes::payout::fetch_payout,
+        routes::payout::create_payout,
+
+        routes::contact::fetch_contacts,
+        routes::contact::fetch_contact,
+        routes::contact::add_contact,
+        routes::contact::update_contact_notes,
+        routes::contact::delete_contact,
+
+        routes::policy::fetch_policy,
+        routes::policy::set_policy,
+        routes::policy::delete_policy,
+        routes::policy::allow_destination,
+        routes::policy::disallow_destination,
+        routes::policy::confirm_transfer,
+        routes::policy::set_resend_policy,
+
+        routes::schedule::fetch_schedules,
+        routes::schedule::fetch_schedule_runs,
+        routes::schedule::add_schedule,
+        routes::schedule::set_schedule_key,
+        routes::schedule::delete_schedule,
+        routes::schedule::unlock_scheduler,
+        routes::schedule::lock_scheduler,
+
         routes::transaction::fetch_transfers,
+        routes::transaction::fetch_transfer,
+        routes::transaction::fetch_incoming_transfers,
+        routes::transaction::fetch_identity_transactions,
+        routes::transaction::fetch_transfer_retries,
+        routes::transaction::fetch_transfer_broadcasts,
         routes::transaction::transfer,
 
         routes::wallet::is_wallet_encrypted,
@@ -158,7 +212,10 @@ async fn main() {
         routes::wallet::set_master_password,
         routes::wallet::download_wallet,
         routes::wallet::is_unlocked,
-        routes::wallet::unlock
+        routes::wallet::unlock,
+        routes::wallet::lock,
+        routes::wallet::session,
+        routes::wallet::extend_session
       ])
       .manage(std::sync::Mutex::new(tx))
       .manage(std::sync::Mutex::new(rx_server_route_responses_from_thread))
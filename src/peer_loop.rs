@@ -4,22 +4,30 @@ mod balance_updater;
 mod disconnected_peer_handler;
 mod transaction_broadcaster;
 mod transaction_confirmer;
-mod broadcast_computors_updater;
+mod epoch_manager;
 mod qx;
 mod issued_asset_monitor;
+mod incoming_transfer_indexer;
+mod transfer_resender;
+mod payment_scheduler;
+mod light_sync;
 
 use std::sync::{mpsc, Arc, Mutex};
 use network::peers::PeerSet;
 
 use crate::peer_loop::balance_updater::update_balances;
-use crate::peer_loop::broadcast_computors_updater::update_broadcast_computors;
 use crate::peer_loop::connected_peer_maintainer::maintain_peers;
 use crate::peer_loop::disconnected_peer_handler::handle_disconnected_peers;
+use crate::peer_loop::epoch_manager::manage_epochs;
+use crate::peer_loop::incoming_transfer_indexer::index_incoming_transfers;
 use crate::peer_loop::issued_asset_monitor::monitor_issued_assets;
 use crate::peer_loop::latest_tick_monitor::monitor_latest_tick;
+use crate::peer_loop::light_sync::sync_tick_chain;
+use crate::peer_loop::payment_scheduler::run_scheduled_payments;
 use crate::peer_loop::qx::monitor_qx_orderbook;
 use crate::peer_loop::transaction_broadcaster::broadcast_transactions;
 use crate::peer_loop::transaction_confirmer::confirm_transactions;
+use crate::peer_loop::transfer_resender::resend_failed_transfers;
 
 pub fn start_peer_set_thread(_: &mpsc::Sender<std::collections::HashMap<String, String>>, _: mpsc::Receiver<std::collections::HashMap<String, String>>) {
     {
@@ -40,10 +48,14 @@ pub fn start_peer_set_thread(_: &mpsc::Sender<std::collections::HashMap<String,
             broadcast_transactions(peer_set.clone());
             maintain_peers(peer_set.clone());
             handle_disconnected_peers(peer_set.clone());
-            update_broadcast_computors(peer_set.clone());
+            manage_epochs(peer_set.clone());
             update_balances(peer_set.clone());
             monitor_qx_orderbook(peer_set.clone());
             monitor_issued_assets(peer_set.clone());
+            index_incoming_transfers(peer_set.clone());
+            resend_failed_transfers();
+            run_scheduled_payments();
+            sync_tick_chain(peer_set.clone());
         });
     }
     
@@ -2,19 +2,15 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use logger::{debug, error};
 use network::peers::PeerSet;
+use protocol::network_profile::get_network_profile;
 use store::{get_db_path, sqlite};
 use crate::*;
 
 pub fn maintain_peers(peer_set: Arc<Mutex<PeerSet>>) {
     std::thread::spawn(move || {
-        let peer_ips = vec![
-            "31.204.159.155:21841",
-            "138.68.105.178:21841",
-            "164.90.210.6:21841"
-        ];
-        for ip in peer_ips {
+        for ip in get_network_profile().bootstrap_peers.iter() {
             debug!("Adding Peer {}", ip);
-            peer_set.lock().unwrap().add_peer(ip).ok();
+            peer_set.lock().unwrap().add_peer(ip.as_str()).ok();
             debug!("Peer Added");
         }
 
@@ -0,0 +1,93 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use logger::error;
+use network::peers::PeerSet;
+use protocol::tick_quorum::{quorum_epoch, TICK_REPORT_MAX_AGE_SECS};
+use store::get_db_path;
+use store::sqlite::{computors, epoch, peer, peer_tick};
+
+//While we hold the current epoch's computor list it is only refreshed this often, otherwise it's asked for every pass
+const COMPUTORS_REFRESH_MS: u64 = 60_000;
+const EPOCH_PASS_MS: u64 = 2_000;
+//Ticks re-requested per pass once their computor list has arrived, and how many times each is asked for
+const PENDING_TICKS_PER_PASS: u32 = 20;
+const MAX_PENDING_TICK_REQUESTS: u32 = 5;
+
+pub fn manage_epochs(peer_set: Arc<Mutex<PeerSet>>) {
+    std::thread::spawn(move || {
+        let mut known_epoch: Option<u16> = None;
+        let mut last_computors_request: Option<Instant> = None;
+        loop {
+            /*
+            *
+            *   SECTION <Detect Epoch Rollover From The Tick Info Peers Report>
+            *
+            */
+            let reports: Vec<(u16, u32)> = peer_tick::fetch_recent_peer_epochs(get_db_path().as_str(), TICK_REPORT_MAX_AGE_SECS).unwrap_or_default();
+            let connected_peers: usize = peer::fetch_connected_peers(get_db_path().as_str()).map(|peers| peers.len()).unwrap_or(0);
+            let epochs: Vec<u16> = reports.iter().map(|(epoch, _)| *epoch).collect();
+            let network_epoch: Option<u16> = quorum_epoch(epochs.as_slice(), connected_peers);
+            if let Some(current) = network_epoch {
+                if known_epoch != Some(current) {
+                    let mut initial_ticks: Vec<u32> = reports.iter().filter(|(epoch, tick)| *epoch == current && *tick > 0).map(|(_, tick)| *tick).collect();
+                    initial_ticks.sort_unstable();
+                    let initial_tick: u32 = initial_ticks.get(initial_ticks.len().saturating_sub(1) / 2).copied().unwrap_or(0);
+                    match epoch::record_epoch(get_db_path().as_str(), current, initial_tick) {
+                        Ok(_) => {
+                            if let Some(previous) = known_epoch {
+                                println!("Epoch Rolled Over From {} To {} (Initial Tick {})", previous, current, initial_tick);
+                            }
+                            known_epoch = Some(current);
+                        },
+                        Err(err) => error!("Failed To Record Epoch {}: {}", current, err)
+                    }
+                }
+            }
+
+            /*
+            *
+            *   SECTION <Fetch BroadcastComputors, Eagerly Until We Hold The Current Epoch's List>
+            *
+            */
+            let stored_epoch: Option<u16> = computors::fetch_latest_epoch(get_db_path().as_str()).unwrap_or(None);
+            let missing_computors: bool = match (network_epoch, stored_epoch) {
+                (Some(current), Some(stored)) => stored < current,
+                (_, None) => true,
+                (None, Some(_)) => false
+            };
+            let refresh_due: bool = last_computors_request.is_none_or(|at| at.elapsed() >= Duration::from_millis(COMPUTORS_REFRESH_MS));
+            if missing_computors || refresh_due {
+                let request = api::QubicApiPacket::get_computors();
+                match peer_set.lock().unwrap().make_request(request) {
+                    Ok(_) => last_computors_request = Some(Instant::now()),
+                    Err(_err) => {
+                        error!("Failed To Request Computors");
+                    }
+                }
+            }
+
+            /*
+            *
+            *   SECTION <Re-Validate Ticks That Arrived Before Their Epoch's Computor List>
+            *
+            */
+            match epoch::fetch_pending_tick_validations(get_db_path().as_str(), MAX_PENDING_TICK_REQUESTS, PENDING_TICKS_PER_PASS) {
+                Ok(pending) => {
+                    for (tick, _, has_computors) in pending {
+                        if !has_computors {
+                            continue;
+                        }
+                        match peer_set.lock().unwrap().make_request(api::QubicApiPacket::request_quorum_tick(tick)) {
+                            Ok(_) => {
+                                let _ = epoch::increment_pending_tick_requests(get_db_path().as_str(), tick);
+                            },
+                            Err(err) => error!("Failed To Re-Request Quorum Tick {}: {}", tick, err)
+                        }
+                    }
+                },
+                Err(err) => error!("Failed To Fetch Ticks Pending Validation: {}", err)
+            }
+            std::thread::sleep(Duration::from_millis(EPOCH_PASS_MS));
+        }
+    });
+}
@@ -0,0 +1,71 @@
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use logger::error;
+use network::peers::PeerSet;
+use store::get_db_path;
+use store::sqlite::{incoming_transfer, tick};
+
+const MAX_INDEX_ATTEMPTS: u32 = 20;
+
+pub fn index_incoming_transfers(peer_set: Arc<Mutex<PeerSet>>) {
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(Duration::from_millis(3000));
+            /*
+            *
+            *   SECTION <Fetch Transactions For Ticks Where Our Identities Received Transfers>
+            *
+            */
+            let latest_tick: u32 = match tick::fetch_latest_tick(get_db_path().as_str()) {
+                Ok(tick) => {
+                    tick.parse::<u32>().unwrap()
+                },
+                Err(_) => {
+                    0
+                }
+            };
+
+            match incoming_transfer::fetch_unindexed_incoming_ticks(get_db_path().as_str(), latest_tick, MAX_INDEX_ATTEMPTS) {
+                Ok(pending) => {
+                    for incoming in pending {
+                        let identity = incoming.get("identity").unwrap();
+                        let tick = u32::from_str(incoming.get("tick").unwrap().as_str()).unwrap();
+                        incoming_transfer::increment_incoming_tick_attempts(get_db_path().as_str(), identity.as_str(), tick).ok();
+
+                        //Work out what we are missing before we can trust the transactions a peer sends us
+                        let request = match tick::fetch_tick(get_db_path().as_str(), tick) {
+                            Ok(tick_result) => {
+                                let valid: i32 = tick_result.get("valid").unwrap().to_lowercase().parse::<i32>().unwrap();
+                                let tx_digests_hash = tick_result.get("transaction_digests_hash").unwrap();
+                                let tx_digests = tick_result.get("transaction_digests").unwrap();
+                                if valid < 1 || tx_digests_hash.len() < 8 {
+                                    api::QubicApiPacket::request_quorum_tick(tick)
+                                } else if tx_digests.len() < 8 {
+                                    api::QubicApiPacket::request_tick_data(tick)
+                                } else {
+                                    api::QubicApiPacket::request_tick_transactions(tick)
+                                }
+                            },
+                            Err(_) => api::QubicApiPacket::request_quorum_tick(tick)
+                        };
+                        {
+                            let mut _lock = peer_set.lock().unwrap();
+                            match _lock.make_request(request) {
+                                Ok(_) => {},
+                                Err(_) => {
+                                    //println!("IncomingTransferIndexer: Failed To Make Request!");
+                                }
+                            }
+                            drop(_lock);
+                        }
+                        std::thread::sleep(Duration::from_millis(250));
+                    }
+                },
+                Err(_) => {
+                    error!("Db Error Fetching Incoming Ticks To Index")
+                }
+            }
+        }
+    });
+}
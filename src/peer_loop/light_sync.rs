@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use logger::{error, info};
+use network::peers::PeerSet;
+use consensus::computor::BroadcastComputors;
+use consensus::tick_chain::{check_continuity, TickDigests};
+use crypto::encoding::from_hex_to_bytes;
+use store::get_db_path;
+use store::sqlite::{computors, epoch, tick, tick_chain};
+use store::sqlite::tick_chain::{TickChainEntry, TICK_CHAIN_FORK, TICK_CHAIN_GAP, TICK_CHAIN_LINKED, TICK_CHAIN_REQUESTED, TICK_CHAIN_VERIFIED};
+use crate::env;
+
+const LIGHT_SYNC_PASS_MS: u64 = 2_000;
+//Ticks past the cursor requested in parallel each pass
+const LIGHT_SYNC_WINDOW: u32 = 32;
+//A tick no peer has returned after this many requests is recorded as a gap
+const MAX_TICK_CHAIN_REQUESTS: u32 = 10;
+
+enum Link {
+    Linked(String),
+    Forked(String),
+    //The previous tick's epoch computor list isn't stored yet
+    Waiting
+}
+
+fn decode_digests(entry: &TickChainEntry) -> Result<TickDigests, String> {
+    let hex = entry.digests.as_ref().ok_or(format!("Tick {} Has No Digests", entry.tick))?;
+    let bytes = from_hex_to_bytes(hex.as_str()).map_err(|err| err.to_string())?;
+    TickDigests::from_bytes(bytes.as_slice())
+}
+
+fn link(previous: Option<&TickChainEntry>, entry: &TickChainEntry) -> Link {
+    let previous = match previous {
+        Some(previous) if previous.status != TICK_CHAIN_GAP => previous,
+        Some(_) => return Link::Linked("Follows A Gap".to_string()),
+        None => return Link::Linked("Sync Start".to_string())
+    };
+    //The state digests restart with an epoch, so only its computors' quorum vouches for the first tick
+    if previous.epoch != entry.epoch {
+        return Link::Linked("Epoch Start".to_string());
+    }
+    let (previous_digests, digests) = match (decode_digests(previous), decode_digests(entry)) {
+        (Ok(previous_digests), Ok(digests)) => (previous_digests, digests),
+        (Err(err), _) | (_, Err(err)) => return Link::Forked(err)
+    };
+    let bytes = match computors::fetch_computors_by_epoch(get_db_path().as_str(), previous.epoch.unwrap_or(0)) {
+        Ok(bytes) => bytes,
+        Err(_) => return Link::Waiting
+    };
+    let bc: BroadcastComputors = BroadcastComputors::new(&bytes);
+    let pub_key = match bc.pub_keys.get(previous_digests.computor_index as usize) {
+        Some(pub_key) => pub_key,
+        None => return Link::Forked(format!("Invalid Computor Index {}", previous_digests.computor_index))
+    };
+    match check_continuity(&previous_digests, pub_key, &digests) {
+        Ok(_) => Link::Linked(String::new()),
+        Err(err) => Link::Forked(err)
+    }
+}
+
+pub fn sync_tick_chain(peer_set: Arc<Mutex<PeerSet>>) {
+    if !env::get_light_sync() {
+        return;
+    }
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(Duration::from_millis(LIGHT_SYNC_PASS_MS));
+            /*
+            *
+            *   SECTION <Pick Up Where The Sync Left Off>
+            *
+            */
+            let latest_tick: u32 = match tick::fetch_latest_tick(get_db_path().as_str()) {
+                Ok(latest) => latest.parse::<u32>().unwrap_or(0),
+                Err(_) => continue
+            };
+            let (start_tick, cursor) = match tick_chain::fetch_light_sync(get_db_path().as_str()) {
+                Ok(Some(state)) => state,
+                Ok(None) => {
+                    let epoch_start: u32 = epoch::fetch_epoch_history(get_db_path().as_str()).unwrap_or_default()
+                        .first()
+                        .and_then(|epoch| epoch.get("initial_tick").and_then(|tick| tick.parse::<u32>().ok()))
+                        .unwrap_or(0);
+                    let start_tick: u32 = env::get_light_sync_start().unwrap_or(match epoch_start {
+                        0 => latest_tick,
+                        _ => epoch_start
+                    });
+                    if let Err(err) = tick_chain::set_light_sync(get_db_path().as_str(), start_tick, start_tick) {
+                        error!("Failed To Start Light Sync: {}", err);
+                        continue;
+                    }
+                    info!("Light Sync Starting From Tick {}", start_tick);
+                    (start_tick, start_tick)
+                }
+                Err(err) => {
+                    error!("Failed To Fetch Light Sync State: {}", err);
+                    continue;
+                }
+            };
+
+            /*
+            *
+            *   SECTION <Settle Ticks In Order From The Cursor, Requesting The Ones Not Yet Verified>
+            *
+            */
+            let from: u32 = if cursor > start_tick { cursor - 1 } else { cursor };
+            let entries: HashMap<u32, TickChainEntry> = match tick_chain::fetch_tick_chain(get_db_path().as_str(), from, LIGHT_SYNC_WINDOW + 1) {
+                Ok(entries) => entries.into_iter().map(|entry| (entry.tick, entry)).collect(),
+                Err(err) => {
+                    error!("Failed To Fetch Tick Chain: {}", err);
+                    continue;
+                }
+            };
+            let mut previous: Option<TickChainEntry> = if cursor > start_tick { entries.get(&(cursor - 1)).cloned() } else { None };
+            let mut new_cursor: u32 = cursor;
+            let mut blocked: bool = false;
+            //The latest tick's quorum may not have formed yet
+            for t in cursor..(cursor.saturating_add(LIGHT_SYNC_WINDOW)).min(latest_tick) {
+                let entry: Option<&TickChainEntry> = entries.get(&t);
+                let status: i64 = entry.map(|entry| entry.status).unwrap_or(TICK_CHAIN_REQUESTED);
+                let requests: u32 = entry.map(|entry| entry.requests).unwrap_or(0);
+                let settled: Option<(i64, String)> = match status {
+                    TICK_CHAIN_REQUESTED if requests < MAX_TICK_CHAIN_REQUESTS => {
+                        match peer_set.lock().unwrap().make_request(api::QubicApiPacket::request_quorum_tick(t)) {
+                            Ok(_) => {
+                                let _ = tick_chain::request_tick_chain_tick(get_db_path().as_str(), t);
+                            },
+                            Err(err) => error!("Failed To Request Quorum Tick {}: {}", t, err)
+                        }
+                        blocked = true;
+                        None
+                    },
+                    TICK_CHAIN_REQUESTED if !blocked => Some((TICK_CHAIN_GAP, format!("No Peer Returned The Quorum Tick After {} Requests", requests))),
+                    TICK_CHAIN_VERIFIED if !blocked => {
+                        match link(previous.as_ref(), entry.unwrap()) {
+                            Link::Linked(detail) => Some((TICK_CHAIN_LINKED, detail)),
+                            Link::Forked(detail) => Some((TICK_CHAIN_FORK, detail)),
+                            Link::Waiting => {
+                                blocked = true;
+                                None
+                            }
+                        }
+                    },
+                    _ => None
+                };
+                if blocked {
+                    continue;
+                }
+                match settled {
+                    Some((status, detail)) => {
+                        if status != TICK_CHAIN_LINKED {
+                            println!("Light Sync: Tick {} Is A {} ({})", t, if status == TICK_CHAIN_GAP { "Gap" } else { "Fork" }, detail);
+                        }
+                        if let Err(err) = tick_chain::set_tick_chain_status(get_db_path().as_str(), t, status, detail.as_str()) {
+                            error!("Failed To Settle Tick {}: {}", t, err);
+                            blocked = true;
+                            continue;
+                        }
+                        previous = entry.cloned().map(|entry| TickChainEntry { status, ..entry })
+                            .or(Some(TickChainEntry { tick: t, epoch: None, status, requests, digests: None }));
+                    },
+                    None => previous = entry.cloned()
+                }
+                new_cursor = t + 1;
+            }
+            if new_cursor != cursor {
+                if let Err(err) = tick_chain::set_light_sync(get_db_path().as_str(), start_tick, new_cursor) {
+                    error!("Failed To Advance Light Sync: {}", err);
+                }
+            }
+        }
+    });
+}
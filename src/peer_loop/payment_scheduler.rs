@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+use logger::{debug, error, info};
+use protocol::identity::Identity;
+use protocol::tick_rate::target_tick_offset;
+use store::get_db_path;
+use store::sqlite::{computors, identity, scheduled_payment, spending_policy, tick, transfer};
+
+//Signs with the schedule's dedicated key while the scheduler is unlocked, otherwise with the unlocked wallet
+fn signing_identity(schedule: &HashMap<String, String>) -> Result<Identity, String> {
+    let source = schedule.get("source_identity").unwrap();
+    let key_seed = schedule.get("key_seed").unwrap();
+    if !key_seed.is_empty() {
+        if let Ok(password) = protocol::wallet_unlock::get_scheduler_password() {
+            let mut dedicated = Identity::from_vars(key_seed.as_str(), schedule.get("key_hash").unwrap().as_str(), schedule.get("key_salt").unwrap().as_str(), source.as_str(), true);
+            if let Ok(identity) = dedicated.decrypt_identity(password.as_str()) {
+                return Ok(identity);
+            }
+        }
+    }
+    let mut source_identity = identity::fetch_identity(get_db_path().as_str(), source.as_str())?;
+    if source_identity.encrypted {
+        source_identity = source_identity.decrypt_identity_unlocked_wallet_in_background()?;
+    }
+    Ok(source_identity)
+}
+
+pub fn run_scheduled_payments() {
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(Duration::from_millis(5000));
+            /*
+            *
+            *   SECTION <Create Transfers For Scheduled Payments That Are Due>
+            *
+            */
+            let latest_tick: u32 = match tick::fetch_latest_tick(get_db_path().as_str()) {
+                Ok(tick) => tick.parse::<u32>().unwrap(),
+                Err(_) => continue
+            };
+            let epoch: u16 = computors::fetch_latest_epoch(get_db_path().as_str()).unwrap_or(None).unwrap_or(0);
+
+            let due = match scheduled_payment::fetch_due_scheduled_payments(get_db_path().as_str(), latest_tick, epoch) {
+                Ok(due) => due,
+                Err(_) => {
+                    error!("Db Error Fetching Due Scheduled Payments");
+                    continue;
+                }
+            };
+            if due.is_empty() {
+                continue;
+            }
+
+            let estimate = tick::fetch_tick_rate_estimate(get_db_path().as_str()).unwrap_or(None);
+            let tick_offset: u32 = target_tick_offset(estimate.as_ref());
+
+            for schedule in due {
+                let id = i64::from_str(schedule.get("id").unwrap().as_str()).unwrap();
+                let source = schedule.get("source_identity").unwrap();
+                let destination = schedule.get("destination_identity").unwrap();
+                let amount = u64::from_str(schedule.get("amount").unwrap().as_str()).unwrap();
+                let interval_ticks = u32::from_str(schedule.get("interval_ticks").unwrap().as_str()).unwrap();
+                let next_tick = u32::from_str(schedule.get("next_tick").unwrap().as_str()).unwrap();
+                let last_epoch = u16::from_str(schedule.get("last_epoch").unwrap().as_str()).unwrap();
+
+                let source_identity = match signing_identity(&schedule) {
+                    Ok(identity) => identity,
+                    Err(err) => {
+                        debug!("Scheduled Payment {} Is Due But Can't Be Signed Yet: {}", id, err);
+                        continue;
+                    }
+                };
+
+                let balance: u64 = match identity::fetch_confirmed_balance(get_db_path().as_str(), source.as_str()) {
+                    Ok(Some(balance)) => balance,
+                    _ => continue
+                };
+                let pending: u64 = transfer::fetch_pending_outgoing_amount(get_db_path().as_str(), source.as_str()).unwrap_or(u64::MAX);
+                if balance < pending.saturating_add(amount) {
+                    debug!("Scheduled Payment {} Is Due, Insufficient Funds ({} Available, {} Pending)", id, balance, pending);
+                    continue;
+                }
+                if let Err(err) = spending_policy::check_spending_limits(get_db_path().as_str(), source.as_str(), destination.as_str(), amount) {
+                    debug!("Scheduled Payment {} Is Due, {}", id, err);
+                    continue;
+                }
+
+                //Move the schedule past this run first, if anything below fails it is never paid twice
+                let advanced = match schedule.get("kind").unwrap().as_str() {
+                    scheduled_payment::AT_TICK => scheduled_payment::advance_scheduled_payment(get_db_path().as_str(), id, next_tick, last_epoch, false),
+                    scheduled_payment::EVERY_N_TICKS => {
+                        let following_tick: u32 = next_tick + ((latest_tick - next_tick) / interval_ticks + 1) * interval_ticks;
+                        scheduled_payment::advance_scheduled_payment(get_db_path().as_str(), id, following_tick, last_epoch, true)
+                    },
+                    _ => scheduled_payment::advance_scheduled_payment(get_db_path().as_str(), id, next_tick, epoch, true)
+                };
+                if let Err(err) = advanced {
+                    error!("Failed To Advance Scheduled Payment {}: {}", id, err);
+                    continue;
+                }
+
+                let transfer_tx = protocol::transfer::TransferTransaction::from_vars_with_offset(&source_identity, destination.as_str(), amount, latest_tick, tick_offset);
+                let txid = transfer_tx.txid();
+                if let Err(err) = scheduled_payment::create_scheduled_payment_run(get_db_path().as_str(), id, txid.as_str(), transfer_tx._tick) {
+                    error!("Failed To Record Run Of Scheduled Payment {}: {}", id, err);
+                    continue;
+                }
+                match transfer::create_transfer(
+                    get_db_path().as_str(),
+                    source.as_str(),
+                    destination.as_str(),
+                    transfer_tx._amount,
+                    transfer_tx._tick,
+                    hex::encode(&transfer_tx._signature).as_str(),
+                    txid.as_str()
+                ) {
+                    Ok(_) => info!("Scheduled Payment {}: {} .({}) ---> {} As <{}> For Tick {}", id, source, amount, destination, txid, transfer_tx._tick),
+                    Err(err) => error!("Failed To Create Transfer For Scheduled Payment {}: {}", id, err)
+                }
+            }
+        }
+    });
+}
@@ -7,10 +7,63 @@ use crypto::qubic_identities::get_public_key_from_identity;
 use logger::{error, info};
 use network::peers::PeerSet;
 use smart_contract::qx::order::QxOrderTransaction;
+use smart_contract::qutil::send_many::{QutilProcedure, SendManyInput, SendManyTransaction};
 use store::{get_db_path, sqlite};
-use store::sqlite::transfer;
+use store::sqlite::{transfer, transfer_broadcast};
 use store::sqlite::transfer::set_transfer_as_broadcast;
 
+//Peers that must relay a transfer back before it counts as broadcast, fewer if we aren't connected to that many.
+//A successful write only means it left our socket, so sent doesn't count.
+const MIN_BROADCAST_ACCEPTANCE: usize = 3;
+const MAX_BROADCAST_ATTEMPTS: u32 = 5;
+//A broadcast still queued this long had its worker die before reporting
+const STALE_BROADCAST_SECS: u32 = 30;
+
+//Sends to every connected peer that hasn't had it yet, or failed to take it and has attempts left.
+//Marked broadcast once enough peers relayed it back.
+fn broadcast_to_peers(peer_set: &Arc<Mutex<PeerSet>>, txid: &str, broadcast: api::QubicApiPacket) {
+    let records = match transfer_broadcast::fetch_transfer_broadcasts(get_db_path().as_str(), txid) {
+        Ok(records) => records,
+        Err(err) => {
+            error!("Failed To Fetch Broadcasts Of <{}>: {}", txid, err);
+            return;
+        }
+    };
+    let mut peer_set = peer_set.lock().unwrap();
+    let peer_ids: Vec<String> = peer_set.get_peer_ids();
+    if peer_ids.is_empty() {
+        return;
+    }
+    let accepted: usize = records.iter().filter(|r| r.get("status").unwrap() == "acked").count();
+    if accepted >= MIN_BROADCAST_ACCEPTANCE.min(peer_ids.len()) {
+        match set_transfer_as_broadcast(get_db_path().as_str(), txid) {
+            Ok(_) => {
+                println!("Transaction {} Broadcast", txid);
+                info!("Transaction {} Broadcast, Relayed Back By {} Peers", txid, accepted);
+            },
+            Err(err) => error!("Failed To Set Transaction <{}> as Broadcast! ({})", txid, err)
+        }
+        return;
+    }
+    for peer_id in peer_ids {
+        let retry = match records.iter().find(|r| r.get("peer").unwrap() == &peer_id) {
+            Some(record) => record.get("status").unwrap() == "failed" && u32::from_str(record.get("attempts").unwrap()).unwrap_or(0) < MAX_BROADCAST_ATTEMPTS,
+            None => true
+        };
+        if !retry {
+            continue;
+        }
+        if let Err(err) = transfer_broadcast::queue_transfer_broadcast(get_db_path().as_str(), txid, peer_id.as_str()) {
+            error!("Failed To Queue Broadcast Of <{}> To Peer {}: {}", txid, peer_id, err);
+            continue;
+        }
+        if let Err(err) = peer_set.make_request_to_peer(peer_id.as_str(), broadcast.clone()) {
+            error!("{}", err);
+            let _ = transfer_broadcast::set_transfer_broadcast_status(get_db_path().as_str(), txid, peer_id.as_str(), "failed");
+        }
+    }
+}
+
 pub fn broadcast_transactions(peer_set: Arc<Mutex<PeerSet>>) {
     std::thread::spawn(move || {
         loop {
@@ -21,6 +74,9 @@ pub fn broadcast_transactions(peer_set: Arc<Mutex<PeerSet>>) {
             *
             */
 
+            if let Err(err) = transfer_broadcast::fail_stale_transfer_broadcasts(get_db_path().as_str(), STALE_BROADCAST_SECS) {
+                error!("Failed To Expire Stale Broadcasts: {}", err);
+            }
             match transfer::fetch_transfers_to_broadcast(get_db_path().as_str()) {
                 Ok(transfers_to_broadcast) => {
                     for transfer_map in transfers_to_broadcast {
@@ -88,7 +144,26 @@ pub fn broadcast_transactions(peer_set: Arc<Mutex<PeerSet>>) {
                                                 //println!("Re-Constructed Tx: {}", otx.txid());
                                                 _broadcast = Some(api::QubicApiPacket::broadcast_transaction(otx));
                                             } else {
-                                                _broadcast = Some(api::QubicApiPacket::broadcast_transaction(tx));
+                                                match sqlite::payout::fetch_payout_recipients_by_txid(get_db_path().as_str(), txid.as_str()) {
+                                                    Ok(recipients) if !recipients.is_empty() => {
+                                                        //This is a Send Many Payout
+                                                        tx._input_size = size_of::<SendManyInput>() as u16;
+                                                        tx._input_type = QutilProcedure::SendToManyV1 as u16;
+                                                        _broadcast = match SendManyTransaction::from_signed_data(tx, recipients.as_slice(), sig_arr.as_slice()) {
+                                                            Ok(stx) => Some(api::QubicApiPacket::broadcast_transaction(stx)),
+                                                            Err(err) => {
+                                                                error!("Failed To Rebuild Payout <{}>: {}", txid, err);
+                                                                None
+                                                            }
+                                                        };
+                                                    },
+                                                    Ok(_) => {
+                                                        _broadcast = Some(api::QubicApiPacket::broadcast_transaction(tx));
+                                                    },
+                                                    Err(_) => {
+                                                        _broadcast = None;
+                                                    }
+                                                }
                                             }
                                         },
                                         Err(_) => {
@@ -101,23 +176,8 @@ pub fn broadcast_transactions(peer_set: Arc<Mutex<PeerSet>>) {
                                 _broadcast = None;
                             }
                         };
-                        {
-                            if let Some(broadcast) = _broadcast {
-                                match peer_set.lock().unwrap().make_request(broadcast) {
-                                    Ok(_) => {
-                                        match set_transfer_as_broadcast(get_db_path().as_str(), txid.as_str()) {
-                                            Ok(_) => {
-                                                println!("Transaction {} Broadcast", txid);
-                                                info!("Transaction {} Broadcast", txid);
-                                            },
-                                            Err(err) => {
-                                                error!("Failed To Set Transaction <{}> as Broadcast! ({})", txid, err);
-                                            }
-                                        }
-                                    },
-                                    Err(err) => error!("{}", err)
-                                }
-                            }
+                        if let Some(broadcast) = _broadcast {
+                            broadcast_to_peers(&peer_set, txid.as_str(), broadcast);
                         }
                     }
                 },
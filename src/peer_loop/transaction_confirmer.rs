@@ -7,7 +7,7 @@ use crypto::qubic_identities::get_identity;
 use logger::error;
 use network::peers::PeerSet;
 use store::get_db_path;
-use store::sqlite::{tick, transfer};
+use store::sqlite::{tick, transfer, transfer_check};
 
 pub fn confirm_transactions(peer_set: Arc<Mutex<PeerSet>>) {
     std::thread::spawn(move || {
@@ -40,6 +40,7 @@ pub fn confirm_transactions(peer_set: Arc<Mutex<PeerSet>>) {
                             match transfer::set_broadcasted_transfer_as_failure(get_db_path().as_str(), txid.as_str()) {
                                 Ok(_) => {
                                     println!("Transaction <{}> Too Old. Marking Failed.", txid);
+                                    let _ = transfer_check::record_transfer_check(get_db_path().as_str(), txid.as_str(), latest_tick, "expired");
                                     continue;
                                 },
                                 Err(err) => {
@@ -95,6 +96,7 @@ pub fn confirm_transactions(peer_set: Arc<Mutex<PeerSet>>) {
                                                     match transfer::set_broadcasted_transfer_as_success(get_db_path().as_str(), txid.as_str()) {
                                                         Ok(_) => {
                                                             println!("Transaction <{}> confirmed.", txid);
+                                                            let _ = transfer_check::record_transfer_check(get_db_path().as_str(), txid.as_str(), latest_tick, "included");
                                                         },
                                                         Err(err) => {
                                                             println!("Failed To Confirm Transaction {} ({})", txid.as_str(), err);
@@ -106,6 +108,7 @@ pub fn confirm_transactions(peer_set: Arc<Mutex<PeerSet>>) {
                                                 match transfer::set_broadcasted_transfer_as_failure(get_db_path().as_str(), txid.as_str()) {
                                                     Ok(_) => {
                                                         println!("Transaction <{}> Failed.", txid);
+                                                        let _ = transfer_check::record_transfer_check(get_db_path().as_str(), txid.as_str(), latest_tick, "not_included");
                                                     },
                                                     Err(err) => {
                                                         println!("Failed To Set Failed Transaction {} ({})", txid.as_str(), err);
@@ -0,0 +1,88 @@
+use std::str::FromStr;
+use std::time::Duration;
+use logger::{debug, error, info};
+use protocol::tick_rate::target_tick_offset;
+use store::get_db_path;
+use store::sqlite::{identity, spending_policy, tick, transfer, transfer_retry};
+
+pub fn resend_failed_transfers() {
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(Duration::from_millis(5000));
+            /*
+            *
+            *   SECTION <Re-Sign Failed Transfers For A Fresh Tick, For Identities That Opted In>
+            *
+            */
+            let latest_tick: u32 = match tick::fetch_latest_tick(get_db_path().as_str()) {
+                Ok(tick) => tick.parse::<u32>().unwrap(),
+                Err(_) => continue
+            };
+
+            let estimate = tick::fetch_tick_rate_estimate(get_db_path().as_str()).unwrap_or(None);
+            let tick_offset: u32 = target_tick_offset(estimate.as_ref());
+
+            let failed = match transfer_retry::fetch_failed_transfers_to_resend(get_db_path().as_str()) {
+                Ok(failed) => failed,
+                Err(_) => {
+                    error!("Db Error Fetching Failed Transfers To Resend");
+                    continue;
+                }
+            };
+            for failed_transfer in failed {
+                let source = failed_transfer.get("source").unwrap();
+                let destination = failed_transfer.get("destination").unwrap();
+                let txid = failed_transfer.get("txid").unwrap();
+                let original_txid = failed_transfer.get("original_txid").unwrap();
+                let amount = u64::from_str(failed_transfer.get("amount").unwrap().as_str()).unwrap();
+                let attempt = u32::from_str(failed_transfer.get("attempt").unwrap().as_str()).unwrap();
+
+                let mut source_identity = match identity::fetch_identity(get_db_path().as_str(), source.as_str()) {
+                    Ok(identity) => identity,
+                    Err(_) => continue
+                };
+                //We can only re-sign while the wallet is unlocked, otherwise leave it for later
+                if source_identity.encrypted {
+                    source_identity = match source_identity.decrypt_identity_unlocked_wallet_in_background() {
+                        Ok(identity) => identity,
+                        Err(_) => continue
+                    };
+                }
+
+                let balance: u64 = match identity::fetch_confirmed_balance(get_db_path().as_str(), source.as_str()) {
+                    Ok(Some(balance)) => balance,
+                    _ => continue
+                };
+                let pending: u64 = transfer::fetch_pending_outgoing_amount(get_db_path().as_str(), source.as_str()).unwrap_or(u64::MAX);
+                if balance < pending.saturating_add(amount) {
+                    debug!("Not Resending Transfer <{}>, Insufficient Funds ({} Available, {} Pending)", txid, balance, pending);
+                    continue;
+                }
+                if let Err(err) = spending_policy::check_spending_limits(get_db_path().as_str(), source.as_str(), destination.as_str(), amount) {
+                    debug!("Not Resending Transfer <{}>, {}", txid, err);
+                    continue;
+                }
+
+                let transfer_tx = protocol::transfer::TransferTransaction::from_vars_with_offset(&source_identity, destination.as_str(), amount, latest_tick, tick_offset);
+                let new_txid = transfer_tx.txid();
+                //Link the retry first, if anything below fails this transfer is never resent twice
+                if let Err(err) = transfer_retry::create_transfer_retry(get_db_path().as_str(), new_txid.as_str(), txid.as_str(), original_txid.as_str(), attempt + 1) {
+                    error!("Failed To Link Retry <{}> To Transfer <{}>: {}", new_txid, txid, err);
+                    continue;
+                }
+                match transfer::create_transfer(
+                    get_db_path().as_str(),
+                    source.as_str(),
+                    destination.as_str(),
+                    transfer_tx._amount,
+                    transfer_tx._tick,
+                    hex::encode(&transfer_tx._signature).as_str(),
+                    new_txid.as_str()
+                ) {
+                    Ok(_) => info!("Resending Failed Transfer <{}> As <{}> For Tick {} (Retry {})", txid, new_txid, transfer_tx._tick, attempt + 1),
+                    Err(err) => error!("Failed To Resend Transfer <{}>: {}", txid, err)
+                }
+            }
+        }
+    });
+}
@@ -5,4 +5,10 @@ pub mod identity;
 pub mod wallet;
 pub mod transaction;
 pub mod asset;
-pub mod qx;
\ No newline at end of file
+pub mod qx;
+pub mod policy;
+pub mod contact;
+pub mod schedule;
+pub mod payout;
+pub mod message;
+pub mod vanity;
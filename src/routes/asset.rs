@@ -4,13 +4,26 @@ use crypto::qubic_identities::get_identity;
 use logger::{debug, error, info};
 use store::{get_db_path, sqlite};
 use store::sqlite::asset::{asset_issuance, fetch_asset_balance};
-use store::sqlite::tick;
+use smart_contract::qx::asset_transfer::QX_TRANSFER_SHARE_FEE;
 use crate::routes::MINPASSWORDLEN;
+use crate::routes::contact::{is_valid_identity, resolve_destination};
+use crate::routes::info::{base_tick, created_transaction_response, estimated_tick_offset};
+
+//Adds the shares held by unconfirmed share transfers and asks, and what is left after them
+fn with_available_shares(address: &str, mut value: HashMap<String, String>) -> HashMap<String, String> {
+    if let (Some(name), Some(issuer)) = (value.get("name"), value.get("issuer")) {
+        if let Ok((_, pending, available)) = sqlite::preflight::fetch_available_shares(get_db_path().as_str(), address, name.as_str(), issuer.as_str()) {
+            value.insert("pending_outgoing".to_string(), pending.to_string());
+            value.insert("available".to_string(), available.to_string());
+        }
+    }
+    value
+}
 
 #[get("/asset/balance/<asset>/<address>")]
 pub fn balance(asset: &str, address: &str) -> String {
     match fetch_asset_balance(get_db_path().as_str(), asset, address) {
-        Ok(value) => { format!("{:?}", value) },
+        Ok(value) => { format!("{:?}", with_available_shares(address, value)) },
         Err(error) => format!("{}", error)
     }
 }
@@ -25,7 +38,7 @@ pub fn all_asset_balances(address: &str) -> String {
                         if value.contains_key(&"balance".to_string()) {
                             let balance = value.get(&"balance".to_string()).unwrap();
                             if balance.len() > 0 && *balance != "0".to_string() {
-                                balances.push(value);
+                                balances.push(with_available_shares(address, value));
                             }
                         }
                     },
@@ -73,7 +86,6 @@ pub fn fetch_transfers(asc: u8, limit: u32, offset: u32) -> String {
 #[get("/asset/transfer/<asset_name>/<issuer>/<source>/<dest>/<amount>/<expiration>/<password>")]
 pub fn transfer(asset_name: &str, issuer: &str, source: &str, dest: &str, amount: &str, expiration: &str, password: &str) -> String {
     let source_identity: String = source.to_string();
-    let dest_identity: String = dest.to_string();
 
     if asset_name.len() > 8 {
         return "Invalid Asset Name!".to_string();
@@ -83,10 +95,15 @@ pub fn transfer(asset_name: &str, issuer: &str, source: &str, dest: &str, amount
         return "Invalid Source Identity!".to_string();
     }
 
-    if dest_identity.len() != 60 {
-        return "Invalid Destination Identity!".to_string();
+    if !is_valid_identity(issuer) {
+        return "Invalid Issuer Identity!".to_string();
     }
 
+    let dest_identity: String = match resolve_destination(dest) {
+        Ok(identity) => identity,
+        Err(err) => return err
+    };
+
 
     let mut source_identity = match sqlite::identity::fetch_identity(get_db_path().as_str(), source) {
         Ok(identity) => identity,
@@ -138,21 +155,27 @@ pub fn transfer(asset_name: &str, issuer: &str, source: &str, dest: &str, amount
         debug!("Creating Transfer, Wallet Is Not Encrypted!");
     }
     let amt: i64 = amount.parse().unwrap();
-    let mut tck: u32 = expiration.parse().unwrap();
-    
-    if tck == 0 {
-        tck = match tick::fetch_latest_tick(get_db_path().as_str()) {
-            Ok(tick) => {
-                tick.parse::<u32>().unwrap()
-            },
-            Err(_) => {
-                0 as u32
-            }
-        };
+    let tck: u32 = base_tick(expiration.parse().unwrap());
+    let tick_offset: u32 = estimated_tick_offset();
+
+    if amt <= 0 {
+        return "Number Of Shares Must Be Positive!".to_string();
+    }
+    if let Err(err) = sqlite::preflight::check_available_shares(get_db_path().as_str(), source_identity.identity.as_str(), asset_name.to_uppercase().as_str(), issuer, amt as u64) {
+        error!("Failed To Create Asset Transfer; {}", err);
+        return err;
+    }
+    if let Err(err) = sqlite::preflight::check_available_balance(get_db_path().as_str(), source_identity.identity.as_str(), QX_TRANSFER_SHARE_FEE) {
+        error!("Failed To Create Asset Transfer; Cannot Cover The QX Transfer Fee; {}", err);
+        return format!("Cannot Cover The QX Transfer Fee Of {} QU; {}", QX_TRANSFER_SHARE_FEE, err);
     }
 
-    info!("Creating Asset Transfer: {} .({}) ---> {} (Expires At Tick.<{}>)", &source_identity.identity.as_str(), amt.to_string().as_str(), dest, tck.to_string().as_str());
-    let transfer_tx = smart_contract::qx::asset_transfer::AssetTransferTransaction::from_vars(&source_identity, asset_name.to_uppercase().as_str(), issuer, dest, amt, tck);
+    info!("Creating Asset Transfer: {} .({}) ---> {} (Expires At Tick.<{}>)", &source_identity.identity.as_str(), amt.to_string().as_str(), dest_identity, (tck + tick_offset).to_string().as_str());
+    let transfer_tx = smart_contract::qx::asset_transfer::AssetTransferTransaction::from_vars(&source_identity, asset_name.to_uppercase().as_str(), issuer, dest_identity.as_str(), amt, tck, tick_offset);
+    if let Err(err) = sqlite::spending_policy::enforce_spending_policy(get_db_path().as_str(), source_identity.identity.as_str(), dest_identity.as_str(), transfer_tx.tx._amount) {
+        error!("Failed To Create Asset Transfer; {}", err);
+        return err;
+    }
     let txid = transfer_tx.txid();
 
     let sig = transfer_tx._signature;
@@ -176,7 +199,7 @@ pub fn transfer(asset_name: &str, issuer: &str, source: &str, dest: &str, amount
                                                                        transfer_tx.tx._input_size,
                                                                        transfer_tx.tx._input_type,
                                                                        txid.as_str()) {
-                Ok(_) => txid,
+                Ok(_) => created_transaction_response(txid, transfer_tx.tx._tick, tick_offset),
                 Err(_) => "Error Creating Asset Transfer".to_string()
             }
         },
@@ -0,0 +1,76 @@
+use rocket::get;
+use logger::info;
+use store::{get_db_path, sqlite};
+use crypto::qubic_identities::get_public_key_from_identity;
+
+//Full checksum validation, not just the length
+pub fn is_valid_identity(identity: &str) -> bool {
+    identity.len() == 60 && get_public_key_from_identity(&identity.to_string()).is_ok()
+}
+
+//Accepts either a raw identity or an address book label
+pub fn resolve_destination(destination: &str) -> Result<String, String> {
+    if is_valid_identity(destination) {
+        return Ok(destination.to_string());
+    }
+    match sqlite::contact::fetch_contact_by_label(get_db_path().as_str(), destination) {
+        Ok(contact) => {
+            let identity = contact.get("identity").unwrap().to_string();
+            match is_valid_identity(identity.as_str()) {
+                true => Ok(identity),
+                false => Err(format!("Contact {} Has An Invalid Identity!", destination))
+            }
+        },
+        Err(_) => Err("Invalid Destination Identity!".to_string())
+    }
+}
+
+#[get("/contacts")]
+pub fn fetch_contacts() -> String {
+    match sqlite::contact::fetch_all_contacts(get_db_path().as_str()) {
+        Ok(contacts) => format!("{:?}", contacts),
+        Err(err) => err
+    }
+}
+
+#[get("/contact/<label>")]
+pub fn fetch_contact(label: &str) -> String {
+    match sqlite::contact::fetch_contact_by_label(get_db_path().as_str(), label) {
+        Ok(contact) => format!("{:?}", contact),
+        Err(err) => err
+    }
+}
+
+#[get("/contact/add/<label>/<identity>/<notes>")]
+pub fn add_contact(label: &str, identity: &str, notes: &str) -> String {
+    //A label that looks like an identity would be ambiguous when resolving destinations
+    if label.is_empty() || label.len() >= 60 {
+        return "Invalid Label!".to_string();
+    }
+    if !is_valid_identity(identity) {
+        return "Invalid Identity!".to_string();
+    }
+    match sqlite::contact::create_contact(get_db_path().as_str(), label, identity, notes) {
+        Ok(_) => {
+            info!("Added Contact {} -> {}", label, identity);
+            "200".to_string()
+        },
+        Err(err) => err
+    }
+}
+
+#[get("/contact/notes/<label>/<notes>")]
+pub fn update_contact_notes(label: &str, notes: &str) -> String {
+    match sqlite::contact::update_contact_notes(get_db_path().as_str(), label, notes) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
+
+#[get("/contact/delete/<label>")]
+pub fn delete_contact(label: &str) -> String {
+    match sqlite::contact::delete_contact(get_db_path().as_str(), label) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
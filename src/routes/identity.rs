@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use rocket::get;
 use store::get_db_path;
 use store::sqlite::identity::insert_new_identity;
@@ -14,6 +15,25 @@ pub fn balance(address: &str) -> String {
     }
 }
 
+//Confirmed balance alongside what is left once unconfirmed outgoing transfers, fees and bid escrow are taken out.
+//Transfers that reached their tick without being broadcast are dead and no longer held back.
+#[get("/balance/available/<address>")]
+pub fn available_balance(address: &str) -> String {
+    match store::sqlite::preflight::fetch_available_balance(get_db_path().as_str(), address) {
+        Ok(Some((confirmed, pending, available))) => {
+            let bid_escrow: u64 = store::sqlite::preflight::fetch_pending_bid_escrow(get_db_path().as_str(), address).unwrap_or(0);
+            let mut response: HashMap<String, String> = HashMap::new();
+            response.insert("confirmed".to_string(), confirmed.to_string());
+            response.insert("pending_outgoing".to_string(), pending.to_string());
+            response.insert("pending_bid_escrow".to_string(), bid_escrow.to_string());
+            response.insert("available".to_string(), available.to_string());
+            format!("{:?}", response)
+        },
+        Ok(None) => "Not Yet Reported".to_string(),
+        Err(error) => error
+    }
+}
+
 #[get("/identities")]
 pub fn get_identities() -> String {
     match store::sqlite::identity::fetch_all_identities_full(store::get_db_path().as_str()) {
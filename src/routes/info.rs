@@ -1,5 +1,30 @@
+use std::collections::HashMap;
 use rocket::get;
 use store;
+use protocol::tick_rate::target_tick_offset;
+
+//Ticks past the latest known tick new transactions should target, from the observed tick rate
+pub fn estimated_tick_offset() -> u32 {
+    let estimate = store::sqlite::tick::fetch_tick_rate_estimate(store::get_db_path().as_str()).unwrap_or(None);
+    target_tick_offset(estimate.as_ref())
+}
+
+//Latest known tick, or the caller's tick when they asked for a specific one
+pub fn base_tick(requested: u32) -> u32 {
+    match requested {
+        0 => store::sqlite::tick::fetch_latest_tick(store::get_db_path().as_str()).map(|t| t.parse::<u32>().unwrap_or(0)).unwrap_or(0),
+        _ => requested
+    }
+}
+
+//What the transfer routes answer with once a transaction is signed and stored
+pub fn created_transaction_response(txid: String, tick: u32, tick_offset: u32) -> String {
+    let mut response: HashMap<String, String> = HashMap::new();
+    response.insert("txid".to_string(), txid);
+    response.insert("tick".to_string(), tick.to_string());
+    response.insert("tick_offset".to_string(), tick_offset.to_string());
+    format!("{:?}", response)
+}
 
 #[get("/tick")]
 pub fn latest_tick() -> String {
@@ -9,6 +34,97 @@ pub fn latest_tick() -> String {
     }
 }
 
+#[get("/tick/rate")]
+pub fn tick_rate() -> String {
+    let mut response: HashMap<String, String> = HashMap::new();
+    match store::sqlite::tick::fetch_tick_rate_estimate(store::get_db_path().as_str()) {
+        Ok(estimate) => {
+            if let Some(estimate) = estimate.as_ref() {
+                response.insert("ms_per_tick".to_string(), estimate.ms_per_tick.to_string());
+                response.insert("samples".to_string(), estimate.samples.to_string());
+            }
+            response.insert("tick_offset".to_string(), target_tick_offset(estimate.as_ref()).to_string());
+            format!("{:?}", response)
+        },
+        Err(err) => err
+    }
+}
+
+//Every epoch we've seen, when its computor list was validated and how many ticks still wait on it
+#[get("/epochs")]
+pub fn epochs() -> String {
+    match store::sqlite::epoch::fetch_epoch_history(store::get_db_path().as_str()) {
+        Ok(epochs) => format!("{:?}", epochs),
+        Err(err) => err
+    }
+}
+
+//Light client sync progress, verified_up_to is the last tick of the unbroken linked chain from start_tick
+#[get("/sync")]
+pub fn light_sync() -> String {
+    let mut response: HashMap<String, String> = HashMap::new();
+    response.insert("enabled".to_string(), crate::env::get_light_sync().to_string());
+    match store::sqlite::tick_chain::fetch_light_sync(store::get_db_path().as_str()) {
+        Ok(Some((start_tick, cursor))) => {
+            response.insert("start_tick".to_string(), start_tick.to_string());
+            response.insert("cursor".to_string(), cursor.to_string());
+            match store::sqlite::tick_chain::fetch_verified_watermark(store::get_db_path().as_str(), start_tick) {
+                Ok(watermark) => response.insert("verified_up_to".to_string(), watermark.map(|tick| tick.to_string()).unwrap_or("None".to_string())),
+                Err(err) => return err
+            };
+            match store::sqlite::tick_chain::fetch_tick_chain_issues(store::get_db_path().as_str(), 100) {
+                Ok(issues) => response.insert("issues".to_string(), format!("{:?}", issues)),
+                Err(err) => return err
+            };
+        },
+        Ok(None) => {
+            response.insert("status".to_string(), "Not Started".to_string());
+        },
+        Err(err) => return err
+    }
+    format!("{:?}", response)
+}
+
+//How the votes in the quorum tick we last received for this tick split, and who was missing or signed badly
+#[get("/tick/<tick>/quorum")]
+pub fn tick_quorum(tick: u32) -> String {
+    match store::sqlite::quorum_report::fetch_quorum_report(store::get_db_path().as_str(), tick) {
+        Ok(Some(report)) => format!("{:?}", report),
+        Ok(None) => format!("No Quorum Report For Tick {}", tick),
+        Err(err) => err
+    }
+}
+
+//When the tick leader stamped the tick, how many transactions it holds and the contract fees it charged
+#[get("/tick/<tick>/data")]
+pub fn tick_data(tick: u32) -> String {
+    match store::sqlite::tick_data::fetch_tick_data(store::get_db_path().as_str(), tick) {
+        Ok(Some(data)) => format!("{:?}", data),
+        Ok(None) => format!("No Tick Data For Tick {}", tick),
+        Err(err) => err
+    }
+}
+
+//Per tick time, transaction count and total fees over a range, for charting. At most 10000 ticks.
+#[get("/ticks/fees/<from>/<to>")]
+pub fn tick_fees(from: u32, to: u32) -> String {
+    if to < from || to - from >= 10000 {
+        return "Range Must Be At Most 10000 Ticks, From <= To".to_string();
+    }
+    match store::sqlite::tick_data::fetch_tick_fees(store::get_db_path().as_str(), from, to) {
+        Ok(fees) => format!("{:?}", fees),
+        Err(err) => err
+    }
+}
+
+#[get("/tick/<tick>/transactions")]
+pub fn tick_transactions(tick: u32) -> String {
+    match store::sqlite::tick_transaction::fetch_tick_transactions_by_tick(store::get_db_path().as_str(), tick) {
+        Ok(txs) => format!("{:?}", txs),
+        Err(err) => err
+    }
+}
+
 #[get("/info")]
 pub fn info() -> String {
     match store::sqlite::peer::fetch_connected_peers(store::get_db_path().as_str()) {
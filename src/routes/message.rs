@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use rocket::post;
+use logger::{error, info};
+use protocol::identity::Identity;
+use store::{get_db_path, sqlite};
+use crate::routes::MINPASSWORDLEN;
+use crate::routes::contact::is_valid_identity;
+
+//A wallet identity with its seed in plaintext, decrypted by the unlocked session or the master password
+pub fn unlock_signing_identity(source: &str, password: &str) -> Result<Identity, String> {
+    let mut identity = match sqlite::identity::fetch_identity(get_db_path().as_str(), source) {
+        Ok(identity) => identity,
+        Err(_) => return Err("Unknown Identity".to_string())
+    };
+    if identity.encrypted {
+        let decrypted = if protocol::wallet_unlock::is_wallet_unlocked().unwrap_or(false) {
+            identity.decrypt_identity_unlocked_wallet()
+        } else if password.len() >= MINPASSWORDLEN {
+            match sqlite::master_password::get_master_password(get_db_path().as_str()) {
+                Ok(master_password) => match crypto::passwords::verify_password(password, master_password[1].as_str()) {
+                    Ok(true) => identity.decrypt_identity(password),
+                    _ => return Err("Invalid Password".to_string())
+                },
+                Err(_) => return Err("Identity Is Encrypted, Yet No Master Password Set! Weird!".to_string())
+            }
+        } else {
+            return Err("Must Enter A Password!".to_string());
+        };
+        identity = match decrypted {
+            Ok(identity) => identity,
+            Err(_) => return Err("Invalid Password For This Identity!".to_string())
+        };
+    }
+    if !identity.contains_seed() {
+        return Err("Identity Has No Seed To Sign With".to_string());
+    }
+    Ok(identity)
+}
+
+//Signature is hex, the same encoding stored for transfers
+pub fn sign_message_with_identity(identity: &Identity, message: &[u8]) -> Result<String, String> {
+    let subseed = crypto::qubic_identities::get_subseed(identity.seed.as_str())?;
+    let public_key = match crypto::qubic_identities::get_public_key_from_identity(&identity.identity) {
+        Ok(public_key) => public_key,
+        Err(_) => return Err("Invalid Identity!".to_string())
+    };
+    Ok(hex::encode(crypto::qubic_identities::sign_message(&subseed, &public_key, message)))
+}
+
+pub fn verify_identity_message(identity: &str, message: &[u8], signature: &str) -> Result<bool, String> {
+    if !is_valid_identity(identity) {
+        return Err("Invalid Identity!".to_string());
+    }
+    let public_key = crypto::qubic_identities::get_public_key_from_identity(&identity.to_string()).unwrap();
+    let signature: [u8; 64] = match hex::decode(signature) {
+        Ok(bytes) if bytes.len() == 64 => bytes.try_into().unwrap(),
+        _ => return Err("Signature Must Be 128 Hex Characters!".to_string())
+    };
+    Ok(crypto::qubic_identities::verify_message(&public_key, message, &signature))
+}
+
+//Body is the message, signed as is. Proves control of the identity to an exchange or counterparty.
+#[post("/message/sign/<identity>/<password>", data = "<message>")]
+pub fn sign_message(identity: &str, password: &str, message: String) -> String {
+    let signer = match unlock_signing_identity(identity, password) {
+        Ok(signer) => signer,
+        Err(err) => {
+            error!("Failed To Sign Message; {}", err);
+            return err;
+        }
+    };
+    match sign_message_with_identity(&signer, message.as_bytes()) {
+        Ok(signature) => {
+            info!("Signed A {} Byte Message With {}", message.len(), identity);
+            let mut response: HashMap<String, String> = HashMap::new();
+            response.insert("identity".to_string(), signer.identity.clone());
+            response.insert("message_digest".to_string(), hex::encode(crypto::qubic_identities::message_digest(message.as_bytes())));
+            response.insert("signature".to_string(), signature);
+            format!("{:?}", response)
+        },
+        Err(err) => {
+            error!("Failed To Sign Message; {}", err);
+            err
+        }
+    }
+}
+
+#[post("/message/verify/<identity>/<signature>", data = "<message>")]
+pub fn verify_message(identity: &str, signature: &str, message: String) -> String {
+    match verify_identity_message(identity, message.as_bytes(), signature) {
+        Ok(verified) => {
+            let mut response: HashMap<String, String> = HashMap::new();
+            response.insert("identity".to_string(), identity.to_string());
+            response.insert("verified".to_string(), verified.to_string());
+            format!("{:?}", response)
+        },
+        Err(err) => err
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use rocket::{get, post};
+use logger::{error, info};
+use store::{get_db_path, sqlite};
+use smart_contract::qutil::send_many::{parse_recipients_csv, SendManyTransaction, QUTIL_ADDRESS, SEND_TO_MANY_FEE, SEND_TO_MANY_MAX_RECIPIENTS};
+use crate::routes::info::{base_tick, estimated_tick_offset};
+use crate::routes::MINPASSWORDLEN;
+
+#[get("/payout/<batch_id>")]
+pub fn fetch_payout(batch_id: &str) -> String {
+    match sqlite::payout::fetch_payout_batch(get_db_path().as_str(), batch_id) {
+        Ok(recipients) => format!("{:?}", recipients),
+        Err(err) => err
+    }
+}
+
+//Body is a csv of identity,amount lines. Recipients are packed into as few send many calls as the contract allows,
+//the batch id is the txid of the first call.
+#[post("/payout/<source>/<expiration>/<password>", data = "<csv>")]
+pub fn create_payout(source: &str, expiration: u32, password: &str, csv: String) -> String {
+    let recipients: Vec<(String, u64)> = match parse_recipients_csv(csv.as_str()) {
+        Ok(recipients) => recipients,
+        Err(err) => return err
+    };
+
+    let mut identity = match sqlite::identity::fetch_identity(get_db_path().as_str(), source) {
+        Ok(identity) => identity,
+        Err(_) => {
+            error!("Failed To Create Payout, Unknown Identity {}", source);
+            return "Unknown Identity".to_string();
+        }
+    };
+    if identity.encrypted {
+        let decrypted = if protocol::wallet_unlock::is_wallet_unlocked().unwrap() {
+            identity.decrypt_identity_unlocked_wallet()
+        } else if password.len() >= MINPASSWORDLEN {
+            match sqlite::master_password::get_master_password(get_db_path().as_str()) {
+                Ok(master_password) => match crypto::passwords::verify_password(password, master_password[1].as_str()) {
+                    Ok(true) => identity.decrypt_identity(password),
+                    _ => return "Invalid Password".to_string()
+                },
+                Err(_) => return "Identity Is Encrypted, Yet No Master Password Set! Weird!".to_string()
+            }
+        } else {
+            return "Must Enter A Password!".to_string();
+        };
+        identity = match decrypted {
+            Ok(identity) => identity,
+            Err(_) => return "Invalid Password For This Identity!".to_string()
+        };
+    }
+
+    //Every recipient is held to the allow list and per transfer max, the payout as a whole to the daily limit and confirmation
+    for (destination, amount) in recipients.iter() {
+        if let Err(err) = sqlite::spending_policy::check_spending_limits(get_db_path().as_str(), source, destination.as_str(), *amount) {
+            error!("Failed To Create Payout; {}", err);
+            return err;
+        }
+    }
+    let batches: Vec<&[(String, u64)]> = recipients.chunks(SEND_TO_MANY_MAX_RECIPIENTS).collect();
+    //Amounts come straight from the csv, a total that doesn't fit would wrap past the balance and policy checks
+    let total: u64 = match batches.iter()
+        .map(|batch| batch.iter().try_fold(SEND_TO_MANY_FEE, |total, (_, amount)| total.checked_add(*amount)))
+        .try_fold(0u64, |total, batch_total| batch_total.and_then(|batch_total| total.checked_add(batch_total))) {
+        Some(total) => total,
+        None => {
+            error!("Failed To Create Payout; Payout Total Overflows!");
+            return "Payout Total Overflows!".to_string();
+        }
+    };
+    if let Err(err) = sqlite::preflight::check_available_balance(get_db_path().as_str(), source, total) {
+        error!("Failed To Create Payout; {}", err);
+        return err;
+    }
+    if let Err(err) = sqlite::spending_policy::enforce_spending_policy(get_db_path().as_str(), source, QUTIL_ADDRESS, total) {
+        error!("Failed To Create Payout; {}", err);
+        return err;
+    }
+
+    let tick: u32 = base_tick(expiration);
+    let tick_offset: u32 = estimated_tick_offset();
+    let mut calls: Vec<SendManyTransaction> = Vec::with_capacity(batches.len());
+    for batch in batches.iter() {
+        match SendManyTransaction::from_vars(&identity, batch, tick, tick_offset) {
+            Ok(call) => calls.push(call),
+            Err(err) => return err
+        }
+    }
+
+    let batch_id: String = calls[0].txid();
+    let txids: Vec<String> = calls.iter().map(|call| call.txid()).collect();
+    let payout_calls: Vec<sqlite::payout::PayoutCall> = calls.iter().zip(batches.iter()).zip(txids.iter()).map(|((call, batch), txid)| sqlite::payout::PayoutCall {
+        txid: txid.clone(),
+        amount: call.tx._amount,
+        tick: call.tx._tick,
+        signature: hex::encode(&call._signature),
+        recipients: batch.to_vec()
+    }).collect();
+    if let Err(err) = sqlite::payout::create_payout(get_db_path().as_str(), batch_id.as_str(), source, QUTIL_ADDRESS, &payout_calls) {
+        error!("Failed To Store Payout <{}>: {}", batch_id, err);
+        return "Error Creating Payout".to_string();
+    }
+    info!("Creating Payout <{}>: {} Recipients In {} Calls From {} (Expires At Tick.<{}>)", batch_id, recipients.len(), txids.len(), source, calls[0].tx._tick);
+
+    let mut response: HashMap<String, String> = HashMap::new();
+    response.insert("batch_id".to_string(), batch_id);
+    response.insert("txids".to_string(), txids.join(","));
+    response.insert("recipients".to_string(), recipients.len().to_string());
+    response.insert("total".to_string(), total.to_string());
+    response.insert("tick".to_string(), calls[0].tx._tick.to_string());
+    response.insert("tick_offset".to_string(), tick_offset.to_string());
+    format!("{:?}", response)
+}
@@ -42,6 +42,15 @@ pub fn delete_peer(peer_id: &str) -> String {
     }
 }
 
+//The CurrentTickInfo each peer last reported, and how often it has strayed from the quorum tick
+#[get("/peers/tick")]
+pub fn peer_ticks() -> String {
+    match store::sqlite::peer_tick::fetch_peer_tick_infos(store::get_db_path().as_str()) {
+        Ok(value) => format!("{:?}", value),
+        Err(err) => err
+    }
+}
+
 #[get("/peers/limit")]
 pub fn get_peer_limit() -> String {
     let current_min = get_min_peers();
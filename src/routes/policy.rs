@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use rocket::get;
+use logger::info;
+use store::{get_db_path, sqlite};
+use crate::routes::contact::is_valid_identity;
+
+//Policies guard against someone holding an unlocked wallet, so changing them always needs the master password
+pub fn verify_master_password(password: &str) -> Result<(), String> {
+    match sqlite::master_password::get_master_password(get_db_path().as_str()) {
+        Ok(master_password) => {
+            match crypto::passwords::verify_password(password, master_password[1].as_str()) {
+                Ok(true) => Ok(()),
+                _ => Err("Invalid Password!".to_string())
+            }
+        },
+        //Only a wallet confirmed to have no master password goes without, any other failure refuses the change
+        Err(err) if err == "No Master Password Set" => Ok(()),
+        Err(err) => Err(format!("Failed To Verify Master Password: {}", err))
+    }
+}
+
+#[get("/policy/<identity>")]
+pub fn fetch_policy(identity: &str) -> String {
+    let mut response: HashMap<String, String> = match sqlite::spending_policy::fetch_spending_policy(get_db_path().as_str(), identity) {
+        Ok(Some(policy)) => policy,
+        Ok(None) => HashMap::new(),
+        Err(err) => return err
+    };
+    if let Ok(Some(resend)) = sqlite::transfer_retry::fetch_resend_policy(get_db_path().as_str(), identity) {
+        response.insert("max_retries".to_string(), resend.get("max_retries").unwrap().to_string());
+    }
+    match sqlite::spending_policy::fetch_allowed_destinations(get_db_path().as_str(), identity) {
+        Ok(allowed) => {
+            response.insert("allowed_destinations".to_string(), allowed.join(","));
+            format!("{:?}", response)
+        },
+        Err(err) => err
+    }
+}
+
+#[get("/policy/set/<identity>/<max_per_transfer>/<daily_limit>/<confirm_threshold>/<password>")]
+pub fn set_policy(identity: &str, max_per_transfer: u64, daily_limit: u64, confirm_threshold: u64, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    if sqlite::identity::fetch_identity(get_db_path().as_str(), identity).is_err() {
+        return "Unknown Identity".to_string();
+    }
+    match sqlite::spending_policy::set_spending_policy(get_db_path().as_str(), identity, max_per_transfer, daily_limit, confirm_threshold) {
+        Ok(_) => {
+            info!("Spending Policy Set For {}", identity);
+            "200".to_string()
+        },
+        Err(err) => err
+    }
+}
+
+#[get("/policy/delete/<identity>/<password>")]
+pub fn delete_policy(identity: &str, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    match sqlite::spending_policy::delete_spending_policy(get_db_path().as_str(), identity) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
+
+#[get("/policy/allow/<identity>/<destination>/<password>")]
+pub fn allow_destination(identity: &str, destination: &str, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    if !is_valid_identity(destination) {
+        return "Invalid Destination Identity!".to_string();
+    }
+    match sqlite::spending_policy::add_allowed_destination(get_db_path().as_str(), identity, destination) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
+
+#[get("/policy/disallow/<identity>/<destination>/<password>")]
+pub fn disallow_destination(identity: &str, destination: &str, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    match sqlite::spending_policy::remove_allowed_destination(get_db_path().as_str(), identity, destination) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
+
+//Opt in to re-sending failed transfers for a fresh tick while the wallet is unlocked, 0 retries opts back out
+#[get("/policy/resend/<identity>/<max_retries>/<password>")]
+pub fn set_resend_policy(identity: &str, max_retries: u32, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    if sqlite::identity::fetch_identity(get_db_path().as_str(), identity).is_err() {
+        return "Unknown Identity".to_string();
+    }
+    let result = match max_retries {
+        0 => sqlite::transfer_retry::delete_resend_policy(get_db_path().as_str(), identity),
+        _ => sqlite::transfer_retry::set_resend_policy(get_db_path().as_str(), identity, max_retries)
+    };
+    match result {
+        Ok(_) => {
+            info!("Resend Policy Set For {} ({} Retries)", identity, max_retries);
+            "200".to_string()
+        },
+        Err(err) => err
+    }
+}
+
+#[get("/policy/confirm/<token>/<password>")]
+pub fn confirm_transfer(token: &str, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    match sqlite::spending_policy::confirm_spending_confirmation(get_db_path().as_str(), token) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
@@ -1,11 +1,11 @@
-use std::str::FromStr;
 use rocket::get;
 use crypto::qubic_identities::get_identity;
 use logger::{debug, error, info};
 use store::{get_db_path, sqlite};
 use store::sqlite::asset::fetch_asset_balance;
 use smart_contract::qx::order;
-use store::sqlite::tick::fetch_latest_tick;
+use protocol::network_profile::get_network_profile;
+use crate::routes::info::{base_tick, created_transaction_response, estimated_tick_offset};
 use crate::routes::MINPASSWORDLEN;
 
 #[get("/qx/orderbook/<asset>/<ask_bid>/<limit>/<offset>")]
@@ -53,10 +53,8 @@ pub fn place_order(tick: u32, issuer: &str, asset: &str, ask_bid: &str, address:
         _ => { return "Invalid QX Order Type!".to_string(); }
     };
     
-    let tick_to_use = match tick {
-        0 => u32::from_str(fetch_latest_tick(get_db_path().as_str()).unwrap().as_str()).unwrap(),
-        _ => tick
-    };
+    let tick_to_use = base_tick(tick);
+    let tick_offset: u32 = estimated_tick_offset();
     
     
     let mut identity = match sqlite::identity::fetch_identity(get_db_path().as_str(), _identity.as_str()) {
@@ -109,10 +107,37 @@ pub fn place_order(tick: u32, issuer: &str, asset: &str, ask_bid: &str, address:
         debug!("Creating QX Order, Wallet Is Not Encrypted!");
     }
     
+    let is_add_order = matches!(_procedure, order::QxProcedure::QxAddAskOrder | order::QxProcedure::QxAddBidOrder);
+    if is_add_order && (price == 0 || amount == 0) {
+        return "Price And Number Of Shares Must Be Positive!".to_string();
+    }
+    let tx_amount: u64 = match order::order_amount(&_procedure, price, amount) {
+        Some(tx_amount) => tx_amount,
+        None => return "Bid Escrow Overflows, Lower The Price Or Number Of Shares!".to_string()
+    };
+    if let order::QxProcedure::QxAddAskOrder = _procedure {
+        if let Err(err) = sqlite::preflight::check_available_shares(get_db_path().as_str(), identity.identity.as_str(), asset.to_uppercase().as_str(), issuer, amount) {
+            error!("Failed To Create QX Order; {}", err);
+            return err;
+        }
+    }
+    if let Err(err) = sqlite::preflight::check_available_balance(get_db_path().as_str(), identity.identity.as_str(), tx_amount) {
+        let reason = match _procedure {
+            order::QxProcedure::QxAddBidOrder => format!("Cannot Cover The Bid Escrow Of {} QU; {}", tx_amount, err),
+            _ => format!("Cannot Cover The QX Fee Of {} QU; {}", tx_amount, err)
+        };
+        error!("Failed To Create QX Order; {}", reason);
+        return reason;
+    }
+
     match fetch_asset_balance(get_db_path().as_str(), asset, address) {
-        Ok(_) => {  //todo: enforce sufficient balance
-            info!("Creating QX Order: {} .({}) ---> {} (Expires At Tick.<{}>)", &identity.identity.as_str(), amount.to_string().as_str(), price, tick_to_use.to_string().as_str());
-            let order_tx = smart_contract::qx::order::QxOrderTransaction::from_vars(_procedure, &identity, asset.to_uppercase().as_str(), issuer, price, amount, tick_to_use);
+        Ok(_) => {
+            info!("Creating QX Order: {} .({}) ---> {} (Expires At Tick.<{}>)", &identity.identity.as_str(), amount.to_string().as_str(), price, (tick_to_use + tick_offset).to_string().as_str());
+            let order_tx = smart_contract::qx::order::QxOrderTransaction::from_vars(_procedure, &identity, asset.to_uppercase().as_str(), issuer, price, amount, tick_to_use, tick_offset);
+            if let Err(err) = sqlite::spending_policy::enforce_spending_policy(get_db_path().as_str(), identity.identity.as_str(), get_network_profile().qx_address.as_str(), order_tx.tx._amount) {
+                error!("Failed To Create QX Order; {}", err);
+                return err;
+            }
             let txid = order_tx.txid();
 
             let sig = order_tx._signature;
@@ -136,7 +161,7 @@ pub fn place_order(tick: u32, issuer: &str, asset: &str, ask_bid: &str, address:
                                                                                order_tx.tx._input_size,
                                                                                order_tx.tx._input_type,
                                                                                txid.as_str()) {
-                        Ok(_) => txid,
+                        Ok(_) => created_transaction_response(txid, order_tx.tx._tick, tick_offset),
                         Err(_) => "Error Creating QX Order".to_string()
                     }
                 },
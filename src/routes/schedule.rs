@@ -0,0 +1,147 @@
+use rocket::get;
+use logger::info;
+use store::{get_db_path, sqlite};
+use store::sqlite::scheduled_payment::{AT_TICK, EPOCH_START, EVERY_N_TICKS};
+use crate::routes::MINPASSWORDLEN;
+use crate::routes::contact::resolve_destination;
+use crate::routes::policy::verify_master_password;
+
+//Schedules are meant to run unattended, so the scheduler may stay unlocked far longer than the wallet. A week at most.
+const MAX_SCHEDULER_TIMEOUT_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+#[get("/schedules")]
+pub fn fetch_schedules() -> String {
+    match sqlite::scheduled_payment::fetch_scheduled_payments(get_db_path().as_str()) {
+        Ok(schedules) => format!("{:?}", schedules),
+        Err(err) => err
+    }
+}
+
+//Transfers a schedule has created, with their broadcast and confirmation status
+#[get("/schedule/<id>/runs")]
+pub fn fetch_schedule_runs(id: i64) -> String {
+    match sqlite::scheduled_payment::fetch_scheduled_payment_runs(get_db_path().as_str(), id) {
+        Ok(runs) => format!("{:?}", runs),
+        Err(err) => err
+    }
+}
+
+//kind is at_tick (value is the tick), every_n_ticks (value is the interval, first run one interval from now)
+//or epoch_start (value is ignored, first run at the next epoch)
+#[get("/schedule/add/<source>/<dest>/<amount>/<kind>/<value>/<password>")]
+pub fn add_schedule(source: &str, dest: &str, amount: u64, kind: &str, value: u32, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    if sqlite::identity::fetch_identity(get_db_path().as_str(), source).is_err() {
+        return "Unknown Identity".to_string();
+    }
+    let destination: String = match resolve_destination(dest) {
+        Ok(identity) => identity,
+        Err(err) => return err
+    };
+    if amount == 0 {
+        return "Amount Must Be Greater Than 0!".to_string();
+    }
+    let latest_tick: u32 = match sqlite::tick::fetch_latest_tick(get_db_path().as_str()) {
+        Ok(tick) => tick.parse::<u32>().unwrap_or(0),
+        Err(_) => 0
+    };
+    let (interval_ticks, next_tick, last_epoch) = match kind {
+        AT_TICK => (0, value, 0),
+        EVERY_N_TICKS => {
+            if value == 0 {
+                return "Interval Must Be At Least 1 Tick!".to_string();
+            }
+            (value, latest_tick + value, 0)
+        },
+        EPOCH_START => match sqlite::computors::fetch_latest_epoch(get_db_path().as_str()) {
+            Ok(Some(epoch)) => (0, 0, epoch),
+            _ => return "Current Epoch Unknown, Try Again Once Computors Are Synced".to_string()
+        },
+        _ => return format!("Unknown Schedule Kind! Expected {}, {} Or {}", AT_TICK, EVERY_N_TICKS, EPOCH_START)
+    };
+    match sqlite::scheduled_payment::create_scheduled_payment(get_db_path().as_str(), source, destination.as_str(), amount, kind, interval_ticks, next_tick, last_epoch) {
+        Ok(_) => {
+            info!("Scheduled Payment Added: {} .({}) ---> {} ({} {})", source, amount, destination, kind, value);
+            "200".to_string()
+        },
+        Err(err) => err
+    }
+}
+
+//Gives a schedule its own copy of the source seed, encrypted with a scheduler password instead of the master password
+#[get("/schedule/key/<id>/<password>/<key_password>")]
+pub fn set_schedule_key(id: i64, password: &str, key_password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    if key_password.len() < MINPASSWORDLEN {
+        return "Scheduler Password Too Short!".to_string();
+    }
+    if key_password == password {
+        return "Scheduler Password Must Differ From The Master Password!".to_string();
+    }
+    let schedules = match sqlite::scheduled_payment::fetch_scheduled_payments(get_db_path().as_str()) {
+        Ok(schedules) => schedules,
+        Err(err) => return err
+    };
+    let source: String = match schedules.iter().find(|s| s.get("id").unwrap() == &id.to_string()) {
+        Some(schedule) => schedule.get("source_identity").unwrap().to_string(),
+        None => return "Unknown Schedule".to_string()
+    };
+    let mut source_identity = match sqlite::identity::fetch_identity(get_db_path().as_str(), source.as_str()) {
+        Ok(identity) => identity,
+        Err(_) => return "Unknown Identity".to_string()
+    };
+    if source_identity.encrypted {
+        source_identity = match source_identity.decrypt_identity(password) {
+            Ok(identity) => identity,
+            Err(_) => return "Invalid Password For This Identity!".to_string()
+        };
+    }
+    match source_identity.encrypt_identity(key_password) {
+        Ok(dedicated) => {
+            match sqlite::scheduled_payment::set_scheduled_payment_key(get_db_path().as_str(), id, dedicated.seed.as_str(), dedicated.salt.as_str(), dedicated.hash.as_str()) {
+                Ok(_) => "200".to_string(),
+                Err(err) => err
+            }
+        },
+        Err(err) => err
+    }
+}
+
+#[get("/schedule/delete/<id>/<password>")]
+pub fn delete_schedule(id: i64, password: &str) -> String {
+    if let Err(err) = verify_master_password(password) {
+        return err;
+    }
+    match sqlite::scheduled_payment::delete_scheduled_payment(get_db_path().as_str(), id) {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
+
+//Lets schedules with a dedicated key pay while the wallet stays locked, until timeout_ms runs out
+#[get("/scheduler/unlock/<key_password>/<timeout_ms>")]
+pub fn unlock_scheduler(key_password: &str, timeout_ms: u64) -> String {
+    if timeout_ms > MAX_SCHEDULER_TIMEOUT_MS {
+        return "Scheduler Unlock Timeout Period Too Long!".to_string();
+    }
+    let key_hashes: Vec<String> = match sqlite::scheduled_payment::fetch_scheduled_payment_key_hashes(get_db_path().as_str()) {
+        Ok(key_hashes) => key_hashes,
+        Err(err) => return err
+    };
+    match protocol::wallet_unlock::unlock_scheduler(&key_hashes, key_password, std::time::Duration::from_millis(timeout_ms)) {
+        Ok(unlocked) => unlocked,
+        Err(err) => err
+    }
+}
+
+#[get("/scheduler/lock")]
+pub fn lock_scheduler() -> String {
+    match protocol::wallet_unlock::lock_scheduler() {
+        Ok(_) => "200".to_string(),
+        Err(err) => err
+    }
+}
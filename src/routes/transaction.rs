@@ -1,7 +1,11 @@
+use std::collections::HashMap;
 use rocket::get;
+use protocol::transfer_lifecycle::TransferLifecycle;
 use logger::{debug, error, info};
 use store::{get_db_path, sqlite};
 use crate::routes::MINPASSWORDLEN;
+use crate::routes::contact::resolve_destination;
+use crate::routes::info::{base_tick, created_transaction_response, estimated_tick_offset};
 
 #[get("/transfer/<asc>/<limit>/<offset>")]
 pub fn fetch_transfers(asc: u8, limit: u32, offset: u32) -> String {
@@ -24,20 +28,138 @@ pub fn fetch_transfers(asc: u8, limit: u32, offset: u32) -> String {
     }
 }
 
+#[get("/incoming/<identity>")]
+pub fn fetch_incoming_transfers(identity: &str) -> String {
+    match sqlite::incoming_transfer::fetch_incoming_transfers_by_identity(get_db_path().as_str(), identity) {
+        Ok(txs) => format!("{:?}", txs),
+        Err(e) => {
+            println!("Error Fetching Incoming Transfers: {}", e);
+            "Error Fetching Incoming Transfers.".to_string()
+        }
+    }
+}
+
+//Every verified transaction we have stored where this identity is the source or destination
+#[get("/transactions/<identity>")]
+pub fn fetch_identity_transactions(identity: &str) -> String {
+    match sqlite::tick_transaction::fetch_tick_transactions_by_identity(get_db_path().as_str(), identity) {
+        Ok(txs) => format!("{:?}", txs),
+        Err(e) => {
+            println!("Error Fetching Transactions: {}", e);
+            "Error Fetching Transactions.".to_string()
+        }
+    }
+}
+
+//One transfer of any kind (QU, asset transfer, QX order or payout) with its lifecycle
+#[get("/transfer/<txid>")]
+pub fn fetch_transfer(txid: &str) -> String {
+    let mut response: HashMap<String, String> = match sqlite::transfer::fetch_transfer_by_txid(get_db_path().as_str(), txid) {
+        Ok(mut transfers) if !transfers.is_empty() => transfers.remove(0),
+        Ok(_) => return "Unknown Transfer".to_string(),
+        Err(e) => {
+            println!("Error Fetching Transfer: {}", e);
+            return "Error Fetching Transfer.".to_string();
+        }
+    };
+    response.remove("signature");
+    let target_tick: u32 = response.get("tick").unwrap().parse().unwrap_or(0);
+    let latest_tick: u32 = base_tick(0);
+    let check = sqlite::transfer_check::fetch_transfer_check(get_db_path().as_str(), txid).unwrap_or(None);
+    let lifecycle = TransferLifecycle::from_state(
+        response.get("broadcast").unwrap() == "1",
+        response.get("status").unwrap().parse().unwrap_or(-1),
+        target_tick,
+        latest_tick,
+        check.as_ref().is_some_and(|c| c.get("outcome").unwrap() == "expired")
+    );
+    response.insert("lifecycle".to_string(), lifecycle.as_str().to_string());
+    response.insert("target_tick".to_string(), target_tick.to_string());
+    response.insert("latest_tick".to_string(), latest_tick.to_string());
+    if let Some(check) = check {
+        response.insert("checked_at_tick".to_string(), check.get("checked_at_tick").unwrap().to_string());
+    }
+    if lifecycle == TransferLifecycle::Included {
+        response.insert("confirming_ticks".to_string(), latest_tick.saturating_sub(target_tick).to_string());
+        //Wall clock time of the tick it executed in, once we hold that tick's verified tick data
+        if let Ok(Some(tick_data)) = sqlite::tick_data::fetch_tick_data(get_db_path().as_str(), target_tick) {
+            response.insert("executed_at".to_string(), tick_data.get("timestamp").unwrap().to_string());
+        }
+    }
+
+    let kind: &str = if let Ok(Some(asset_tx)) = sqlite::asset::asset_transfer::fetch_transfer_by_txid(get_db_path().as_str(), txid) {
+        for key in ["name", "issuer", "num_shares", "new_owner_and_possessor"] {
+            response.insert(key.to_string(), asset_tx.get(key).unwrap().to_string());
+        }
+        "asset"
+    } else if let Ok(Some(order)) = sqlite::qx::order::fetch_qx_order_by_txid(get_db_path().as_str(), txid) {
+        for key in ["name", "issuer", "num_shares", "price"] {
+            response.insert(key.to_string(), order.get(key).unwrap().to_string());
+        }
+        let side = match order.get("input_type").unwrap().as_str() {
+            "5" => "ask",
+            "6" => "bid",
+            "7" => "remove_ask",
+            "8" => "remove_bid",
+            _ => "unknown"
+        };
+        response.insert("side".to_string(), side.to_string());
+        "qx"
+    } else {
+        match sqlite::payout::fetch_payout_recipients_by_txid(get_db_path().as_str(), txid) {
+            Ok(recipients) if !recipients.is_empty() => {
+                response.insert("recipients".to_string(), recipients.len().to_string());
+                "payout"
+            },
+            _ => "qu"
+        }
+    };
+    response.insert("kind".to_string(), kind.to_string());
+
+    let broadcasts = sqlite::transfer_broadcast::fetch_transfer_broadcasts(get_db_path().as_str(), txid).unwrap_or_default();
+    let peers: Vec<String> = broadcasts.iter().map(|b| format!("{}:{}", b.get("peer").unwrap(), b.get("status").unwrap())).collect();
+    response.insert("peers".to_string(), peers.join(","));
+    format!("{:?}", response)
+}
+
+//Which peers a transfer was sent to and whether each took it
+#[get("/transfer/<txid>/broadcast")]
+pub fn fetch_transfer_broadcasts(txid: &str) -> String {
+    match sqlite::transfer_broadcast::fetch_transfer_broadcasts(get_db_path().as_str(), txid) {
+        Ok(broadcasts) => format!("{:?}", broadcasts),
+        Err(e) => {
+            println!("Error Fetching Transfer Broadcasts: {}", e);
+            "Error Fetching Transfer Broadcasts.".to_string()
+        }
+    }
+}
+
+//Retries made for a failed transfer, by the original txid or any retry of it
+#[get("/retries/<txid>")]
+pub fn fetch_transfer_retries(txid: &str) -> String {
+    match sqlite::transfer_retry::fetch_transfer_retries(get_db_path().as_str(), txid) {
+        Ok(retries) => format!("{:?}", retries),
+        Err(e) => {
+            println!("Error Fetching Transfer Retries: {}", e);
+            "Error Fetching Transfer Retries.".to_string()
+        }
+    }
+}
 
 //transfer/${sourceIdentity}/${destinationIdentity}/${amountToSend}/${expirationTick}/${password}
+//dest may be an identity or an address book label
 #[get("/transfer/<source>/<dest>/<amount>/<expiration>/<password>")]
 pub fn transfer(source: &str, dest: &str, amount: &str, expiration: &str, password: &str) -> String {
     let source_identity: String = source.to_string();
-    let dest_identity: String = dest.to_string();
 
     if source_identity.len() != 60 {
         return format!("Invalid Source Identity!");
     }
 
-    if dest_identity.len() != 60 {
-        return format!("Invalid Destination Identity!");
-    }
+    let dest_identity: String = match resolve_destination(dest) {
+        Ok(identity) => identity,
+        Err(err) => return err
+    };
 
 
     let mut source_identity = match sqlite::identity::fetch_identity(get_db_path().as_str(), source) {
@@ -91,10 +213,20 @@ pub fn transfer(source: &str, dest: &str, amount: &str, expiration: &str, passwo
     }
     
     let amt: u64 = amount.parse().unwrap();
-    let tck: u32 = expiration.parse().unwrap();
+    let tck: u32 = base_tick(expiration.parse().unwrap());
+    let tick_offset: u32 = estimated_tick_offset();
+
+    if let Err(err) = sqlite::preflight::check_available_balance(get_db_path().as_str(), source_identity.identity.as_str(), amt) {
+        error!("Failed To Create Transfer; {}", err);
+        return err;
+    }
+    if let Err(err) = sqlite::spending_policy::enforce_spending_policy(get_db_path().as_str(), source_identity.identity.as_str(), dest_identity.as_str(), amt) {
+        error!("Failed To Create Transfer; {}", err);
+        return err;
+    }
 
-    let transfer_tx = protocol::transfer::TransferTransaction::from_vars(&source_identity, &dest, amt, tck);
-    info!("Creating Transfer: {} .({}) ---> {} (Expires At Tick.<{}>)", &source_identity.identity.as_str(), amt.to_string().as_str(), dest, tck.to_string().as_str());
+    let transfer_tx = protocol::transfer::TransferTransaction::from_vars_with_offset(&source_identity, dest_identity.as_str(), amt, tck, tick_offset);
+    info!("Creating Transfer: {} .({}) ---> {} (Expires At Tick.<{}>)", &source_identity.identity.as_str(), amt.to_string().as_str(), dest_identity, transfer_tx._tick.to_string().as_str());
 
     let txid = transfer_tx.txid();
 
@@ -111,7 +243,7 @@ pub fn transfer(source: &str, dest: &str, amount: &str, expiration: &str, passwo
         txid.as_str()
     ) {
         Ok(_) => {
-            txid
+            created_transaction_response(txid, transfer_tx._tick, tick_offset)
         },
         Err(err) => {
             println!("Error Inserting Tx into Db: {}", err);
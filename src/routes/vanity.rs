@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use once_cell::sync::Lazy;
+use rocket::get;
+use logger::{error, info};
+use crypto::secret::Secret;
+use crypto::vanity::{search, VanityPattern};
+use protocol::identity::Identity;
+use store::{get_db_path, sqlite};
+use crate::routes::MINPASSWORDLEN;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VanityJobState {
+    Running,
+    Imported(String),
+    Cancelled,
+    Failed(String)
+}
+
+pub struct VanityJob {
+    pub pattern: VanityPattern,
+    pub threads: usize,
+    pub started: Instant,
+    pub finished: Arc<Mutex<Option<Instant>>>,
+    pub attempts: Arc<AtomicU64>,
+    pub cancel: Arc<AtomicBool>,
+    pub state: Arc<Mutex<VanityJobState>>
+}
+
+//Searches started this run, by job id. Only one runs at a time, each already uses every thread it asked for.
+pub static VANITY_JOBS: Lazy<Mutex<HashMap<String, VanityJob>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+//Matches are always imported encrypted, so a master password is required even if the wallet is unlocked
+fn import_password(password: &str) -> Result<Secret, String> {
+    let master_password = match sqlite::master_password::get_master_password(get_db_path().as_str()) {
+        Ok(master_password) => master_password,
+        Err(_) => return Err("Set A Master Password First, Vanity Identities Are Imported Encrypted".to_string())
+    };
+    if protocol::wallet_unlock::is_wallet_unlocked().unwrap_or(false) {
+        return protocol::wallet_unlock::get_plaintext_password().map_err(|_| "Failed To Retrieve Unlocked Password".to_string());
+    }
+    if password.len() < MINPASSWORDLEN {
+        return Err("Must Enter A Password!".to_string());
+    }
+    match crypto::passwords::verify_password(password, master_password[1].as_str()) {
+        Ok(true) => Ok(Secret::from(password)),
+        _ => Err("Invalid Password!".to_string())
+    }
+}
+
+fn import_match(seed: &Secret, password: &Secret) -> Result<String, String> {
+    let identity = Identity::new(seed.as_str()).encrypt_identity(password.as_str())?;
+    sqlite::identity::insert_new_identity(get_db_path().as_str(), &identity)?;
+    Ok(identity.identity)
+}
+
+fn job_status(job_id: &str, job: &VanityJob) -> HashMap<String, String> {
+    let attempts: u64 = job.attempts.load(Ordering::Relaxed);
+    let elapsed: f64 = job.finished.lock().unwrap().unwrap_or_else(Instant::now).duration_since(job.started).as_secs_f64();
+    let rate: f64 = if elapsed > 0.0 { attempts as f64 / elapsed } else { 0.0 };
+    let mut status: HashMap<String, String> = HashMap::new();
+    status.insert("job_id".to_string(), job_id.to_string());
+    status.insert("prefix".to_string(), job.pattern.prefix.clone());
+    status.insert("suffix".to_string(), job.pattern.suffix.clone());
+    status.insert("threads".to_string(), job.threads.to_string());
+    status.insert("attempts".to_string(), attempts.to_string());
+    status.insert("elapsed_secs".to_string(), format!("{:.0}", elapsed));
+    status.insert("attempts_per_sec".to_string(), format!("{:.1}", rate));
+    status.insert("expected_attempts".to_string(), format!("{:.0}", job.pattern.expected_attempts()));
+    //The search is memoryless, a running job always expects the full estimate from now
+    status.insert("expected_secs".to_string(), if rate > 0.0 { format!("{:.0}", job.pattern.expected_attempts() / rate) } else { "unknown".to_string() });
+    status.insert("chance_found_by_now".to_string(), format!("{:.4}", job.pattern.chance_within(attempts)));
+    let (state, identity, reason) = match &*job.state.lock().unwrap() {
+        VanityJobState::Running => ("running", String::new(), String::new()),
+        VanityJobState::Imported(identity) => ("imported", identity.clone(), String::new()),
+        VanityJobState::Cancelled => ("cancelled", String::new(), String::new()),
+        VanityJobState::Failed(reason) => ("failed", String::new(), reason.clone())
+    };
+    status.insert("state".to_string(), state.to_string());
+    status.insert("identity".to_string(), identity);
+    status.insert("reason".to_string(), reason);
+    status
+}
+
+//prefix and suffix are letters or - for none, threads of 0 uses every core
+#[get("/identity/vanity/new/<prefix>/<suffix>/<threads>/<password>")]
+pub fn start_vanity_search(prefix: &str, suffix: &str, threads: usize, password: &str) -> String {
+    let pattern = match VanityPattern::new(if prefix == "-" { "" } else { prefix }, if suffix == "-" { "" } else { suffix }) {
+        Ok(pattern) => pattern,
+        Err(err) => return err
+    };
+    let password: Secret = match import_password(password) {
+        Ok(password) => password,
+        Err(err) => return err
+    };
+    let threads: usize = if threads == 0 { std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) } else { threads };
+
+    let mut jobs = VANITY_JOBS.lock().unwrap();
+    if jobs.values().any(|job| *job.state.lock().unwrap() == VanityJobState::Running) {
+        return "A Vanity Search Is Already Running".to_string();
+    }
+    let job_id: String = hex::encode(crypto::random::random_bytes(8));
+    let job = VanityJob {
+        pattern: pattern.clone(),
+        threads,
+        started: Instant::now(),
+        finished: Arc::new(Mutex::new(None)),
+        attempts: Arc::new(AtomicU64::new(0)),
+        cancel: Arc::new(AtomicBool::new(false)),
+        state: Arc::new(Mutex::new(VanityJobState::Running))
+    };
+    let (finished, attempts, cancel, state) = (job.finished.clone(), job.attempts.clone(), job.cancel.clone(), job.state.clone());
+    info!("Starting Vanity Search {} For {}...{} On {} Threads, ~{:.0} Attempts Expected", job_id, pattern.prefix, pattern.suffix, threads, pattern.expected_attempts());
+    let id: String = job_id.clone();
+    std::thread::spawn(move || {
+        let outcome = match search(&pattern, threads, &attempts, &cancel) {
+            Some(found) => match import_match(&found.seed, &password) {
+                Ok(identity) => {
+                    info!("Vanity Search {} Imported {} After {} Attempts", id, identity, found.attempts);
+                    VanityJobState::Imported(identity)
+                },
+                Err(err) => {
+                    error!("Vanity Search {} Failed To Import Its Match; {}", id, err);
+                    VanityJobState::Failed(err)
+                }
+            },
+            None => VanityJobState::Cancelled
+        };
+        *finished.lock().unwrap() = Some(Instant::now());
+        *state.lock().unwrap() = outcome;
+    });
+    jobs.insert(job_id.clone(), job);
+    job_id
+}
+
+#[get("/identity/vanity")]
+pub fn fetch_vanity_searches() -> String {
+    let jobs = VANITY_JOBS.lock().unwrap();
+    let statuses: Vec<HashMap<String, String>> = jobs.iter().map(|(job_id, job)| job_status(job_id, job)).collect();
+    format!("{:?}", statuses)
+}
+
+#[get("/identity/vanity/<job_id>")]
+pub fn fetch_vanity_search(job_id: &str) -> String {
+    match VANITY_JOBS.lock().unwrap().get(job_id) {
+        Some(job) => format!("{:?}", job_status(job_id, job)),
+        None => "Unknown Vanity Search".to_string()
+    }
+}
+
+#[get("/identity/vanity/cancel/<job_id>")]
+pub fn cancel_vanity_search(job_id: &str) -> String {
+    match VANITY_JOBS.lock().unwrap().get(job_id) {
+        Some(job) => {
+            job.cancel.store(true, Ordering::Relaxed);
+            "200".to_string()
+        },
+        None => "Unknown Vanity Search".to_string()
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use rocket::get;
 use logger::{debug, error, info};
 use store;
@@ -25,6 +26,37 @@ pub fn is_unlocked() -> String {
     }
 }
 
+#[get("/wallet/lock")]
+pub fn lock() -> String {
+    protocol::wallet_unlock::lock_wallet().unwrap_or_else(|err| err)
+}
+
+#[get("/wallet/session")]
+pub fn session() -> String {
+    let mut response: HashMap<String, String> = HashMap::new();
+    match protocol::wallet_unlock::get_session_expiry() {
+        Ok(Some((remaining, timeout))) => {
+            response.insert("unlocked".to_string(), "true".to_string());
+            response.insert("remaining_ms".to_string(), remaining.as_millis().to_string());
+            response.insert("timeout_ms".to_string(), timeout.as_millis().to_string());
+        },
+        Ok(None) => {
+            response.insert("unlocked".to_string(), "false".to_string());
+            response.insert("remaining_ms".to_string(), "0".to_string());
+        },
+        Err(err) => return format!("{:?}", err)
+    }
+    format!("{:?}", response)
+}
+
+#[get("/wallet/session/extend")]
+pub fn extend_session() -> String {
+    match protocol::wallet_unlock::extend_session() {
+        Ok(timeout) => format!("Wallet Session Extended For {} ms", timeout.as_millis()),
+        Err(err) => err
+    }
+}
+
 #[get("/wallet/unlock/<password>/<timeout_ms>")]
 pub fn unlock(password: String, timeout_ms: u64) -> String {
     if password.len() < MINPASSWORDLEN {
@@ -38,7 +70,18 @@ pub fn unlock(password: String, timeout_ms: u64) -> String {
     let timeout_ms = std::time::Duration::from_millis(timeout_ms);
     match store::sqlite::master_password::get_master_password(store::get_db_path().as_str()) {
         Ok(master_password) => {
-            protocol::wallet_unlock::unlock_wallet(master_password[1].as_str(), password.as_str(), timeout_ms).unwrap_or_else(|err| err)       
+            match protocol::wallet_unlock::unlock_wallet(master_password[1].as_str(), password.as_str(), timeout_ms) {
+                Ok(unlocked) => {
+                    //Move any identities still on an old encryption envelope over to the current kdf
+                    match store::sqlite::identity::upgrade_identity_encryption(store::get_db_path().as_str(), password.as_str()) {
+                        Ok(0) => {},
+                        Ok(upgraded) => info!("Upgraded Encryption For {} Identities", upgraded),
+                        Err(err) => error!("Failed To Upgrade Identity Encryption : <{}>", err)
+                    }
+                    unlocked
+                },
+                Err(err) => err
+            }
         },
         Err(_) => "Wallet Cannot Be Unlocked. Not Already Encrypted!".to_string()
     }
@@ -141,7 +184,7 @@ pub fn download_wallet(password: &str) -> String {
                 //ret_val +=
                 if password.len() < MINPASSWORDLEN {
                     is_valid = true;
-                    ret_val += &identity.seed.clone();
+                    ret_val += identity.seed.as_str();
                     ret_val += ",";
 
                     ret_val += ",";
@@ -152,7 +195,7 @@ pub fn download_wallet(password: &str) -> String {
                         match identity.decrypt_identity(password) {
                             Ok(decrypted) => {
                                 is_valid = true;
-                                ret_val += &decrypted.seed.clone();
+                                ret_val += decrypted.seed.as_str();
                                 ret_val += ",";
 
                                 ret_val += &decrypted.salt.clone();
@@ -167,7 +210,7 @@ pub fn download_wallet(password: &str) -> String {
                         }
                     } else {
                         is_valid = true;
-                        ret_val += &identity.seed.clone();
+                        ret_val += identity.seed.as_str();
                         ret_val += ",";
 
                         ret_val += &identity.salt.clone();
@@ -2,6 +2,7 @@
 extern crate dotenv_codegen;
 
 use dotenv::dotenv;
+use protocol::network_profile::get_network_profile;
 pub mod sqlite;
 
 
@@ -34,7 +35,7 @@ pub fn get_db_path() -> String {
                     };
                 }
             }
-            v
+            get_network_profile().namespace_db_path(v.as_str())
         },
         Err(_) => {
             //println!("RUBIC_DB not found in env vars! Defaulting...");
@@ -71,7 +72,7 @@ pub fn get_db_path() -> String {
                 };
             }
             default_path.push_str("rubic.sqlite");
-            return default_path;
+            return get_network_profile().namespace_db_path(default_path.as_str());
         }
     }
 }
@@ -17,4 +17,19 @@ pub mod identity;
 pub mod transfer;
 pub mod response_entity;
 pub mod asset;
-pub mod qx;
\ No newline at end of file
+pub mod qx;
+pub mod spending_policy;
+pub mod contact;
+pub mod incoming_transfer;
+pub mod tick_transaction;
+pub mod transfer_retry;
+pub mod scheduled_payment;
+pub mod payout;
+pub mod transfer_broadcast;
+pub mod transfer_check;
+pub mod preflight;
+pub mod peer_tick;
+pub mod epoch;
+pub mod tick_chain;
+pub mod quorum_report;
+pub mod tick_data;
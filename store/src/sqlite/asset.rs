@@ -233,6 +233,44 @@ pub mod asset_issuance {
 }
 
 
+//Latest possessed shares of one issuer's asset, names alone are not unique across issuers. 0 if none are recorded.
+pub fn fetch_asset_balance_by_issuer(path: &str, asset_name: &str, issuer: &str, identity: &str) -> Result<u64, String> {
+    let prep_query = "SELECT r.num_shares AS balance FROM asset_record r INNER JOIN asset_issuance i ON i.id = r.asset_id \
+    WHERE i.name = :asset_name AND i.pub_key = :issuer AND r.identity = :identity AND r.record_type = 'P' \
+    ORDER BY r.tick DESC LIMIT 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":asset_name", asset_name),
+                        (":issuer", issuer),
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => Ok(statement.read::<i64, _>("balance").unwrap() as u64),
+                                Ok(State::Done) => Ok(0),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_asset_balance_by_issuer! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_asset_balance_by_issuer! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
 pub fn fetch_asset_balance(path: &str, asset_name: &str, identity: &str) -> Result<HashMap<String, String>, String> {
     let prep_query = "
                         SELECT asset.tick, asset.num_shares as balance,
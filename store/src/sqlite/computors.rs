@@ -214,3 +214,36 @@ pub fn fetch_latest_computors(path: &str) -> Result<[u8; 2 + 676*32 + 64], Strin
         }
     }
 }
+
+//Epoch of the newest computor list we hold, None until the first BroadcastComputors arrives
+pub fn fetch_latest_epoch(path: &str) -> Result<Option<u16>, String> {
+    let _lock = get_db_lock().lock().unwrap();
+    let prep_query = "SELECT epoch FROM computors ORDER BY epoch DESC LIMIT 1;";
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.next() {
+                        Ok(State::Row) => {
+                            let epoch_string: String = statement.read::<String, _>("epoch").unwrap();
+                            match u16::from_str(epoch_string.as_str()) {
+                                Ok(epoch) => Ok(Some(epoch)),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Ok(State::Done) => Ok(None),
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_latest_epoch! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_latest_epoch! : {}", &err);
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+pub fn create_contact(path: &str, label: &str, identity: &str, notes: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO contact (label, identity, notes) VALUES (:label, :identity, :notes);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":label", label),
+                        (":identity", identity),
+                        (":notes", notes),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_contact! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_contact! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn update_contact_notes(path: &str, label: &str, notes: &str) -> Result<(), String> {
+    let prep_query = "UPDATE contact SET notes = :notes WHERE label = :label;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":label", label),
+                        (":notes", notes),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => {
+                                    match connection.change_count() {
+                                        0 => Err("Contact Not Found!".to_string()),
+                                        _ => Ok(())
+                                    }
+                                },
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in update_contact_notes! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in update_contact_notes! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_contact_by_label(path: &str, label: &str) -> Result<HashMap<String, String>, String> {
+    let prep_query = "SELECT * FROM contact WHERE label = :label LIMIT 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":label", label),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let mut contact: HashMap<String, String> = HashMap::new();
+                                    contact.insert("label".to_string(), statement.read::<String, _>("label").unwrap());
+                                    contact.insert("identity".to_string(), statement.read::<String, _>("identity").unwrap());
+                                    contact.insert("notes".to_string(), statement.read::<String, _>("notes").unwrap());
+                                    contact.insert("created".to_string(), statement.read::<String, _>("created").unwrap());
+                                    Ok(contact)
+                                },
+                                Ok(State::Done) => Err("Contact Not Found!".to_string()),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_contact_by_label! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_contact_by_label! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_all_contacts(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM contact ORDER BY label ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut response: Vec<HashMap<String, String>> = Vec::new();
+                    while let Ok(State::Row) = statement.next() {
+                        let mut contact: HashMap<String, String> = HashMap::new();
+                        contact.insert("label".to_string(), statement.read::<String, _>("label").unwrap());
+                        contact.insert("identity".to_string(), statement.read::<String, _>("identity").unwrap());
+                        contact.insert("notes".to_string(), statement.read::<String, _>("notes").unwrap());
+                        contact.insert("created".to_string(), statement.read::<String, _>("created").unwrap());
+                        response.push(contact);
+                    }
+                    Ok(response)
+                },
+                Err(err) => {
+                    error!("Error in fetch_all_contacts! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_all_contacts! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn delete_contact(path: &str, label: &str) -> Result<(), String> {
+    let prep_query = "DELETE FROM contact WHERE label = :label;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":label", label),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in delete_contact! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in delete_contact! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_contact {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::contact::{create_contact, delete_contact, fetch_all_contacts, fetch_contact_by_label, update_contact_notes};
+
+    #[test]
+    #[serial]
+    fn create_contact_and_fetch_and_update_and_delete() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            create_contact("test.sqlite", "qx", "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID", "exchange").unwrap();
+            assert!(create_contact("test.sqlite", "qx", "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON", "").is_err());
+            let contact = fetch_contact_by_label("test.sqlite", "qx").unwrap();
+            assert_eq!(contact.get("identity").unwrap().as_str(), "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID");
+            assert_eq!(contact.get("notes").unwrap().as_str(), "exchange");
+            update_contact_notes("test.sqlite", "qx", "contract").unwrap();
+            assert!(update_contact_notes("test.sqlite", "missing", "contract").is_err());
+            assert_eq!(fetch_all_contacts("test.sqlite").unwrap()[0].get("notes").unwrap().as_str(), "contract");
+            delete_contact("test.sqlite", "qx").unwrap();
+            assert!(fetch_contact_by_label("test.sqlite", "qx").is_err());
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -132,6 +132,222 @@ pub fn open_database(path: &str, create: bool) -> Result<sqlite::Connection, Str
         created DATETIME DEFAULT CURRENT_TIMESTAMP,
         PRIMARY KEY(asset, side, entity, price, num_shares)
     );
+
+    CREATE TABLE IF NOT EXISTS spending_policy (
+        identity TEXT UNIQUE NOT NULL PRIMARY KEY,
+        max_per_transfer UNSIGNED INTEGER NOT NULL DEFAULT 0,
+        daily_limit UNSIGNED INTEGER NOT NULL DEFAULT 0,
+        confirm_threshold UNSIGNED INTEGER NOT NULL DEFAULT 0,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(identity) REFERENCES identities(identity) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS spending_allowlist (
+        identity TEXT NOT NULL,
+        destination TEXT NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(identity) REFERENCES identities(identity) ON DELETE CASCADE,
+        UNIQUE (identity, destination) ON CONFLICT IGNORE
+    );
+
+    CREATE TABLE IF NOT EXISTS contact (
+        label TEXT UNIQUE NOT NULL PRIMARY KEY,
+        identity TEXT NOT NULL,
+        notes TEXT NOT NULL DEFAULT '',
+        created DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS spending_confirmation (
+        token TEXT UNIQUE NOT NULL PRIMARY KEY,
+        identity TEXT NOT NULL,
+        destination TEXT NOT NULL,
+        amount UNSIGNED INTEGER NOT NULL,
+        confirmed BOOLEAN DEFAULT false,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(identity) REFERENCES identities(identity) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS resend_policy (
+        identity TEXT UNIQUE NOT NULL PRIMARY KEY,
+        max_retries INTEGER NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(identity) REFERENCES identities(identity) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS transfer_retry (
+        txid TEXT UNIQUE NOT NULL,
+        previous_txid TEXT UNIQUE NOT NULL,
+        original_txid TEXT NOT NULL,
+        attempt INTEGER NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS tick_transaction (
+        txid TEXT UNIQUE NOT NULL,
+        tick UNSIGNED INTEGER NOT NULL,
+        source_identity TEXT NOT NULL,
+        destination_identity TEXT NOT NULL,
+        amount UNSIGNED INTEGER NOT NULL,
+        input_type INTEGER NOT NULL,
+        input_size INTEGER NOT NULL,
+        input TEXT NOT NULL DEFAULT '',
+        signature TEXT NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS incoming_tick (
+        identity TEXT NOT NULL,
+        tick UNSIGNED INTEGER NOT NULL,
+        indexed BOOLEAN DEFAULT false,
+        attempts INTEGER DEFAULT 0,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (identity, tick) ON CONFLICT IGNORE,
+        FOREIGN KEY(identity) REFERENCES identities(identity) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS incoming_transfer (
+        txid TEXT UNIQUE NOT NULL,
+        source_identity TEXT NOT NULL,
+        destination_identity TEXT NOT NULL,
+        amount UNSIGNED INTEGER NOT NULL,
+        tick UNSIGNED INTEGER NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(destination_identity) REFERENCES identities(identity) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS scheduled_payment (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        source_identity TEXT NOT NULL,
+        destination_identity TEXT NOT NULL,
+        amount UNSIGNED INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        interval_ticks UNSIGNED INTEGER DEFAULT 0,
+        next_tick UNSIGNED INTEGER DEFAULT 0,
+        last_epoch UNSIGNED INTEGER DEFAULT 0,
+        enabled BOOLEAN DEFAULT true,
+        key_seed TEXT NOT NULL DEFAULT '',
+        key_salt TEXT NOT NULL DEFAULT '',
+        key_hash TEXT NOT NULL DEFAULT '',
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(source_identity) REFERENCES identities(identity) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS scheduled_payment_run (
+        schedule_id INTEGER NOT NULL,
+        txid TEXT UNIQUE NOT NULL,
+        tick UNSIGNED INTEGER NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY(schedule_id) REFERENCES scheduled_payment(id) ON DELETE CASCADE
+    );
+
+    CREATE TABLE IF NOT EXISTS transfer_broadcast (
+        txid TEXT NOT NULL,
+        peer TEXT NOT NULL,
+        status TEXT CHECK( status IN ('queued','sent','failed','acked') ) NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        updated DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (txid, peer)
+    );
+
+    CREATE TABLE IF NOT EXISTS transfer_check (
+        txid TEXT UNIQUE NOT NULL,
+        checked_at_tick UNSIGNED INTEGER NOT NULL,
+        outcome TEXT CHECK( outcome IN ('included','not_included','expired') ) NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS payout_recipient (
+        batch_id TEXT NOT NULL,
+        txid TEXT NOT NULL,
+        recipient_index INTEGER NOT NULL,
+        destination_identity TEXT NOT NULL,
+        amount UNSIGNED INTEGER NOT NULL,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE (txid, recipient_index)
+    );
+
+    CREATE TABLE IF NOT EXISTS peer_tick_info (
+        peer TEXT UNIQUE NOT NULL,
+        tick UNSIGNED INTEGER NOT NULL,
+        epoch INTEGER NOT NULL,
+        tick_duration INTEGER NOT NULL,
+        aligned_votes INTEGER NOT NULL,
+        misaligned_votes INTEGER NOT NULL,
+        initial_tick UNSIGNED INTEGER NOT NULL,
+        outlier BOOLEAN DEFAULT false,
+        updated DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS peer_score (
+        peer TEXT UNIQUE NOT NULL,
+        tick_reports INTEGER NOT NULL DEFAULT 0,
+        tick_outliers INTEGER NOT NULL DEFAULT 0,
+        last_outlier_tick UNSIGNED INTEGER,
+        updated DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS epoch_history (
+        epoch INTEGER UNIQUE NOT NULL,
+        initial_tick UNSIGNED INTEGER NOT NULL DEFAULT 0,
+        detected DATETIME DEFAULT CURRENT_TIMESTAMP,
+        computors_validated DATETIME
+    );
+
+    CREATE TABLE IF NOT EXISTS tick_pending_validation (
+        tick UNSIGNED INTEGER UNIQUE NOT NULL,
+        epoch INTEGER NOT NULL,
+        requests INTEGER NOT NULL DEFAULT 0,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS tick_chain (
+        tick UNSIGNED INTEGER UNIQUE NOT NULL,
+        epoch INTEGER,
+        status INTEGER NOT NULL DEFAULT 0,
+        requests INTEGER NOT NULL DEFAULT 0,
+        digests TEXT,
+        detail TEXT,
+        updated DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS tick_quorum_report (
+        tick UNSIGNED INTEGER UNIQUE NOT NULL,
+        epoch INTEGER NOT NULL,
+        passed BOOLEAN NOT NULL,
+        required_votes INTEGER NOT NULL,
+        winning_digest TEXT NOT NULL DEFAULT '',
+        winning_votes INTEGER NOT NULL DEFAULT 0,
+        vote_groups TEXT NOT NULL DEFAULT '',
+        invalid_signatures TEXT NOT NULL DEFAULT '',
+        missing_computors TEXT NOT NULL DEFAULT '',
+        updated DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS light_sync (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        start_tick UNSIGNED INTEGER NOT NULL,
+        cursor UNSIGNED INTEGER NOT NULL,
+        updated DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS tick_data (
+        tick UNSIGNED INTEGER UNIQUE NOT NULL,
+        epoch INTEGER NOT NULL,
+        computor_index INTEGER NOT NULL,
+        timestamp DATETIME NOT NULL DEFAULT '',
+        unix_ms INTEGER NOT NULL DEFAULT 0,
+        transaction_count INTEGER NOT NULL,
+        total_contract_fees INTEGER NOT NULL DEFAULT 0,
+        created DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS tick_contract_fee (
+        tick UNSIGNED INTEGER NOT NULL,
+        contract_index INTEGER NOT NULL,
+        fee INTEGER NOT NULL,
+        UNIQUE(tick, contract_index)
+    );
 ";
     //        FOREIGN KEY(identity) REFERENCES identities(identity)
     match sqlite::open(path) {
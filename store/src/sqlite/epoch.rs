@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//Every epoch we have seen the network enter, and when its computor list was validated.
+//Ticks whose quorum votes couldn't be checked because their epoch's computor list was missing wait in tick_pending_validation.
+
+fn execute_epoch_statement(path: &str, prep_query: &str, params: &[(&str, &str)], fn_name: &str) -> Result<(), String> {
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(params) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in {}! : {}", fn_name, &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in {}! : {}", fn_name, &err);
+            Err(err)
+        }
+    }
+}
+
+//An initial tick of 0 means it isn't known yet and is filled in by a later report
+pub fn record_epoch(path: &str, epoch: u16, initial_tick: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO epoch_history (epoch, initial_tick) VALUES (:epoch, :initial_tick) \
+    ON CONFLICT(epoch) DO UPDATE SET initial_tick = :initial_tick WHERE initial_tick = 0;";
+    execute_epoch_statement(path, prep_query, &[
+        (":epoch", epoch.to_string().as_str()),
+        (":initial_tick", initial_tick.to_string().as_str()),
+    ], "record_epoch")
+}
+
+pub fn set_epoch_computors_validated(path: &str, epoch: u16) -> Result<(), String> {
+    let prep_query = "INSERT INTO epoch_history (epoch, computors_validated) VALUES (:epoch, CURRENT_TIMESTAMP) \
+    ON CONFLICT(epoch) DO UPDATE SET computors_validated = CURRENT_TIMESTAMP WHERE computors_validated IS NULL;";
+    execute_epoch_statement(path, prep_query, &[
+        (":epoch", epoch.to_string().as_str()),
+    ], "set_epoch_computors_validated")
+}
+
+pub fn fetch_epoch_history(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT e.epoch, e.initial_tick, e.detected, COALESCE(e.computors_validated, '') AS computors_validated, \
+    (SELECT COUNT(*) FROM tick_pending_validation p WHERE p.epoch = e.epoch) AS pending_ticks \
+    FROM epoch_history e ORDER BY e.epoch DESC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut response: Vec<HashMap<String, String>> = Vec::new();
+                    while let Ok(State::Row) = statement.next() {
+                        let mut epoch: HashMap<String, String> = HashMap::new();
+                        for column in ["epoch", "initial_tick", "detected", "computors_validated", "pending_ticks"] {
+                            epoch.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                        }
+                        response.push(epoch);
+                    }
+                    Ok(response)
+                },
+                Err(err) => {
+                    error!("Error in fetch_epoch_history! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_epoch_history! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn insert_pending_tick_validation(path: &str, tick: u32, epoch: u16) -> Result<(), String> {
+    let prep_query = "INSERT INTO tick_pending_validation (tick, epoch) VALUES (:tick, :epoch) ON CONFLICT(tick) DO NOTHING;";
+    execute_epoch_statement(path, prep_query, &[
+        (":tick", tick.to_string().as_str()),
+        (":epoch", epoch.to_string().as_str()),
+    ], "insert_pending_tick_validation")
+}
+
+pub fn delete_pending_tick_validation(path: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "DELETE FROM tick_pending_validation WHERE tick = :tick;";
+    execute_epoch_statement(path, prep_query, &[
+        (":tick", tick.to_string().as_str()),
+    ], "delete_pending_tick_validation")
+}
+
+//Each re-request counts, so a tick that never validates stops being asked for
+pub fn increment_pending_tick_requests(path: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "UPDATE tick_pending_validation SET requests = requests + 1 WHERE tick = :tick;";
+    execute_epoch_statement(path, prep_query, &[
+        (":tick", tick.to_string().as_str()),
+    ], "increment_pending_tick_requests")
+}
+
+//(tick, epoch, whether that epoch's computor list is now stored) for pending ticks asked for fewer than max_requests times, ready ones first
+pub fn fetch_pending_tick_validations(path: &str, max_requests: u32, limit: u32) -> Result<Vec<(u32, u16, bool)>, String> {
+    let prep_query = "SELECT p.tick, p.epoch, EXISTS (SELECT 1 FROM computors c WHERE c.epoch = p.epoch) AS has_computors \
+    FROM tick_pending_validation p WHERE p.requests < :max_requests ORDER BY has_computors DESC, p.tick ASC LIMIT :limit;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":max_requests", max_requests.to_string().as_str()),
+                        (":limit", limit.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<(u32, u16, bool)> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let tick: i64 = statement.read::<i64, _>("tick").unwrap();
+                                let epoch: i64 = statement.read::<i64, _>("epoch").unwrap();
+                                let has_computors: i64 = statement.read::<i64, _>("has_computors").unwrap();
+                                response.push((tick as u32, epoch as u16, has_computors == 1));
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_pending_tick_validations! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_pending_tick_validations! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_epoch {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::computors::insert_computors_from_parts;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::epoch::{delete_pending_tick_validation, fetch_epoch_history, fetch_pending_tick_validations, increment_pending_tick_requests, insert_pending_tick_validation, record_epoch, set_epoch_computors_validated};
+
+    #[test]
+    #[serial]
+    fn track_epochs_and_pending_ticks() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            record_epoch("test.sqlite", 150, 0).unwrap();
+            record_epoch("test.sqlite", 150, 17_000_000).unwrap();
+            record_epoch("test.sqlite", 150, 17_000_500).unwrap();
+            record_epoch("test.sqlite", 151, 17_500_000).unwrap();
+            set_epoch_computors_validated("test.sqlite", 151).unwrap();
+            let history = fetch_epoch_history("test.sqlite").unwrap();
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].get("epoch").unwrap().as_str(), "151");
+            assert_ne!(history[0].get("computors_validated").unwrap().as_str(), "");
+            assert_eq!(history[1].get("initial_tick").unwrap().as_str(), "17000000");
+            assert_eq!(history[1].get("computors_validated").unwrap().as_str(), "");
+
+            insert_pending_tick_validation("test.sqlite", 17_500_010, 151).unwrap();
+            insert_pending_tick_validation("test.sqlite", 17_500_011, 151).unwrap();
+            insert_pending_tick_validation("test.sqlite", 17_500_010, 151).unwrap();
+            assert_eq!(fetch_pending_tick_validations("test.sqlite", 3, 10).unwrap(), vec![(17_500_010, 151, false), (17_500_011, 151, false)]);
+            assert_eq!(fetch_epoch_history("test.sqlite").unwrap()[0].get("pending_ticks").unwrap().as_str(), "2");
+
+            //Once the computor list arrives the ticks are ready to be asked for again
+            insert_computors_from_parts("test.sqlite", 151, &vec![vec![0u8; 32]; 676], &vec![0u8; 64]).unwrap();
+            assert_eq!(fetch_pending_tick_validations("test.sqlite", 3, 1).unwrap(), vec![(17_500_010, 151, true)]);
+            for _ in 0..3 {
+                increment_pending_tick_requests("test.sqlite", 17_500_010).unwrap();
+            }
+            delete_pending_tick_validation("test.sqlite", 17_500_011).unwrap();
+            assert!(fetch_pending_tick_validations("test.sqlite", 3, 10).unwrap().is_empty());
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -93,6 +93,26 @@ pub fn update_identity_encrypted(path: &str, identity: &Identity) -> Result<(),
         }
     }
 }
+//Re-encrypts identities still using a legacy or outdated encryption envelope, returns how many were upgraded
+pub fn upgrade_identity_encryption(path: &str, password: &str) -> Result<u32, String> {
+    let mut upgraded: u32 = 0;
+    for mut id in fetch_all_identities_full(path)? {
+        if !id.needs_encryption_upgrade() {
+            continue;
+        }
+        match id.decrypt_identity(password) {
+            Ok(mut decrypted) => {
+                let encrypted = decrypted.encrypt_identity(password)?;
+                update_identity_encrypted(path, &encrypted)?;
+                upgraded += 1;
+            },
+            Err(err) => {
+                error!("Failed To Upgrade Encryption For Identity.({}) : <{}>", &id.identity, err);
+            }
+        }
+    }
+    Ok(upgraded)
+}
 pub fn fetch_all_identities(path: &str) -> Result<LinkedList<String>, String> {
     let prep_query = "SELECT identity FROM identities;";
     let _lock = get_db_lock().lock().unwrap();
@@ -270,6 +290,12 @@ pub fn fetch_balance_by_identity(path: &str, identity: &str) -> Result<Vec<Strin
         }
     }
 }
+//Lowest balance reported by the peers that agreed on the latest tick, None until at least 2 have answered
+pub fn fetch_confirmed_balance(path: &str, identity: &str) -> Result<Option<u64>, String> {
+    let balances = fetch_balance_by_identity(path, identity)?;
+    Ok(balances.chunks_exact(3).filter_map(|row| row[2].parse::<u64>().ok()).min())
+}
+
 pub fn fetch_identity(path: &str, identity: &str) -> Result<Identity, String> {
     let prep_query = "SELECT * FROM identities WHERE identity = :identity LIMIT 1;";
     let _lock = get_db_lock().lock().unwrap();
@@ -363,7 +389,7 @@ pub fn delete_identity(path: &str, identity: &str) -> Result<(), String> {
 
 pub mod test_identities {
     use protocol::identity::Identity;
-    use crate::sqlite::identity::{insert_new_identity, fetch_identity, delete_identity};
+    use crate::sqlite::identity::{insert_new_identity, fetch_identity, delete_identity, upgrade_identity_encryption};
     use serial_test::serial;
     use std::fs;
     #[test]
@@ -473,4 +499,27 @@ pub mod test_identities {
         }
         fs::remove_file("test.sqlite").unwrap();
     }
+    #[test]
+    #[serial]
+    fn upgrade_a_legacy_encrypted_identity() {
+        {
+            crate::sqlite::create::open_database("test.sqlite", true).unwrap();
+            let kdf = crypto::encryption::Kdf::Pbkdf2Sha256 { iterations: crypto::encryption::LEGACY_PBKDF2_ITERATIONS };
+            let (salt, encrypted) = crypto::encryption::encrypt_with_kdf("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf", "password", &kdf).unwrap();
+            let id: Identity = Identity::from_vars(
+                hex::encode(&encrypted).as_str(),
+                crypto::passwords::hash_password("password").unwrap().as_str(),
+                hex::encode(&salt[15..]).as_str(),
+                "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON",
+                true
+            );
+            insert_new_identity("test.sqlite", &id).unwrap();
+            assert_eq!(upgrade_identity_encryption("test.sqlite", "password").unwrap(), 1);
+            let mut upgraded = fetch_identity("test.sqlite", "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON").unwrap();
+            assert_eq!(upgraded.needs_encryption_upgrade(), false);
+            assert_eq!(upgraded.decrypt_identity("password").unwrap().seed.as_str(), "lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+            assert_eq!(upgrade_identity_encryption("test.sqlite", "password").unwrap(), 0);
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
 }
\ No newline at end of file
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+pub fn queue_incoming_tick(path: &str, identity: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO incoming_tick (identity, tick) VALUES (:identity, :tick);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in queue_incoming_tick! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in queue_incoming_tick! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_unindexed_incoming_ticks(path: &str, latest_tick: u32, max_attempts: u32) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM incoming_tick WHERE indexed = false AND tick <= :latest_tick AND attempts < :max_attempts ORDER BY tick ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":latest_tick", latest_tick.to_string().as_str()),
+                        (":max_attempts", max_attempts.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut pending: HashMap<String, String> = HashMap::new();
+                                pending.insert("identity".to_string(), statement.read::<String, _>("identity").unwrap());
+                                pending.insert("tick".to_string(), statement.read::<String, _>("tick").unwrap());
+                                pending.insert("attempts".to_string(), statement.read::<String, _>("attempts").unwrap());
+                                response.push(pending);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_unindexed_incoming_ticks! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_unindexed_incoming_ticks! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn increment_incoming_tick_attempts(path: &str, identity: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "UPDATE incoming_tick SET attempts = attempts + 1 WHERE identity = :identity AND tick = :tick;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in increment_incoming_tick_attempts! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in increment_incoming_tick_attempts! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn set_incoming_tick_indexed(path: &str, identity: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "UPDATE incoming_tick SET indexed = true WHERE identity = :identity AND tick = :tick;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in set_incoming_tick_indexed! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in set_incoming_tick_indexed! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//True if any of our identities is still waiting on transactions from this tick
+pub fn is_incoming_tick_pending(path: &str, tick: u32) -> Result<bool, String> {
+    let prep_query = "SELECT COUNT(*) AS pending FROM incoming_tick WHERE tick = :tick AND indexed = false;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => Ok(statement.read::<i64, _>("pending").unwrap() > 0),
+                                Ok(State::Done) => Ok(false),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in is_incoming_tick_pending! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in is_incoming_tick_pending! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn create_incoming_transfer(path: &str, txid: &str, source: &str, destination: &str, amount: u64, tick: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO incoming_transfer (txid, source_identity, destination_identity, amount, tick) VALUES (:txid, :source, :destination, :amount, :tick) ON CONFLICT(txid) DO NOTHING;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                        (":source", source),
+                        (":destination", destination),
+                        (":amount", amount.to_string().as_str()),
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_incoming_transfer! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_incoming_transfer! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_incoming_transfers_by_identity(path: &str, identity: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM incoming_transfer WHERE destination_identity = :identity ORDER BY tick DESC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut transfer: HashMap<String, String> = HashMap::new();
+                                transfer.insert("txid".to_string(), statement.read::<String, _>("txid").unwrap());
+                                transfer.insert("source_identity".to_string(), statement.read::<String, _>("source_identity").unwrap());
+                                transfer.insert("destination_identity".to_string(), statement.read::<String, _>("destination_identity").unwrap());
+                                transfer.insert("amount".to_string(), statement.read::<String, _>("amount").unwrap());
+                                transfer.insert("tick".to_string(), statement.read::<String, _>("tick").unwrap());
+                                transfer.insert("created".to_string(), statement.read::<String, _>("created").unwrap());
+                                response.push(transfer);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_incoming_transfers_by_identity! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_incoming_transfers_by_identity! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_incoming_transfer {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use protocol::identity::Identity;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::incoming_transfer::{create_incoming_transfer, fetch_incoming_transfers_by_identity, fetch_unindexed_incoming_ticks, increment_incoming_tick_attempts, is_incoming_tick_pending, queue_incoming_tick, set_incoming_tick_indexed};
+
+    const IDENTITY: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const SOURCE: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    #[test]
+    #[serial]
+    fn queue_an_incoming_tick_and_index_a_transfer() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            let id: Identity = Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+            insert_new_identity("test.sqlite", &id).unwrap();
+
+            queue_incoming_tick("test.sqlite", IDENTITY, 100).unwrap();
+            queue_incoming_tick("test.sqlite", IDENTITY, 100).unwrap();
+            queue_incoming_tick("test.sqlite", IDENTITY, 200).unwrap();
+            assert_eq!(fetch_unindexed_incoming_ticks("test.sqlite", 150, 3).unwrap().len(), 1);
+            assert!(is_incoming_tick_pending("test.sqlite", 100).unwrap());
+
+            for _ in 0..3 {
+                increment_incoming_tick_attempts("test.sqlite", IDENTITY, 200).unwrap();
+            }
+            assert_eq!(fetch_unindexed_incoming_ticks("test.sqlite", 250, 3).unwrap().len(), 1);
+
+            create_incoming_transfer("test.sqlite", "txid", SOURCE, IDENTITY, 1000, 100).unwrap();
+            create_incoming_transfer("test.sqlite", "txid", SOURCE, IDENTITY, 1000, 100).unwrap();
+            set_incoming_tick_indexed("test.sqlite", IDENTITY, 100).unwrap();
+            assert!(!is_incoming_tick_pending("test.sqlite", 100).unwrap());
+
+            let transfers = fetch_incoming_transfers_by_identity("test.sqlite", IDENTITY).unwrap();
+            assert_eq!(transfers.len(), 1);
+            assert_eq!(transfers[0].get("amount").unwrap().as_str(), "1000");
+            assert_eq!(transfers[0].get("source_identity").unwrap().as_str(), SOURCE);
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
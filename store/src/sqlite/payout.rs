@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//One signed send many call of a payout and the recipients packed into it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayoutCall {
+    pub txid: String,
+    pub amount: u64,
+    pub tick: u32,
+    pub signature: String,
+    pub recipients: Vec<(String, u64)>
+}
+
+fn step(statement: &mut sqlite::Statement, values: &[(&str, &str)]) -> Result<(), String> {
+    statement.reset().map_err(|err| err.to_string())?;
+    match statement.bind::<&[(&str, &str)]>(values) {
+        Ok(_) => {
+            match statement.next() {
+                Ok(State::Done) => Ok(()),
+                Err(error) => Err(error.to_string()),
+                _ => Err("Weird!".to_string())
+            }
+        },
+        Err(err) => Err(err.to_string())
+    }
+}
+
+//Every call of a payout is queued or none is, so a failed payout never leaves earlier calls to broadcast.
+//Recipients go in with their transfer so the broadcaster never sees a call without them.
+pub fn create_payout(path: &str, batch_id: &str, source: &str, destination: &str, calls: &[PayoutCall]) -> Result<(), String> {
+    let recipient_query = "INSERT INTO payout_recipient (batch_id, txid, recipient_index, destination_identity, amount) VALUES (:batch_id, :txid, :index, :destination, :amount);";
+    let transfer_query = "INSERT INTO transfer (source_identity, destination_identity, amount, tick, signature, txid) VALUES (:source, :destination, :amount, :tick, :signature, :txid);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            connection.execute("BEGIN TRANSACTION;").unwrap();
+            let result = prepare_crud_statement(&connection, recipient_query).and_then(|mut recipient_statement| {
+                let mut transfer_statement = prepare_crud_statement(&connection, transfer_query)?;
+                for call in calls.iter() {
+                    for (index, (recipient, amount)) in call.recipients.iter().enumerate() {
+                        step(&mut recipient_statement, &[
+                            (":batch_id", batch_id),
+                            (":txid", call.txid.as_str()),
+                            (":index", index.to_string().as_str()),
+                            (":destination", recipient.as_str()),
+                            (":amount", amount.to_string().as_str()),
+                        ])?;
+                    }
+                    step(&mut transfer_statement, &[
+                        (":source", source),
+                        (":destination", destination),
+                        (":amount", call.amount.to_string().as_str()),
+                        (":tick", call.tick.to_string().as_str()),
+                        (":signature", call.signature.as_str()),
+                        (":txid", call.txid.as_str()),
+                    ])?;
+                }
+                Ok(())
+            });
+            match result {
+                Ok(_) => match connection.execute("COMMIT;") {
+                    Ok(_) => Ok(()),
+                    Err(err) => Err(err.to_string())
+                },
+                Err(err) => {
+                    connection.execute("ROLLBACK;").unwrap();
+                    error!("Error in create_payout! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_payout! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//(destination, amount) in packing order, empty if the txid is not a send many call
+pub fn fetch_payout_recipients_by_txid(path: &str, txid: &str) -> Result<Vec<(String, u64)>, String> {
+    let prep_query = "SELECT destination_identity, amount FROM payout_recipient WHERE txid = :txid ORDER BY recipient_index ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<(String, u64)> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let destination = statement.read::<String, _>("destination_identity").unwrap();
+                                let amount = statement.read::<i64, _>("amount").unwrap() as u64;
+                                response.push((destination, amount));
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_payout_recipients_by_txid! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_payout_recipients_by_txid! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Every recipient of a payout with the broadcast/status of the call that pays them
+pub fn fetch_payout_batch(path: &str, batch_id: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT p.txid, p.recipient_index, p.destination_identity, p.amount, \
+    COALESCE(t.tick, 0) AS tick, COALESCE(t.broadcast, 0) AS broadcast, COALESCE(t.status, -1) AS status \
+    FROM payout_recipient p LEFT JOIN transfer t ON t.txid = p.txid \
+    WHERE p.batch_id = :batch_id ORDER BY p.rowid ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":batch_id", batch_id),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut recipient: HashMap<String, String> = HashMap::new();
+                                for column in ["txid", "recipient_index", "destination_identity", "amount", "tick", "broadcast", "status"] {
+                                    recipient.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(recipient);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_payout_batch! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_payout_batch! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_payout {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use protocol::identity::Identity;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::payout::{create_payout, fetch_payout_batch, fetch_payout_recipients_by_txid, PayoutCall};
+    use crate::sqlite::transfer::fetch_transfer_by_txid;
+
+    const FIRST: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const SECOND: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    #[test]
+    #[serial]
+    fn create_and_fetch_payout_recipients() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            insert_new_identity("test.sqlite", &Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf")).unwrap();
+            let recipients = vec![(FIRST.to_string(), 100), (SECOND.to_string(), 250)];
+            let call = |txid: &str, recipients: &[(String, u64)]| PayoutCall {
+                txid: txid.to_string(),
+                amount: recipients.iter().map(|(_, amount)| *amount).sum(),
+                tick: 1100,
+                signature: "ab".to_string(),
+                recipients: recipients.to_vec()
+            };
+            create_payout("test.sqlite", "txa", FIRST, SECOND, &[call("txa", &recipients), call("txb", &recipients[..1])]).unwrap();
+            //The whole payout is rejected if any call fails to insert, earlier calls included
+            assert!(create_payout("test.sqlite", "txc", FIRST, SECOND, &[call("txc", &recipients), call("txb", &recipients)]).is_err());
+            assert!(fetch_payout_recipients_by_txid("test.sqlite", "txc").unwrap().is_empty());
+            assert!(fetch_transfer_by_txid("test.sqlite", "txc").unwrap().is_empty());
+
+            assert_eq!(fetch_payout_recipients_by_txid("test.sqlite", "txa").unwrap(), recipients);
+            assert_eq!(fetch_payout_recipients_by_txid("test.sqlite", "txb").unwrap().len(), 1);
+            assert_eq!(fetch_transfer_by_txid("test.sqlite", "txb").unwrap()[0].get("amount").unwrap().as_str(), "100");
+
+            let batch = fetch_payout_batch("test.sqlite", "txa").unwrap();
+            assert_eq!(batch.len(), 3);
+            assert_eq!(batch[1].get("destination_identity").unwrap().as_str(), SECOND);
+            assert_eq!(batch[1].get("status").unwrap().as_str(), "-1");
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//The latest CurrentTickInfo each peer reported, replaced on every report
+pub fn record_peer_tick_info(path: &str, peer: &str, tick: u32, epoch: u16, tick_duration: u16, aligned_votes: u16, misaligned_votes: u16, initial_tick: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO peer_tick_info (peer, tick, epoch, tick_duration, aligned_votes, misaligned_votes, initial_tick) \
+    VALUES (:peer, :tick, :epoch, :tick_duration, :aligned_votes, :misaligned_votes, :initial_tick) \
+    ON CONFLICT(peer) DO UPDATE SET tick = :tick, epoch = :epoch, tick_duration = :tick_duration, aligned_votes = :aligned_votes, \
+    misaligned_votes = :misaligned_votes, initial_tick = :initial_tick, updated = CURRENT_TIMESTAMP;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":peer", peer),
+                        (":tick", tick.to_string().as_str()),
+                        (":epoch", epoch.to_string().as_str()),
+                        (":tick_duration", tick_duration.to_string().as_str()),
+                        (":aligned_votes", aligned_votes.to_string().as_str()),
+                        (":misaligned_votes", misaligned_votes.to_string().as_str()),
+                        (":initial_tick", initial_tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in record_peer_tick_info! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in record_peer_tick_info! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//(peer, tick) for every peer that has reported within the last max_age_secs
+pub fn fetch_recent_peer_ticks(path: &str, max_age_secs: u32) -> Result<Vec<(String, u32)>, String> {
+    let prep_query = "SELECT peer, tick FROM peer_tick_info WHERE updated >= datetime('now', '-' || :max_age || ' seconds');";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":max_age", max_age_secs.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<(String, u32)> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let peer: String = statement.read::<String, _>("peer").unwrap();
+                                let tick: i64 = statement.read::<i64, _>("tick").unwrap();
+                                response.push((peer, tick as u32));
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_recent_peer_ticks! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_recent_peer_ticks! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//(epoch, initial tick) for every peer that has reported within the last max_age_secs
+pub fn fetch_recent_peer_epochs(path: &str, max_age_secs: u32) -> Result<Vec<(u16, u32)>, String> {
+    let prep_query = "SELECT epoch, initial_tick FROM peer_tick_info WHERE updated >= datetime('now', '-' || :max_age || ' seconds');";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":max_age", max_age_secs.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<(u16, u32)> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let epoch: i64 = statement.read::<i64, _>("epoch").unwrap();
+                                let initial_tick: i64 = statement.read::<i64, _>("initial_tick").unwrap();
+                                response.push((epoch as u16, initial_tick as u32));
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_recent_peer_epochs! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_recent_peer_epochs! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Scores a peer's tick report against the quorum, counting every report and every outlier
+pub fn score_peer_tick_report(path: &str, peer: &str, tick: u32, outlier: bool) -> Result<(), String> {
+    let prep_query = "INSERT INTO peer_score (peer, tick_reports, tick_outliers, last_outlier_tick) \
+    VALUES (:peer, 1, :outlier, CASE WHEN CAST(:outlier AS INTEGER) = 1 THEN :tick ELSE NULL END) \
+    ON CONFLICT(peer) DO UPDATE SET tick_reports = tick_reports + 1, tick_outliers = tick_outliers + :outlier, \
+    last_outlier_tick = CASE WHEN CAST(:outlier AS INTEGER) = 1 THEN :tick ELSE last_outlier_tick END, updated = CURRENT_TIMESTAMP;";
+    let flag_query = "UPDATE peer_tick_info SET outlier = :outlier WHERE peer = :peer;";
+    let outlier: &str = if outlier { "1" } else { "0" };
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            for query in [prep_query, flag_query] {
+                let mut statement = match prepare_crud_statement(&connection, query) {
+                    Ok(statement) => statement,
+                    Err(err) => {
+                        error!("Error in score_peer_tick_report! : {}", &err);
+                        return Err(err);
+                    }
+                };
+                let mut params: Vec<(&str, &str)> = vec![(":peer", peer), (":outlier", outlier)];
+                let tick = tick.to_string();
+                if query == prep_query {
+                    params.push((":tick", tick.as_str()));
+                }
+                match statement.bind::<&[(&str, &str)]>(&params[..]) {
+                    Ok(_) => {
+                        match statement.next() {
+                            Ok(State::Done) => {},
+                            Err(error) => return Err(error.to_string()),
+                            _ => return Err("Weird!".to_string())
+                        }
+                    },
+                    Err(err) => return Err(err.to_string())
+                }
+            }
+            Ok(())
+        },
+        Err(err) => {
+            error!("Error in score_peer_tick_report! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_peer_tick_infos(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT i.peer, COALESCE(p.ip, '') AS ip, i.tick, i.epoch, i.tick_duration, i.aligned_votes, i.misaligned_votes, i.initial_tick, \
+    i.outlier, i.updated, COALESCE(s.tick_reports, 0) AS tick_reports, COALESCE(s.tick_outliers, 0) AS tick_outliers, \
+    COALESCE(s.last_outlier_tick, '') AS last_outlier_tick \
+    FROM peer_tick_info i LEFT JOIN peer p ON p.id = i.peer LEFT JOIN peer_score s ON s.peer = i.peer ORDER BY i.tick DESC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut response: Vec<HashMap<String, String>> = Vec::new();
+                    while let Ok(State::Row) = statement.next() {
+                        let mut info: HashMap<String, String> = HashMap::new();
+                        for column in ["peer", "ip", "tick", "epoch", "tick_duration", "aligned_votes", "misaligned_votes", "initial_tick",
+                            "outlier", "updated", "tick_reports", "tick_outliers", "last_outlier_tick"] {
+                            info.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                        }
+                        response.push(info);
+                    }
+                    Ok(response)
+                },
+                Err(err) => {
+                    error!("Error in fetch_peer_tick_infos! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_peer_tick_infos! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_peer_tick {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::peer_tick::{fetch_peer_tick_infos, fetch_recent_peer_epochs, fetch_recent_peer_ticks, record_peer_tick_info, score_peer_tick_report};
+
+    #[test]
+    #[serial]
+    fn record_and_score_peer_ticks() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            record_peer_tick_info("test.sqlite", "p1", 100, 150, 2000, 451, 0, 90).unwrap();
+            record_peer_tick_info("test.sqlite", "p2", 4_000_000, 150, 2000, 451, 0, 90).unwrap();
+            record_peer_tick_info("test.sqlite", "p1", 101, 150, 2000, 451, 0, 90).unwrap();
+            let mut ticks = fetch_recent_peer_ticks("test.sqlite", 30).unwrap();
+            ticks.sort();
+            assert_eq!(ticks, vec![("p1".to_string(), 101), ("p2".to_string(), 4_000_000)]);
+            assert_eq!(fetch_recent_peer_epochs("test.sqlite", 30).unwrap(), vec![(150, 90), (150, 90)]);
+
+            score_peer_tick_report("test.sqlite", "p1", 101, false).unwrap();
+            score_peer_tick_report("test.sqlite", "p2", 4_000_000, true).unwrap();
+            score_peer_tick_report("test.sqlite", "p2", 4_000_001, true).unwrap();
+            let infos = fetch_peer_tick_infos("test.sqlite").unwrap();
+            let p2 = infos.iter().find(|i| i.get("peer").unwrap() == "p2").unwrap();
+            assert_eq!(p2.get("outlier").unwrap().as_str(), "1");
+            assert_eq!(p2.get("tick_reports").unwrap().as_str(), "2");
+            assert_eq!(p2.get("tick_outliers").unwrap().as_str(), "2");
+            assert_eq!(p2.get("last_outlier_tick").unwrap().as_str(), "4000001");
+            let p1 = infos.iter().find(|i| i.get("peer").unwrap() == "p1").unwrap();
+            assert_eq!(p1.get("outlier").unwrap().as_str(), "0");
+            assert_eq!(p1.get("tick_outliers").unwrap().as_str(), "0");
+            assert_eq!(p1.get("epoch").unwrap().as_str(), "150");
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use sqlite::State;
+use logger::error;
+use smart_contract::qx::asset_transfer::QX_TRANSFER_SHARE;
+use smart_contract::qx::QxProcedure;
+use crate::sqlite::asset::fetch_asset_balance_by_issuer;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+use crate::sqlite::identity::fetch_confirmed_balance;
+use crate::sqlite::transfer::fetch_pending_outgoing_amount;
+
+//Checks run before a transaction is signed, so a transfer that can't be covered is rejected with the reason instead of queued
+
+//Shares of an asset held by unconfirmed share transfers and ask orders from this identity, expired unbroadcast ones excluded
+pub fn fetch_pending_outgoing_shares(path: &str, identity: &str, asset_name: &str, issuer: &str) -> Result<u64, String> {
+    let prep_query = "SELECT COALESCE(SUM(shares), 0) AS pending FROM (
+        SELECT a.num_shares AS shares FROM asset_transfer a INNER JOIN transfer t ON t.txid = a.txid
+            WHERE t.source_identity = :identity AND t.status = -1 AND (t.broadcast = true OR t.tick > COALESCE((SELECT MAX(tick) FROM tick), 0)) AND a.name = :name AND a.issuer = :issuer AND a.input_type = :transfer_share
+        UNION ALL
+        SELECT q.num_shares AS shares FROM qx_order q INNER JOIN transfer t ON t.txid = q.txid
+            WHERE t.source_identity = :identity AND t.status = -1 AND (t.broadcast = true OR t.tick > COALESCE((SELECT MAX(tick) FROM tick), 0)) AND q.name = :name AND q.issuer = :issuer AND q.input_type = :add_ask
+    );";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":name", asset_name),
+                        (":issuer", issuer),
+                        (":transfer_share", QX_TRANSFER_SHARE.to_string().as_str()),
+                        (":add_ask", (QxProcedure::QxAddAskOrder as u16).to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => Ok(statement.read::<i64, _>("pending").unwrap() as u64),
+                                Ok(State::Done) => Ok(0),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_pending_outgoing_shares! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_pending_outgoing_shares! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//QU escrowed by unconfirmed bid orders from this identity, already counted in its pending outgoing amount
+pub fn fetch_pending_bid_escrow(path: &str, identity: &str) -> Result<u64, String> {
+    let prep_query = "SELECT COALESCE(SUM(t.amount), 0) AS pending FROM qx_order q INNER JOIN transfer t ON t.txid = q.txid
+        WHERE t.source_identity = :identity AND t.status = -1 AND (t.broadcast = true OR t.tick > COALESCE((SELECT MAX(tick) FROM tick), 0)) AND q.input_type = :add_bid;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":add_bid", (QxProcedure::QxAddBidOrder as u16).to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => Ok(statement.read::<i64, _>("pending").unwrap() as u64),
+                                Ok(State::Done) => Ok(0),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_pending_bid_escrow! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_pending_bid_escrow! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//(confirmed, pending outgoing, available), None until enough peers have reported a balance.
+//Pending covers plain transfers, share transfer fees, QX fees and bid escrow alike, but not ones that expired unbroadcast.
+pub fn fetch_available_balance(path: &str, identity: &str) -> Result<Option<(u64, u64, u64)>, String> {
+    let balance: u64 = match fetch_confirmed_balance(path, identity)? {
+        Some(balance) => balance,
+        None => return Ok(None)
+    };
+    let pending: u64 = fetch_pending_outgoing_amount(path, identity)?;
+    Ok(Some((balance, pending, balance.saturating_sub(pending))))
+}
+
+//(possessed, pending outgoing, available) shares of an asset
+pub fn fetch_available_shares(path: &str, identity: &str, asset_name: &str, issuer: &str) -> Result<(u64, u64, u64), String> {
+    let possessed: u64 = fetch_asset_balance_by_issuer(path, asset_name, issuer, identity)?;
+    let pending: u64 = fetch_pending_outgoing_shares(path, identity, asset_name, issuer)?;
+    Ok((possessed, pending, possessed.saturating_sub(pending)))
+}
+
+//Confirmed balance less every unconfirmed outgoing transfer must cover amount
+pub fn check_available_balance(path: &str, identity: &str, amount: u64) -> Result<(), String> {
+    let (balance, pending, available) = match fetch_available_balance(path, identity)? {
+        Some(balances) => balances,
+        None => return Err(format!("Balance Of {} Is Not Yet Confirmed By Enough Peers, Try Again Once Synced", identity))
+    };
+    if amount > available {
+        return Err(format!("Insufficient Balance: {} QU Required, {} QU Available ({} Confirmed, {} Pending Outgoing)", amount, available, balance, pending));
+    }
+    Ok(())
+}
+
+//Latest possessed shares less unconfirmed share transfers and asks must cover shares
+pub fn check_available_shares(path: &str, identity: &str, asset_name: &str, issuer: &str, shares: u64) -> Result<(), String> {
+    let (possessed, pending, available) = fetch_available_shares(path, identity, asset_name, issuer)?;
+    if shares > available {
+        return Err(format!("Insufficient {} Shares: {} Required, {} Available ({} Possessed, {} Pending Outgoing)", asset_name, shares, available, possessed, pending));
+    }
+    Ok(())
+}
+
+pub mod test_preflight {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use std::time::SystemTime;
+    use protocol::identity::Identity;
+    use crate::sqlite::asset::asset_issuance::create_asset_issuance;
+    use crate::sqlite::asset::asset_record::create_asset_possession;
+    use crate::sqlite::asset::asset_transfer::create_asset_transfer;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::peer::create_peer;
+    use crate::sqlite::preflight::{check_available_balance, check_available_shares, fetch_available_balance, fetch_available_shares, fetch_pending_bid_escrow, fetch_pending_outgoing_shares};
+    use crate::sqlite::qx::order::create_qx_order;
+    use crate::sqlite::response_entity::create_response_entity;
+    use crate::sqlite::tick::insert_tick;
+    use crate::sqlite::transfer::{create_transfer, set_broadcasted_transfer_as_success, set_transfer_as_broadcast};
+
+    const SOURCE: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const QX: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    #[test]
+    #[serial]
+    fn reject_transfers_that_cant_be_covered() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            insert_new_identity("test.sqlite", &Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf")).unwrap();
+            let asset_id = create_asset_issuance("test.sqlite", "peer", QX, 2, "QX", 0, 0).unwrap();
+            create_asset_possession("test.sqlite", asset_id, SOURCE, 1, 0, 100, 1000).unwrap();
+            //Same name from another issuer, more recent, must not be mistaken for it
+            let other_id = create_asset_issuance("test.sqlite", "peer", SOURCE, 2, "QX", 0, 0).unwrap();
+            create_asset_possession("test.sqlite", other_id, SOURCE, 1, 0, 500, 1001).unwrap();
+
+            //No peers have reported a balance yet
+            assert!(check_available_balance("test.sqlite", SOURCE, 1).unwrap_err().contains("Not Yet Confirmed"));
+
+            create_transfer("test.sqlite", SOURCE, QX, 1000000, 1100, "ab", "txa").unwrap();
+            create_asset_transfer("test.sqlite", QX, QX, 30, "QX", 80, 2, "txa").unwrap();
+            create_transfer("test.sqlite", SOURCE, QX, 1, 1100, "ab", "txb").unwrap();
+            create_qx_order("test.sqlite", QX, 5, 20, "QX", 56, 5, "txb").unwrap();
+            //Bids don't hold shares
+            create_transfer("test.sqlite", SOURCE, QX, 50, 1100, "ab", "txc").unwrap();
+            create_qx_order("test.sqlite", QX, 5, 10, "QX", 56, 6, "txc").unwrap();
+            assert_eq!(fetch_pending_outgoing_shares("test.sqlite", SOURCE, "QX", QX).unwrap(), 50);
+            assert_eq!(fetch_available_shares("test.sqlite", SOURCE, "QX", QX).unwrap(), (100, 50, 50));
+            assert_eq!(fetch_pending_bid_escrow("test.sqlite", SOURCE).unwrap(), 50);
+            assert!(fetch_available_balance("test.sqlite", SOURCE).unwrap().is_none());
+
+            //Two peers agreeing on a tick confirm the lower balance
+            for (id, ip, balance) in [("p1", "1.1.1.1", 1000100), ("p2", "2.2.2.2", 1000200)] {
+                create_peer("test.sqlite", id, ip, "", 0, false, SystemTime::now()).unwrap();
+                create_response_entity("test.sqlite", id, SOURCE, balance, 0, balance, 1, 0, 900, 0, 1000, 1).unwrap();
+            }
+            assert_eq!(fetch_available_balance("test.sqlite", SOURCE).unwrap(), Some((1000100, 1000051, 49)));
+            assert!(check_available_balance("test.sqlite", SOURCE, 49).is_ok());
+            assert_eq!(check_available_balance("test.sqlite", SOURCE, 50).unwrap_err(), "Insufficient Balance: 50 QU Required, 49 QU Available (1000100 Confirmed, 1000051 Pending Outgoing)");
+
+            assert!(check_available_shares("test.sqlite", SOURCE, "QX", QX, 50).is_ok());
+            let err = check_available_shares("test.sqlite", SOURCE, "QX", QX, 51).unwrap_err();
+            assert_eq!(err, "Insufficient QX Shares: 51 Required, 50 Available (100 Possessed, 50 Pending Outgoing)");
+
+            //Once confirmed they no longer count as pending
+            set_transfer_as_broadcast("test.sqlite", "txa").unwrap();
+            set_broadcasted_transfer_as_success("test.sqlite", "txa").unwrap();
+            assert_eq!(fetch_pending_outgoing_shares("test.sqlite", SOURCE, "QX", QX).unwrap(), 20);
+            assert!(check_available_shares("test.sqlite", SOURCE, "NOPE", QX, 1).is_err());
+
+            //Never broadcast by its tick, it can't be included any more and releases its amount and shares
+            insert_tick("test.sqlite", "p1", 1100).unwrap();
+            assert_eq!(fetch_available_balance("test.sqlite", SOURCE).unwrap(), Some((1000100, 0, 1000100)));
+            assert_eq!(fetch_pending_outgoing_shares("test.sqlite", SOURCE, "QX", QX).unwrap(), 0);
+            assert_eq!(fetch_pending_bid_escrow("test.sqlite", SOURCE).unwrap(), 0);
+            assert!(check_available_balance("test.sqlite", SOURCE, 1000100).is_ok());
+            //Broadcast ones stay pending until the confirmer settles them
+            create_transfer("test.sqlite", SOURCE, QX, 70, 1100, "ab", "txd").unwrap();
+            set_transfer_as_broadcast("test.sqlite", "txd").unwrap();
+            assert!(check_available_balance("test.sqlite", SOURCE, 1000031).unwrap_err().contains("70 Pending Outgoing"));
+            create_transfer("test.sqlite", SOURCE, QX, 5, 1101, "ab", "txe").unwrap();
+            assert_eq!(fetch_available_balance("test.sqlite", SOURCE).unwrap(), Some((1000100, 75, 1000025)));
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//A consensus::quorum_votes::QuorumReport flattened for storage.
+//vote_groups is digest:votes pairs, largest first, the computor lists are comma separated indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuorumReportRecord {
+    pub tick: u32,
+    pub epoch: u16,
+    pub passed: bool,
+    pub required_votes: u16,
+    pub winning_digest: String,
+    pub winning_votes: u16,
+    pub vote_groups: String,
+    pub invalid_signatures: String,
+    pub missing_computors: String
+}
+
+//A passing report is never replaced by a later failing one, e.g. a peer returning fewer votes
+pub fn record_quorum_report(path: &str, report: &QuorumReportRecord) -> Result<(), String> {
+    let prep_query = "INSERT INTO tick_quorum_report (tick, epoch, passed, required_votes, winning_digest, winning_votes, vote_groups, invalid_signatures, missing_computors) \
+    VALUES (:tick, :epoch, CAST(:passed AS INTEGER), :required_votes, :winning_digest, :winning_votes, :vote_groups, :invalid_signatures, :missing_computors) \
+    ON CONFLICT(tick) DO UPDATE SET epoch = excluded.epoch, passed = excluded.passed, required_votes = excluded.required_votes, \
+    winning_digest = excluded.winning_digest, winning_votes = excluded.winning_votes, vote_groups = excluded.vote_groups, \
+    invalid_signatures = excluded.invalid_signatures, missing_computors = excluded.missing_computors, updated = CURRENT_TIMESTAMP \
+    WHERE passed = 0 OR excluded.passed = 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", report.tick.to_string().as_str()),
+                        (":epoch", report.epoch.to_string().as_str()),
+                        (":passed", if report.passed { "1" } else { "0" }),
+                        (":required_votes", report.required_votes.to_string().as_str()),
+                        (":winning_digest", report.winning_digest.as_str()),
+                        (":winning_votes", report.winning_votes.to_string().as_str()),
+                        (":vote_groups", report.vote_groups.as_str()),
+                        (":invalid_signatures", report.invalid_signatures.as_str()),
+                        (":missing_computors", report.missing_computors.as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in record_quorum_report! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in record_quorum_report! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_quorum_report(path: &str, tick: u32) -> Result<Option<HashMap<String, String>>, String> {
+    let prep_query = "SELECT tick, epoch, CASE WHEN passed = 1 THEN 'true' ELSE 'false' END AS passed, required_votes, winning_digest, winning_votes, \
+    vote_groups, invalid_signatures, missing_computors, updated FROM tick_quorum_report WHERE tick = :tick;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let mut report: HashMap<String, String> = HashMap::new();
+                                    for column in ["tick", "epoch", "passed", "required_votes", "winning_digest", "winning_votes", "vote_groups", "invalid_signatures", "missing_computors", "updated"] {
+                                        report.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                    }
+                                    Ok(Some(report))
+                                },
+                                Ok(State::Done) => Ok(None),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_quorum_report! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_quorum_report! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_quorum_report {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::quorum_report::{fetch_quorum_report, record_quorum_report, QuorumReportRecord};
+
+    #[test]
+    #[serial]
+    fn keep_quorum_reports_per_tick() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            assert_eq!(fetch_quorum_report("test.sqlite", 100).unwrap(), None);
+            let mut report = QuorumReportRecord {
+                tick: 100,
+                epoch: 150,
+                passed: false,
+                required_votes: 451,
+                winning_digest: "ab".to_string(),
+                winning_votes: 300,
+                vote_groups: "ab:300,cd:2".to_string(),
+                invalid_signatures: "7".to_string(),
+                missing_computors: "1,2,3".to_string()
+            };
+            record_quorum_report("test.sqlite", &report).unwrap();
+            report.passed = true;
+            report.winning_votes = 460;
+            record_quorum_report("test.sqlite", &report).unwrap();
+            let passing = report.clone();
+            report.passed = false;
+            report.winning_votes = 12;
+            record_quorum_report("test.sqlite", &report).unwrap();
+
+            let stored = fetch_quorum_report("test.sqlite", 100).unwrap().unwrap();
+            assert_eq!(stored.get("passed").unwrap().as_str(), "true");
+            assert_eq!(stored.get("winning_votes").unwrap().as_str(), "460");
+            assert_eq!(stored.get("vote_groups").unwrap().as_str(), passing.vote_groups.as_str());
+            assert_eq!(stored.get("invalid_signatures").unwrap().as_str(), "7");
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+pub const AT_TICK: &str = "at_tick";
+pub const EVERY_N_TICKS: &str = "every_n_ticks";
+pub const EPOCH_START: &str = "epoch_start";
+
+const SCHEDULE_COLUMNS: [&str; 10] = ["id", "source_identity", "destination_identity", "amount", "kind", "interval_ticks", "next_tick", "last_epoch", "enabled", "created"];
+
+//next_tick drives at_tick and every_n_ticks schedules, last_epoch drives epoch_start schedules
+pub fn create_scheduled_payment(path: &str, source: &str, destination: &str, amount: u64, kind: &str, interval_ticks: u32, next_tick: u32, last_epoch: u16) -> Result<(), String> {
+    let prep_query = "INSERT INTO scheduled_payment (source_identity, destination_identity, amount, kind, interval_ticks, next_tick, last_epoch) \
+    VALUES (:source, :destination, :amount, :kind, :interval_ticks, :next_tick, :last_epoch);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":source", source),
+                        (":destination", destination),
+                        (":amount", amount.to_string().as_str()),
+                        (":kind", kind),
+                        (":interval_ticks", interval_ticks.to_string().as_str()),
+                        (":next_tick", next_tick.to_string().as_str()),
+                        (":last_epoch", last_epoch.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_scheduled_payment! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_scheduled_payment! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Never returns the dedicated key, only whether the schedule has one
+pub fn fetch_scheduled_payments(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT *, key_seed != '' AS has_key FROM scheduled_payment ORDER BY id ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut response: Vec<HashMap<String, String>> = Vec::new();
+                    while let Ok(State::Row) = statement.next() {
+                        let mut schedule: HashMap<String, String> = HashMap::new();
+                        for column in SCHEDULE_COLUMNS.iter().chain(["has_key"].iter()) {
+                            schedule.insert(column.to_string(), statement.read::<String, _>(*column).unwrap());
+                        }
+                        response.push(schedule);
+                    }
+                    Ok(response)
+                },
+                Err(err) => {
+                    error!("Error in fetch_scheduled_payments! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_scheduled_payments! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Enabled schedules whose tick has been reached, or whose epoch has rolled over. Includes the dedicated key columns.
+pub fn fetch_due_scheduled_payments(path: &str, latest_tick: u32, epoch: u16) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM scheduled_payment WHERE enabled = true AND ( \
+        (kind IN ('at_tick', 'every_n_ticks') AND next_tick <= :tick) \
+        OR (kind = 'epoch_start' AND last_epoch < :epoch) \
+    ) ORDER BY id ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", latest_tick.to_string().as_str()),
+                        (":epoch", epoch.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut schedule: HashMap<String, String> = HashMap::new();
+                                for column in SCHEDULE_COLUMNS.iter().chain(["key_seed", "key_salt", "key_hash"].iter()) {
+                                    schedule.insert(column.to_string(), statement.read::<String, _>(*column).unwrap());
+                                }
+                                response.push(schedule);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_due_scheduled_payments! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_due_scheduled_payments! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Moves a schedule past the run we are about to make, a one-off schedule is disabled instead
+pub fn advance_scheduled_payment(path: &str, id: i64, next_tick: u32, last_epoch: u16, enabled: bool) -> Result<(), String> {
+    let prep_query = "UPDATE scheduled_payment SET next_tick = :next_tick, last_epoch = :last_epoch, enabled = :enabled WHERE id = :id;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":next_tick", next_tick.to_string().as_str()),
+                        (":last_epoch", last_epoch.to_string().as_str()),
+                        (":enabled", if enabled { "1" } else { "0" }),
+                        (":id", id.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in advance_scheduled_payment! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in advance_scheduled_payment! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//The source seed encrypted under a scheduler-only password, so the schedule can run without unlocking the wallet
+pub fn set_scheduled_payment_key(path: &str, id: i64, seed: &str, salt: &str, hash: &str) -> Result<(), String> {
+    let prep_query = "UPDATE scheduled_payment SET key_seed = :seed, key_salt = :salt, key_hash = :hash WHERE id = :id;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":seed", seed),
+                        (":salt", salt),
+                        (":hash", hash),
+                        (":id", id.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in set_scheduled_payment_key! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in set_scheduled_payment_key! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Password hashes of every dedicated key, for checking a scheduler password before it is kept
+pub fn fetch_scheduled_payment_key_hashes(path: &str) -> Result<Vec<String>, String> {
+    let prep_query = "SELECT key_hash FROM scheduled_payment WHERE key_seed != '' ORDER BY id ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut response: Vec<String> = Vec::new();
+                    while let Ok(State::Row) = statement.next() {
+                        response.push(statement.read::<String, _>("key_hash").unwrap());
+                    }
+                    Ok(response)
+                },
+                Err(err) => {
+                    error!("Error in fetch_scheduled_payment_key_hashes! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_scheduled_payment_key_hashes! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn delete_scheduled_payment(path: &str, id: i64) -> Result<(), String> {
+    let prep_query = "DELETE FROM scheduled_payment WHERE id = :id;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":id", id.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in delete_scheduled_payment! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in delete_scheduled_payment! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn create_scheduled_payment_run(path: &str, id: i64, txid: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO scheduled_payment_run (schedule_id, txid, tick) VALUES (:id, :txid, :tick);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":id", id.to_string().as_str()),
+                        (":txid", txid),
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_scheduled_payment_run! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_scheduled_payment_run! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Each run with the broadcast/status of the transfer it created
+pub fn fetch_scheduled_payment_runs(path: &str, id: i64) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT r.txid, r.tick, r.created, COALESCE(t.broadcast, 0) AS broadcast, COALESCE(t.status, -1) AS status \
+    FROM scheduled_payment_run r LEFT JOIN transfer t ON t.txid = r.txid \
+    WHERE r.schedule_id = :id ORDER BY r.tick DESC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":id", id.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut run: HashMap<String, String> = HashMap::new();
+                                for column in ["txid", "tick", "broadcast", "status", "created"] {
+                                    run.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(run);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_scheduled_payment_runs! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_scheduled_payment_runs! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_scheduled_payment {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use protocol::identity::Identity;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::scheduled_payment::*;
+    use crate::sqlite::transfer::create_transfer;
+
+    const SOURCE: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const DESTINATION: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    #[test]
+    #[serial]
+    fn schedule_advance_and_track_runs() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            insert_new_identity("test.sqlite", &Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf")).unwrap();
+            create_scheduled_payment("test.sqlite", SOURCE, DESTINATION, 10, AT_TICK, 0, 500, 0).unwrap();
+            create_scheduled_payment("test.sqlite", SOURCE, DESTINATION, 20, EVERY_N_TICKS, 100, 450, 0).unwrap();
+            create_scheduled_payment("test.sqlite", SOURCE, DESTINATION, 30, EPOCH_START, 0, 0, 120).unwrap();
+
+            assert_eq!(fetch_due_scheduled_payments("test.sqlite", 400, 120).unwrap().len(), 0);
+            let due = fetch_due_scheduled_payments("test.sqlite", 460, 121).unwrap();
+            assert_eq!(due.len(), 2);
+            assert_eq!(due[0].get("kind").unwrap().as_str(), EVERY_N_TICKS);
+            assert_eq!(due[1].get("kind").unwrap().as_str(), EPOCH_START);
+
+            let every_id: i64 = due[0].get("id").unwrap().parse().unwrap();
+            advance_scheduled_payment("test.sqlite", every_id, 550, 0, true).unwrap();
+            create_scheduled_payment_run("test.sqlite", every_id, "txa", 470).unwrap();
+            create_transfer("test.sqlite", SOURCE, DESTINATION, 20, 470, "ab", "txa").unwrap();
+            assert!(create_scheduled_payment_run("test.sqlite", every_id, "txa", 470).is_err());
+
+            let runs = fetch_scheduled_payment_runs("test.sqlite", every_id).unwrap();
+            assert_eq!(runs.len(), 1);
+            assert_eq!(runs[0].get("status").unwrap().as_str(), "-1");
+
+            let at_tick_id: i64 = fetch_due_scheduled_payments("test.sqlite", 500, 120).unwrap()[0].get("id").unwrap().parse().unwrap();
+            advance_scheduled_payment("test.sqlite", at_tick_id, 500, 0, false).unwrap();
+            assert_eq!(fetch_due_scheduled_payments("test.sqlite", 600, 120).unwrap().len(), 1);
+
+            assert!(fetch_scheduled_payment_key_hashes("test.sqlite").unwrap().is_empty());
+            set_scheduled_payment_key("test.sqlite", at_tick_id, "seed", "salt", "hash").unwrap();
+            assert_eq!(fetch_scheduled_payment_key_hashes("test.sqlite").unwrap(), vec!["hash".to_string()]);
+            let all = fetch_scheduled_payments("test.sqlite").unwrap();
+            assert_eq!(all.len(), 3);
+            assert_eq!(all[0].get("has_key").unwrap().as_str(), "1");
+            assert!(all[0].get("key_seed").is_none());
+
+            delete_scheduled_payment("test.sqlite", at_tick_id).unwrap();
+            assert_eq!(fetch_scheduled_payments("test.sqlite").unwrap().len(), 2);
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
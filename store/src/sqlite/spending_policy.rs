@@ -0,0 +1,492 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//A limit of 0 means that check is disabled
+pub fn set_spending_policy(path: &str, identity: &str, max_per_transfer: u64, daily_limit: u64, confirm_threshold: u64) -> Result<(), String> {
+    let prep_query = "INSERT INTO spending_policy (identity, max_per_transfer, daily_limit, confirm_threshold) VALUES (:identity, :max_per_transfer, :daily_limit, :confirm_threshold) \
+    ON CONFLICT(identity) DO UPDATE SET max_per_transfer = :max_per_transfer, daily_limit = :daily_limit, confirm_threshold = :confirm_threshold;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":max_per_transfer", max_per_transfer.to_string().as_str()),
+                        (":daily_limit", daily_limit.to_string().as_str()),
+                        (":confirm_threshold", confirm_threshold.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in set_spending_policy! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in set_spending_policy! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_spending_policy(path: &str, identity: &str) -> Result<Option<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM spending_policy WHERE identity = :identity LIMIT 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let mut policy: HashMap<String, String> = HashMap::new();
+                                    policy.insert("identity".to_string(), statement.read::<String, _>("identity").unwrap());
+                                    policy.insert("max_per_transfer".to_string(), statement.read::<String, _>("max_per_transfer").unwrap());
+                                    policy.insert("daily_limit".to_string(), statement.read::<String, _>("daily_limit").unwrap());
+                                    policy.insert("confirm_threshold".to_string(), statement.read::<String, _>("confirm_threshold").unwrap());
+                                    policy.insert("created".to_string(), statement.read::<String, _>("created").unwrap());
+                                    Ok(Some(policy))
+                                },
+                                Ok(State::Done) => Ok(None),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_spending_policy! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_spending_policy! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn delete_spending_policy(path: &str, identity: &str) -> Result<(), String> {
+    let prep_query = "DELETE FROM spending_policy WHERE identity = :identity;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in delete_spending_policy! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in delete_spending_policy! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn add_allowed_destination(path: &str, identity: &str, destination: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO spending_allowlist (identity, destination) VALUES (:identity, :destination);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":destination", destination),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in add_allowed_destination! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in add_allowed_destination! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn remove_allowed_destination(path: &str, identity: &str, destination: &str) -> Result<(), String> {
+    let prep_query = "DELETE FROM spending_allowlist WHERE identity = :identity AND destination = :destination;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":destination", destination),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in remove_allowed_destination! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in remove_allowed_destination! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_allowed_destinations(path: &str, identity: &str) -> Result<Vec<String>, String> {
+    let prep_query = "SELECT destination FROM spending_allowlist WHERE identity = :identity ORDER BY created ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut ret_val: Vec<String> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                ret_val.push(statement.read::<String, _>("destination").unwrap());
+                            }
+                            Ok(ret_val)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_allowed_destinations! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_allowed_destinations! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Sum of everything sent from an identity in the last 24 hours, transfers known to have failed don't count
+pub fn fetch_amount_spent_last_day(path: &str, identity: &str) -> Result<u64, String> {
+    let prep_query = "SELECT CAST(COALESCE(SUM(amount), 0) AS TEXT) AS spent FROM transfer \
+    WHERE source_identity = :identity AND status != 1 AND created >= datetime('now', '-1 day');";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    match statement.read::<String, _>("spent").unwrap().parse::<u64>() {
+                                        Ok(spent) => Ok(spent),
+                                        Err(err) => Err(err.to_string())
+                                    }
+                                },
+                                Ok(State::Done) => Ok(0),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_amount_spent_last_day! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_amount_spent_last_day! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn create_spending_confirmation(path: &str, token: &str, identity: &str, destination: &str, amount: u64) -> Result<(), String> {
+    let prep_query = "INSERT INTO spending_confirmation (token, identity, destination, amount) VALUES (:token, :identity, :destination, :amount);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":token", token),
+                        (":identity", identity),
+                        (":destination", destination),
+                        (":amount", amount.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_spending_confirmation! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_spending_confirmation! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Confirmations are only good for an hour
+pub fn confirm_spending_confirmation(path: &str, token: &str) -> Result<(), String> {
+    let prep_query = "UPDATE spending_confirmation SET confirmed = true WHERE token = :token AND created >= datetime('now', '-1 hour');";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":token", token),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => {
+                                    match connection.change_count() {
+                                        0 => Err("Unknown Or Expired Confirmation".to_string()),
+                                        _ => Ok(())
+                                    }
+                                },
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in confirm_spending_confirmation! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in confirm_spending_confirmation! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Deletes a confirmed, unexpired confirmation matching this exact transfer. Returns whether one existed
+pub fn consume_spending_confirmation(path: &str, identity: &str, destination: &str, amount: u64) -> Result<bool, String> {
+    let prep_query = "DELETE FROM spending_confirmation WHERE token = (SELECT token FROM spending_confirmation \
+    WHERE identity = :identity AND destination = :destination AND amount = :amount AND confirmed = true AND created >= datetime('now', '-1 hour') LIMIT 1);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":destination", destination),
+                        (":amount", amount.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(connection.change_count() > 0),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in consume_spending_confirmation! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in consume_spending_confirmation! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Checks a transfer against the source identity's policy before it is signed and stored.
+//Above the confirm threshold a pending confirmation is created and its token returned in the error.
+pub fn enforce_spending_policy(path: &str, identity: &str, destination: &str, amount: u64) -> Result<(), String> {
+    let confirm_threshold: u64 = match check_spending_limits(path, identity, destination, amount)? {
+        Some(policy) => policy.get("confirm_threshold").unwrap().parse().unwrap_or(0),
+        None => return Ok(())
+    };
+    if confirm_threshold > 0 && amount > confirm_threshold && !consume_spending_confirmation(path, identity, destination, amount)? {
+        let token = hex::encode(crypto::random::random_bytes(16));
+        create_spending_confirmation(path, token.as_str(), identity, destination, amount)?;
+        return Err(format!("Spending Policy: Confirmation Required, Confirm With Token {}", token));
+    }
+    Ok(())
+}
+
+//Limits and allow list only, for re-sending a payment that was already confirmed once
+pub fn check_spending_limits(path: &str, identity: &str, destination: &str, amount: u64) -> Result<Option<HashMap<String, String>>, String> {
+    let policy = match fetch_spending_policy(path, identity)? {
+        Some(policy) => policy,
+        None => return Ok(None)
+    };
+    let max_per_transfer: u64 = policy.get("max_per_transfer").unwrap().parse().unwrap_or(0);
+    let daily_limit: u64 = policy.get("daily_limit").unwrap().parse().unwrap_or(0);
+
+    if max_per_transfer > 0 && amount > max_per_transfer {
+        return Err(format!("Spending Policy: Amount {} Exceeds Max Per Transfer Of {}", amount, max_per_transfer));
+    }
+    let allowed = fetch_allowed_destinations(path, identity)?;
+    if !allowed.is_empty() && !allowed.iter().any(|d| d.as_str() == destination) {
+        return Err(format!("Spending Policy: Destination {} Is Not On The Allow List", destination));
+    }
+    if daily_limit > 0 {
+        let spent = fetch_amount_spent_last_day(path, identity)?;
+        if spent.saturating_add(amount) > daily_limit {
+            return Err(format!("Spending Policy: Daily Limit Of {} Exceeded, {} Already Spent In The Last 24 Hours", daily_limit, spent));
+        }
+    }
+    Ok(Some(policy))
+}
+
+
+pub mod test_spending_policy {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use protocol::identity::Identity;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::transfer::create_transfer;
+    use crate::sqlite::spending_policy::{add_allowed_destination, confirm_spending_confirmation, enforce_spending_policy, fetch_allowed_destinations, fetch_spending_policy, remove_allowed_destination, set_spending_policy};
+
+    const SOURCE: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const DEST: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    fn setup() {
+        open_database("test.sqlite", true).unwrap();
+        let id: Identity = Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+        insert_new_identity("test.sqlite", &id).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn set_and_fetch_a_spending_policy() {
+        {
+            setup();
+            assert!(fetch_spending_policy("test.sqlite", SOURCE).unwrap().is_none());
+            set_spending_policy("test.sqlite", SOURCE, 100, 1000, 50).unwrap();
+            set_spending_policy("test.sqlite", SOURCE, 200, 1000, 50).unwrap();
+            let policy = fetch_spending_policy("test.sqlite", SOURCE).unwrap().unwrap();
+            assert_eq!(policy.get("max_per_transfer").unwrap().as_str(), "200");
+            assert_eq!(policy.get("daily_limit").unwrap().as_str(), "1000");
+            add_allowed_destination("test.sqlite", SOURCE, DEST).unwrap();
+            add_allowed_destination("test.sqlite", SOURCE, DEST).unwrap();
+            assert_eq!(fetch_allowed_destinations("test.sqlite", SOURCE).unwrap(), vec![DEST.to_string()]);
+            remove_allowed_destination("test.sqlite", SOURCE, DEST).unwrap();
+            assert!(fetch_allowed_destinations("test.sqlite", SOURCE).unwrap().is_empty());
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn enforce_limits_and_allow_list() {
+        {
+            setup();
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 1_000_000).is_ok());
+            set_spending_policy("test.sqlite", SOURCE, 500, 1000, 0).unwrap();
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 600).unwrap_err().contains("Max Per Transfer"));
+            create_transfer("test.sqlite", SOURCE, DEST, 400, 1, "sig", "txid1").unwrap();
+            create_transfer("test.sqlite", SOURCE, DEST, 400, 2, "sig", "txid2").unwrap();
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 200).is_ok());
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 300).unwrap_err().contains("Daily Limit"));
+            add_allowed_destination("test.sqlite", SOURCE, SOURCE).unwrap();
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 100).unwrap_err().contains("Allow List"));
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, SOURCE, 100).is_ok());
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn require_a_second_confirmation_above_threshold() {
+        {
+            setup();
+            set_spending_policy("test.sqlite", SOURCE, 0, 0, 100).unwrap();
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 100).is_ok());
+            let err = enforce_spending_policy("test.sqlite", SOURCE, DEST, 150).unwrap_err();
+            let token = err.split(' ').last().unwrap().to_string();
+            assert!(confirm_spending_confirmation("test.sqlite", "bogus").is_err());
+            //Unconfirmed tokens don't let the transfer through
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 150).is_err());
+            confirm_spending_confirmation("test.sqlite", token.as_str()).unwrap();
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 151).is_err());
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 150).is_ok());
+            //Confirmations are single use
+            assert!(enforce_spending_policy("test.sqlite", SOURCE, DEST, 150).is_err());
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -5,6 +5,9 @@ use crate::sqlite::create::open_database;
 use base64::Engine;
 use base64::engine::general_purpose;
 use crate::sqlite::get_db_lock;
+use protocol::tick_rate::{estimate_tick_rate, TickRateEstimate};
+
+const TICK_RATE_SAMPLES: u32 = 100;
 
 pub fn insert_tick(path: &str, peer_id: &str, tick: u32) -> Result<(), String> {
     let prep_query = "INSERT INTO tick (tick, peer) VALUES(:tick, :peer) ON CONFLICT(tick) DO NOTHING";
@@ -261,4 +264,112 @@ pub fn set_tick_transaction_digests(path: &str, tick: u32, tx_digests: &[u8]) ->
             Err(err)
         }
     }
-}
\ No newline at end of file
+}
+//Only meaningful once the verified digests for this tick have been stored
+pub fn tick_includes_transaction_digest(path: &str, tick: u32, digest: &[u8]) -> Result<bool, String> {
+    let prep_query = "SELECT transaction_digests FROM tick WHERE tick = :tick LIMIT 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match crate::sqlite::crud::prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let encoded: String = statement.read::<String, _>("transaction_digests").unwrap();
+                                    match general_purpose::STANDARD_NO_PAD.decode::<&String>(&encoded) {
+                                        Ok(digests) => Ok(digests.chunks_exact(32).any(|d| d == digest)),
+                                        Err(err) => Err(err.to_string())
+                                    }
+                                },
+                                Ok(State::Done) => Err(format!("Tick {} Not Reported", tick)),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in tick_includes_transaction_digest! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in tick_includes_transaction_digest! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//(tick, unix seconds we first saw it) for the most recent ticks
+pub fn fetch_recent_tick_timestamps(path: &str, limit: u32) -> Result<Vec<(u32, u64)>, String> {
+    let prep_query = "SELECT tick, CAST(strftime('%s', created) AS INTEGER) AS created_unix FROM tick ORDER BY tick DESC LIMIT :limit;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match crate::sqlite::crud::prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":limit", limit.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<(u32, u64)> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let tick: i64 = statement.read::<i64, _>("tick").unwrap();
+                                let created: i64 = statement.read::<i64, _>("created_unix").unwrap();
+                                response.push((tick as u32, created as u64));
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_recent_tick_timestamps! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_recent_tick_timestamps! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_tick_rate_estimate(path: &str) -> Result<Option<TickRateEstimate>, String> {
+    let samples = fetch_recent_tick_timestamps(path, TICK_RATE_SAMPLES)?;
+    Ok(estimate_tick_rate(samples.as_slice()))
+}
+
+
+pub mod test_tick {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use std::time::SystemTime;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::peer::create_peer;
+    use crate::sqlite::tick::{fetch_recent_tick_timestamps, fetch_tick_rate_estimate, insert_tick};
+
+    #[test]
+    #[serial]
+    fn fetch_recent_tick_timestamps_newest_first() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            create_peer("test.sqlite", "id", "ip", "nickname", 3000, false, SystemTime::now()).unwrap();
+            assert!(fetch_tick_rate_estimate("test.sqlite").unwrap().is_none());
+            insert_tick("test.sqlite", "id", 100).unwrap();
+            insert_tick("test.sqlite", "id", 101).unwrap();
+            let samples = fetch_recent_tick_timestamps("test.sqlite", 10).unwrap();
+            assert_eq!(samples.len(), 2);
+            assert_eq!(samples[0].0, 101);
+            assert!(samples[0].1 > 1_600_000_000);
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
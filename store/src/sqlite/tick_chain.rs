@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//The light client's walk over the tick chain. A tick is requested, verified once its quorum votes pass,
+//then linked once its prev_* digests follow from the tick before it. Ticks no peer returns become gaps,
+//ticks whose digests don't follow become forks.
+pub const TICK_CHAIN_REQUESTED: i64 = 0;
+pub const TICK_CHAIN_VERIFIED: i64 = 1;
+pub const TICK_CHAIN_LINKED: i64 = 2;
+pub const TICK_CHAIN_GAP: i64 = 3;
+pub const TICK_CHAIN_FORK: i64 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickChainEntry {
+    pub tick: u32,
+    pub epoch: Option<u16>,
+    pub status: i64,
+    pub requests: u32,
+    pub digests: Option<String>
+}
+
+fn execute_tick_chain_statement(path: &str, prep_query: &str, params: &[(&str, &str)], fn_name: &str) -> Result<(), String> {
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(params) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in {}! : {}", fn_name, &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in {}! : {}", fn_name, &err);
+            Err(err)
+        }
+    }
+}
+
+//digests is the hex of the winning vote's consensus::tick_chain::TickDigests. Settled ticks are left alone.
+pub fn record_verified_tick(path: &str, tick: u32, epoch: u16, digests: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO tick_chain (tick, epoch, status, digests) VALUES (:tick, :epoch, 1, :digests) \
+    ON CONFLICT(tick) DO UPDATE SET epoch = :epoch, status = 1, digests = :digests, updated = CURRENT_TIMESTAMP WHERE status = 0;";
+    execute_tick_chain_statement(path, prep_query, &[
+        (":tick", tick.to_string().as_str()),
+        (":epoch", epoch.to_string().as_str()),
+        (":digests", digests),
+    ], "record_verified_tick")
+}
+
+pub fn request_tick_chain_tick(path: &str, tick: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO tick_chain (tick, requests) VALUES (:tick, 1) \
+    ON CONFLICT(tick) DO UPDATE SET requests = requests + 1, updated = CURRENT_TIMESTAMP WHERE status = 0;";
+    execute_tick_chain_statement(path, prep_query, &[
+        (":tick", tick.to_string().as_str()),
+    ], "request_tick_chain_tick")
+}
+
+pub fn set_tick_chain_status(path: &str, tick: u32, status: i64, detail: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO tick_chain (tick, status, detail) VALUES (:tick, CAST(:status AS INTEGER), :detail) \
+    ON CONFLICT(tick) DO UPDATE SET status = CAST(:status AS INTEGER), detail = :detail, updated = CURRENT_TIMESTAMP;";
+    execute_tick_chain_statement(path, prep_query, &[
+        (":tick", tick.to_string().as_str()),
+        (":status", status.to_string().as_str()),
+        (":detail", detail),
+    ], "set_tick_chain_status")
+}
+
+//Entries for ticks in [from, from + limit), in order. Ticks never requested have no entry.
+pub fn fetch_tick_chain(path: &str, from: u32, limit: u32) -> Result<Vec<TickChainEntry>, String> {
+    let prep_query = "SELECT tick, COALESCE(epoch, -1) AS epoch, status, requests, COALESCE(digests, '') AS digests FROM tick_chain \
+    WHERE tick >= CAST(:from AS INTEGER) AND tick < CAST(:from AS INTEGER) + CAST(:limit AS INTEGER) ORDER BY tick ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":from", from.to_string().as_str()),
+                        (":limit", limit.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<TickChainEntry> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let epoch: i64 = statement.read::<i64, _>("epoch").unwrap();
+                                let digests: String = statement.read::<String, _>("digests").unwrap();
+                                response.push(TickChainEntry {
+                                    tick: statement.read::<i64, _>("tick").unwrap() as u32,
+                                    epoch: if epoch < 0 { None } else { Some(epoch as u16) },
+                                    status: statement.read::<i64, _>("status").unwrap(),
+                                    requests: statement.read::<i64, _>("requests").unwrap() as u32,
+                                    digests: if digests.is_empty() { None } else { Some(digests) }
+                                });
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_tick_chain! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_tick_chain! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Gaps and forks, newest first
+pub fn fetch_tick_chain_issues(path: &str, limit: u32) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT tick, CASE status WHEN 3 THEN 'gap' ELSE 'fork' END AS kind, COALESCE(detail, '') AS detail, updated \
+    FROM tick_chain WHERE status IN (3, 4) ORDER BY tick DESC LIMIT :limit;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":limit", limit.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut issue: HashMap<String, String> = HashMap::new();
+                                for column in ["tick", "kind", "detail", "updated"] {
+                                    issue.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(issue);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_tick_chain_issues! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_tick_chain_issues! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//The last tick of the unbroken run of linked ticks starting at start_tick, None if start_tick itself isn't linked
+pub fn fetch_verified_watermark(path: &str, start_tick: u32) -> Result<Option<u32>, String> {
+    let prep_query = "SELECT MIN(t.tick) AS watermark FROM tick_chain t \
+    WHERE t.tick >= CAST(:start AS INTEGER) AND t.status = 2 \
+    AND NOT EXISTS (SELECT 1 FROM tick_chain n WHERE n.tick = t.tick + 1 AND n.status = 2) \
+    AND EXISTS (SELECT 1 FROM tick_chain s WHERE s.tick = CAST(:start AS INTEGER) AND s.status = 2);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":start", start_tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    match statement.read::<Option<i64>, _>("watermark").unwrap() {
+                                        Some(tick) => Ok(Some(tick as u32)),
+                                        None => Ok(None)
+                                    }
+                                },
+                                Ok(State::Done) => Ok(None),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_verified_watermark! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_verified_watermark! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//(start tick, cursor) where the cursor is the first tick not yet settled
+pub fn fetch_light_sync(path: &str) -> Result<Option<(u32, u32)>, String> {
+    let prep_query = "SELECT start_tick, cursor FROM light_sync WHERE id = 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.next() {
+                        Ok(State::Row) => {
+                            let start_tick: i64 = statement.read::<i64, _>("start_tick").unwrap();
+                            let cursor: i64 = statement.read::<i64, _>("cursor").unwrap();
+                            Ok(Some((start_tick as u32, cursor as u32)))
+                        },
+                        Ok(State::Done) => Ok(None),
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_light_sync! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_light_sync! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn set_light_sync(path: &str, start_tick: u32, cursor: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO light_sync (id, start_tick, cursor) VALUES (1, :start_tick, :cursor) \
+    ON CONFLICT(id) DO UPDATE SET start_tick = :start_tick, cursor = :cursor, updated = CURRENT_TIMESTAMP;";
+    execute_tick_chain_statement(path, prep_query, &[
+        (":start_tick", start_tick.to_string().as_str()),
+        (":cursor", cursor.to_string().as_str()),
+    ], "set_light_sync")
+}
+
+
+pub mod test_tick_chain {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::tick_chain::*;
+
+    #[test]
+    #[serial]
+    fn walk_the_tick_chain() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            assert_eq!(fetch_light_sync("test.sqlite").unwrap(), None);
+            set_light_sync("test.sqlite", 100, 100).unwrap();
+            set_light_sync("test.sqlite", 100, 102).unwrap();
+            assert_eq!(fetch_light_sync("test.sqlite").unwrap(), Some((100, 102)));
+
+            request_tick_chain_tick("test.sqlite", 100).unwrap();
+            request_tick_chain_tick("test.sqlite", 100).unwrap();
+            request_tick_chain_tick("test.sqlite", 101).unwrap();
+            record_verified_tick("test.sqlite", 100, 150, "aa").unwrap();
+            //Once verified a tick isn't re-requested
+            request_tick_chain_tick("test.sqlite", 100).unwrap();
+            let chain = fetch_tick_chain("test.sqlite", 100, 5).unwrap();
+            assert_eq!(chain, vec![
+                TickChainEntry { tick: 100, epoch: Some(150), status: TICK_CHAIN_VERIFIED, requests: 2, digests: Some("aa".to_string()) },
+                TickChainEntry { tick: 101, epoch: None, status: TICK_CHAIN_REQUESTED, requests: 1, digests: None }
+            ]);
+            assert_eq!(fetch_verified_watermark("test.sqlite", 100).unwrap(), None);
+
+            set_tick_chain_status("test.sqlite", 100, TICK_CHAIN_LINKED, "").unwrap();
+            set_tick_chain_status("test.sqlite", 101, TICK_CHAIN_LINKED, "").unwrap();
+            set_tick_chain_status("test.sqlite", 102, TICK_CHAIN_GAP, "No Peer Returned The Quorum Tick").unwrap();
+            set_tick_chain_status("test.sqlite", 103, TICK_CHAIN_LINKED, "").unwrap();
+            set_tick_chain_status("test.sqlite", 104, TICK_CHAIN_FORK, "Spectrum Digest Does Not Follow The Previous Tick").unwrap();
+            //A settled tick isn't overwritten by a late quorum tick
+            record_verified_tick("test.sqlite", 102, 150, "bb").unwrap();
+            assert_eq!(fetch_verified_watermark("test.sqlite", 100).unwrap(), Some(101));
+            assert_eq!(fetch_verified_watermark("test.sqlite", 103).unwrap(), Some(103));
+
+            let issues = fetch_tick_chain_issues("test.sqlite", 10).unwrap();
+            assert_eq!(issues.len(), 2);
+            assert_eq!(issues[0].get("kind").unwrap().as_str(), "fork");
+            assert_eq!(issues[1].get("tick").unwrap().as_str(), "102");
+            assert_eq!(issues[1].get("kind").unwrap().as_str(), "gap");
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//What we keep of a verified consensus::tick_data::TickData besides its digests.
+//timestamp is '' and unix_ms 0 when the tick data carried no valid time, contract_fees holds only non zero fees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickDataRecord {
+    pub tick: u32,
+    pub epoch: u16,
+    pub computor_index: u16,
+    pub timestamp: String,
+    pub unix_ms: u64,
+    pub transaction_count: u16,
+    pub total_contract_fees: i64,
+    pub contract_fees: Vec<(u16, i64)>
+}
+
+fn step(statement: &mut sqlite::Statement, values: &[(&str, &str)]) -> Result<(), String> {
+    statement.reset().map_err(|err| err.to_string())?;
+    match statement.bind::<&[(&str, &str)]>(values) {
+        Ok(_) => {
+            match statement.next() {
+                Ok(State::Done) => Ok(()),
+                Err(error) => Err(error.to_string()),
+                _ => Err("Weird!".to_string())
+            }
+        },
+        Err(err) => Err(err.to_string())
+    }
+}
+
+//Kept only once quorum has agreed on the tick and data_hash (the identity of the signed tick data) is the transaction
+//digest it agreed on. A computor can sign tick data quorum never adopts, or the tick can end up empty.
+pub fn record_tick_data(path: &str, record: &TickDataRecord, data_hash: &str) -> Result<(), String> {
+    let tick_query = "INSERT OR IGNORE INTO tick_data (tick, epoch, computor_index, timestamp, unix_ms, transaction_count, total_contract_fees) \
+    SELECT :tick, :epoch, :computor_index, :timestamp, CAST(:unix_ms AS INTEGER), :transaction_count, CAST(:total_contract_fees AS INTEGER) \
+    WHERE EXISTS (SELECT 1 FROM tick WHERE tick = :tick AND transaction_digests_hash != '' AND UPPER(transaction_digests_hash) = UPPER(:data_hash));";
+    let fee_query = "INSERT OR IGNORE INTO tick_contract_fee (tick, contract_index, fee) VALUES (:tick, :contract_index, CAST(:fee AS INTEGER));";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            connection.execute("BEGIN TRANSACTION;").unwrap();
+            let result = prepare_crud_statement(&connection, tick_query).and_then(|mut statement| {
+                step(&mut statement, &[
+                    (":tick", record.tick.to_string().as_str()),
+                    (":epoch", record.epoch.to_string().as_str()),
+                    (":computor_index", record.computor_index.to_string().as_str()),
+                    (":timestamp", record.timestamp.as_str()),
+                    (":unix_ms", record.unix_ms.to_string().as_str()),
+                    (":transaction_count", record.transaction_count.to_string().as_str()),
+                    (":total_contract_fees", record.total_contract_fees.to_string().as_str()),
+                    (":data_hash", data_hash),
+                ])
+            }).and_then(|_| {
+                //Fees belong to the tick row, nothing to add for a repeat or a copy quorum didn't adopt
+                if connection.change_count() == 0 {
+                    return Ok(());
+                }
+                let mut statement = prepare_crud_statement(&connection, fee_query)?;
+                for (contract_index, fee) in record.contract_fees.iter() {
+                    step(&mut statement, &[
+                        (":tick", record.tick.to_string().as_str()),
+                        (":contract_index", contract_index.to_string().as_str()),
+                        (":fee", fee.to_string().as_str()),
+                    ])?;
+                }
+                Ok(())
+            });
+            match result {
+                Ok(_) => match connection.execute("COMMIT;") {
+                    Ok(_) => Ok(()),
+                    Err(err) => Err(err.to_string())
+                },
+                Err(err) => {
+                    connection.execute("ROLLBACK;").unwrap();
+                    error!("Error in record_tick_data! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in record_tick_data! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//contract_fees comes back as contract:fee pairs, comma separated in contract order
+pub fn fetch_tick_data(path: &str, tick: u32) -> Result<Option<HashMap<String, String>>, String> {
+    let prep_query = "SELECT tick, epoch, computor_index, timestamp, unix_ms, transaction_count, total_contract_fees, \
+    COALESCE((SELECT GROUP_CONCAT(contract_index || ':' || fee, ',') FROM (SELECT contract_index, fee FROM tick_contract_fee WHERE tick = :tick ORDER BY contract_index)), '') AS contract_fees \
+    FROM tick_data WHERE tick = :tick;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let mut data: HashMap<String, String> = HashMap::new();
+                                    for column in ["tick", "epoch", "computor_index", "timestamp", "unix_ms", "transaction_count", "total_contract_fees", "contract_fees"] {
+                                        data.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                    }
+                                    Ok(Some(data))
+                                },
+                                Ok(State::Done) => Ok(None),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_tick_data! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_tick_data! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Time, transaction count and total fees of each recorded tick in [from, to], oldest first, for charting
+pub fn fetch_tick_fees(path: &str, from: u32, to: u32) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT tick, timestamp, transaction_count, total_contract_fees FROM tick_data \
+    WHERE tick >= CAST(:from AS INTEGER) AND tick <= CAST(:to AS INTEGER) ORDER BY tick ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":from", from.to_string().as_str()),
+                        (":to", to.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut data: HashMap<String, String> = HashMap::new();
+                                for column in ["tick", "timestamp", "transaction_count", "total_contract_fees"] {
+                                    data.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(data);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_tick_fees! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_tick_fees! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_tick_data {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use std::time::SystemTime;
+    use crate::sqlite::peer::create_peer;
+    use crate::sqlite::tick::{insert_tick, set_tick_tx_digest_hash};
+    use crate::sqlite::tick_data::{fetch_tick_data, fetch_tick_fees, record_tick_data, TickDataRecord};
+
+    const QUORUM_HASH: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+
+    #[test]
+    #[serial]
+    fn record_tick_time_and_fees() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            create_peer("test.sqlite", "p1", "1.1.1.1", "", 0, false, SystemTime::now()).unwrap();
+            for tick in [100, 101, 102] {
+                insert_tick("test.sqlite", "p1", tick).unwrap();
+            }
+            set_tick_tx_digest_hash("test.sqlite", &QUORUM_HASH.to_string(), 100).unwrap();
+            set_tick_tx_digest_hash("test.sqlite", &QUORUM_HASH.to_string(), 101).unwrap();
+            assert_eq!(fetch_tick_data("test.sqlite", 100).unwrap(), None);
+            let mut record = TickDataRecord {
+                tick: 100,
+                epoch: 163,
+                computor_index: 375,
+                timestamp: "2025-05-30 19:52:35.000".to_string(),
+                unix_ms: 1748634755000,
+                transaction_count: 12,
+                total_contract_fees: 1250,
+                contract_fees: vec![(4, 250), (1, 1000)]
+            };
+            //Signed tick data quorum didn't adopt arrives first and must not stick
+            let mut stray = record.clone();
+            stray.transaction_count = 3;
+            stray.contract_fees = vec![(7, 1)];
+            record_tick_data("test.sqlite", &stray, "STRAYHASH").unwrap();
+            assert_eq!(fetch_tick_data("test.sqlite", 100).unwrap(), None);
+            record_tick_data("test.sqlite", &record, QUORUM_HASH.to_lowercase().as_str()).unwrap();
+            //A second copy of the same tick data changes nothing
+            record.transaction_count = 0;
+            record.contract_fees = vec![(2, 5)];
+            record_tick_data("test.sqlite", &record, QUORUM_HASH).unwrap();
+            record.tick = 101;
+            record.timestamp = String::new();
+            record.unix_ms = 0;
+            record.total_contract_fees = 5;
+            record_tick_data("test.sqlite", &record, QUORUM_HASH).unwrap();
+            //No quorum digest yet for this tick
+            record.tick = 102;
+            record_tick_data("test.sqlite", &record, QUORUM_HASH).unwrap();
+            assert_eq!(fetch_tick_data("test.sqlite", 102).unwrap(), None);
+
+            let stored = fetch_tick_data("test.sqlite", 100).unwrap().unwrap();
+            assert_eq!(stored.get("timestamp").unwrap().as_str(), "2025-05-30 19:52:35.000");
+            assert_eq!(stored.get("unix_ms").unwrap().as_str(), "1748634755000");
+            assert_eq!(stored.get("transaction_count").unwrap().as_str(), "12");
+            assert_eq!(stored.get("contract_fees").unwrap().as_str(), "1:1000,4:250");
+
+            let fees = fetch_tick_fees("test.sqlite", 100, 102).unwrap();
+            assert_eq!(fees.len(), 2);
+            assert_eq!(fees[0].get("total_contract_fees").unwrap().as_str(), "1250");
+            assert_eq!(fees[1].get("timestamp").unwrap().as_str(), "");
+            assert_eq!(fetch_tick_data("test.sqlite", 101).unwrap().unwrap().get("contract_fees").unwrap().as_str(), "2:5");
+            assert_eq!(fetch_tick_fees("test.sqlite", 103, 200).unwrap().len(), 0);
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//input and signature are hex encoded
+pub fn create_tick_transaction(path: &str, txid: &str, tick: u32, source: &str, destination: &str, amount: u64, input_type: u16, input_size: u16, input: &str, signature: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO tick_transaction (txid, tick, source_identity, destination_identity, amount, input_type, input_size, input, signature) VALUES (:txid, :tick, :source, :destination, :amount, :input_type, :input_size, :input, :signature) ON CONFLICT(txid) DO NOTHING;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                        (":tick", tick.to_string().as_str()),
+                        (":source", source),
+                        (":destination", destination),
+                        (":amount", amount.to_string().as_str()),
+                        (":input_type", input_type.to_string().as_str()),
+                        (":input_size", input_size.to_string().as_str()),
+                        (":input", input),
+                        (":signature", signature),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_tick_transaction! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_tick_transaction! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_tick_transactions_by_tick(path: &str, tick: u32) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM tick_transaction WHERE tick = :tick ORDER BY created ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":tick", tick.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut tx: HashMap<String, String> = HashMap::new();
+                                for column in ["txid", "tick", "source_identity", "destination_identity", "amount", "input_type", "input_size", "input", "signature", "created"] {
+                                    tx.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(tx);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_tick_transactions_by_tick! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_tick_transactions_by_tick! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Every stored transaction where the identity is either side
+pub fn fetch_tick_transactions_by_identity(path: &str, identity: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM tick_transaction WHERE source_identity = :identity OR destination_identity = :identity ORDER BY tick DESC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut tx: HashMap<String, String> = HashMap::new();
+                                for column in ["txid", "tick", "source_identity", "destination_identity", "amount", "input_type", "input_size", "input", "signature", "created"] {
+                                    tx.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(tx);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_tick_transactions_by_identity! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_tick_transactions_by_identity! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_tick_transaction {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::tick_transaction::{create_tick_transaction, fetch_tick_transactions_by_identity, fetch_tick_transactions_by_tick};
+
+    const SOURCE: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const QX: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    #[test]
+    #[serial]
+    fn create_and_fetch_tick_transactions() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            create_tick_transaction("test.sqlite", "txa", 100, SOURCE, QX, 1, 6, 4, "01020304", "ab").unwrap();
+            create_tick_transaction("test.sqlite", "txa", 100, SOURCE, QX, 1, 6, 4, "01020304", "ab").unwrap();
+            create_tick_transaction("test.sqlite", "txb", 101, QX, SOURCE, 50, 0, 0, "", "cd").unwrap();
+
+            let at_tick = fetch_tick_transactions_by_tick("test.sqlite", 100).unwrap();
+            assert_eq!(at_tick.len(), 1);
+            assert_eq!(at_tick[0].get("input").unwrap().as_str(), "01020304");
+            assert_eq!(at_tick[0].get("input_type").unwrap().as_str(), "6");
+
+            let by_identity = fetch_tick_transactions_by_identity("test.sqlite", SOURCE).unwrap();
+            assert_eq!(by_identity.len(), 2);
+            assert_eq!(by_identity[0].get("txid").unwrap().as_str(), "txb");
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -403,6 +403,42 @@ pub fn set_broadcasted_transfer_as_failure(path: &str, txid: &str) -> Result<(),
 }
 
 
+//Everything we have signed from this identity that hasn't been confirmed or failed yet. A transfer never
+//broadcast by its tick stays at -1 but can't be included any more, so it no longer counts.
+pub fn fetch_pending_outgoing_amount(path: &str, identity: &str) -> Result<u64, String> {
+    let prep_query = "SELECT COALESCE(SUM(amount), 0) AS pending FROM transfer WHERE source_identity = :identity AND status = -1 \
+    AND (broadcast = true OR tick > COALESCE((SELECT MAX(tick) FROM tick), 0));";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => Ok(statement.read::<i64, _>("pending").unwrap() as u64),
+                                Ok(State::Done) => Ok(0),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_pending_outgoing_amount! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_pending_outgoing_amount! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
 pub mod test_transfer {
     use crate::sqlite::identity::insert_new_identity;
     use crate::sqlite::transfer::{create_transfer, fetch_transfer_by_txid, set_transfer_as_broadcast};
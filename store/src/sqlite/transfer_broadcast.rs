@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//A (txid, peer) pair moves queued -> sent/failed as the peer's worker writes it, and to acked once the peer relays it back
+
+fn execute_broadcast_statement(path: &str, prep_query: &str, txid: &str, peer: &str, status: Option<&str>, fn_name: &str) -> Result<(), String> {
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut params: Vec<(&str, &str)> = vec![(":txid", txid), (":peer", peer)];
+                    if let Some(status) = status {
+                        params.push((":status", status));
+                    }
+                    match statement.bind::<&[(&str, &str)]>(&params[..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in {}! : {}", fn_name, &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in {}! : {}", fn_name, &err);
+            Err(err)
+        }
+    }
+}
+
+//Each queueing counts as an attempt
+pub fn queue_transfer_broadcast(path: &str, txid: &str, peer: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO transfer_broadcast (txid, peer, status, attempts) VALUES (:txid, :peer, 'queued', 1) \
+    ON CONFLICT(txid, peer) DO UPDATE SET status = 'queued', attempts = attempts + 1, updated = CURRENT_TIMESTAMP;";
+    execute_broadcast_statement(path, prep_query, txid, peer, None, "queue_transfer_broadcast")
+}
+
+//sent or failed, as reported by the peer's worker. An ack is never downgraded.
+pub fn set_transfer_broadcast_status(path: &str, txid: &str, peer: &str, status: &str) -> Result<(), String> {
+    let prep_query = "UPDATE transfer_broadcast SET status = :status, updated = CURRENT_TIMESTAMP WHERE txid = :txid AND peer = :peer AND status != 'acked';";
+    execute_broadcast_statement(path, prep_query, txid, peer, Some(status), "set_transfer_broadcast_status")
+}
+
+//A peer relayed one of our transfers back to us, even if it heard of it from another peer
+pub fn acknowledge_transfer_broadcast(path: &str, txid: &str, peer: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO transfer_broadcast (txid, peer, status, attempts) \
+    SELECT :txid, :peer, 'acked', 0 WHERE EXISTS (SELECT 1 FROM transfer WHERE txid = :txid) \
+    ON CONFLICT(txid, peer) DO UPDATE SET status = 'acked', updated = CURRENT_TIMESTAMP;";
+    execute_broadcast_statement(path, prep_query, txid, peer, None, "acknowledge_transfer_broadcast")
+}
+
+//A worker that dies before reporting leaves its row queued forever, past max_age_secs it is retried like a failure
+pub fn fail_stale_transfer_broadcasts(path: &str, max_age_secs: u32) -> Result<(), String> {
+    let prep_query = "UPDATE transfer_broadcast SET status = 'failed', updated = CURRENT_TIMESTAMP \
+    WHERE status = 'queued' AND updated <= datetime('now', :age);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":age", format!("-{} seconds", max_age_secs).as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fail_stale_transfer_broadcasts! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fail_stale_transfer_broadcasts! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_transfer_broadcasts(path: &str, txid: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM transfer_broadcast WHERE txid = :txid ORDER BY created ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = Vec::new();
+                            while let Ok(State::Row) = statement.next() {
+                                let mut broadcast: HashMap<String, String> = HashMap::new();
+                                for column in ["peer", "status", "attempts", "created", "updated"] {
+                                    broadcast.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                }
+                                response.push(broadcast);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_transfer_broadcasts! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_transfer_broadcasts! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_transfer_broadcast {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use protocol::identity::Identity;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::transfer::create_transfer;
+    use crate::sqlite::transfer_broadcast::{acknowledge_transfer_broadcast, fail_stale_transfer_broadcasts, fetch_transfer_broadcasts, queue_transfer_broadcast, set_transfer_broadcast_status};
+
+    const SOURCE: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const DEST: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    fn status_of(peer: &str) -> (String, String) {
+        let broadcasts = fetch_transfer_broadcasts("test.sqlite", "txa").unwrap();
+        let record = broadcasts.iter().find(|b| b.get("peer").unwrap() == peer).unwrap();
+        (record.get("status").unwrap().to_string(), record.get("attempts").unwrap().to_string())
+    }
+
+    #[test]
+    #[serial]
+    fn track_a_broadcast_per_peer() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            insert_new_identity("test.sqlite", &Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf")).unwrap();
+            create_transfer("test.sqlite", SOURCE, DEST, 10, 100, "ab", "txa").unwrap();
+
+            queue_transfer_broadcast("test.sqlite", "txa", "peer1").unwrap();
+            queue_transfer_broadcast("test.sqlite", "txa", "peer2").unwrap();
+            set_transfer_broadcast_status("test.sqlite", "txa", "peer1", "sent").unwrap();
+            set_transfer_broadcast_status("test.sqlite", "txa", "peer2", "failed").unwrap();
+            assert_eq!(status_of("peer1"), ("sent".to_string(), "1".to_string()));
+
+            //Retrying a failed peer bumps its attempts
+            queue_transfer_broadcast("test.sqlite", "txa", "peer2").unwrap();
+            assert_eq!(status_of("peer2"), ("queued".to_string(), "2".to_string()));
+            //Still queued long after, its worker never reported
+            fail_stale_transfer_broadcasts("test.sqlite", 60).unwrap();
+            assert_eq!(status_of("peer2").0, "queued");
+            fail_stale_transfer_broadcasts("test.sqlite", 0).unwrap();
+            assert_eq!(status_of("peer2"), ("failed".to_string(), "2".to_string()));
+
+            //Acks come from any peer relaying it, and stick
+            acknowledge_transfer_broadcast("test.sqlite", "txa", "peer1").unwrap();
+            acknowledge_transfer_broadcast("test.sqlite", "txa", "peer3").unwrap();
+            set_transfer_broadcast_status("test.sqlite", "txa", "peer1", "failed").unwrap();
+            assert_eq!(status_of("peer1").0, "acked");
+            assert_eq!(status_of("peer3"), ("acked".to_string(), "0".to_string()));
+
+            //Transactions that aren't ours are ignored
+            acknowledge_transfer_broadcast("test.sqlite", "txb", "peer1").unwrap();
+            assert!(fetch_transfer_broadcasts("test.sqlite", "txb").unwrap().is_empty());
+            assert_eq!(fetch_transfer_broadcasts("test.sqlite", "txa").unwrap().len(), 3);
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//How and when the confirmer settled a transfer's status. outcome is included, not_included or expired.
+pub fn record_transfer_check(path: &str, txid: &str, checked_at_tick: u32, outcome: &str) -> Result<(), String> {
+    let prep_query = "INSERT INTO transfer_check (txid, checked_at_tick, outcome) VALUES (:txid, :checked_at_tick, :outcome) \
+    ON CONFLICT(txid) DO UPDATE SET checked_at_tick = :checked_at_tick, outcome = :outcome;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                        (":checked_at_tick", checked_at_tick.to_string().as_str()),
+                        (":outcome", outcome),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in record_transfer_check! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in record_transfer_check! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_transfer_check(path: &str, txid: &str) -> Result<Option<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM transfer_check WHERE txid = :txid LIMIT 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let mut check: HashMap<String, String> = HashMap::new();
+                                    for column in ["txid", "checked_at_tick", "outcome", "created"] {
+                                        check.insert(column.to_string(), statement.read::<String, _>(column).unwrap());
+                                    }
+                                    Ok(Some(check))
+                                },
+                                Ok(State::Done) => Ok(None),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_transfer_check! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_transfer_check! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_transfer_check {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::transfer_check::{fetch_transfer_check, record_transfer_check};
+
+    #[test]
+    #[serial]
+    fn record_and_fetch_a_transfer_check() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            assert!(fetch_transfer_check("test.sqlite", "txa").unwrap().is_none());
+            record_transfer_check("test.sqlite", "txa", 120, "not_included").unwrap();
+            record_transfer_check("test.sqlite", "txa", 35200, "expired").unwrap();
+            assert!(record_transfer_check("test.sqlite", "txa", 35200, "unknown").is_err());
+            let check = fetch_transfer_check("test.sqlite", "txa").unwrap().unwrap();
+            assert_eq!(check.get("checked_at_tick").unwrap().as_str(), "35200");
+            assert_eq!(check.get("outcome").unwrap().as_str(), "expired");
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
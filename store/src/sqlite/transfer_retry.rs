@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use sqlite::State;
+use logger::error;
+use crate::sqlite::create::open_database;
+use crate::sqlite::crud::prepare_crud_statement;
+use crate::sqlite::get_db_lock;
+
+//Opting an identity in to resending its failed transfers, max_retries caps resends per original transfer
+pub fn set_resend_policy(path: &str, identity: &str, max_retries: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO resend_policy (identity, max_retries) VALUES (:identity, :max_retries) \
+    ON CONFLICT(identity) DO UPDATE SET max_retries = :max_retries;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                        (":max_retries", max_retries.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in set_resend_policy! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in set_resend_policy! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn fetch_resend_policy(path: &str, identity: &str) -> Result<Option<HashMap<String, String>>, String> {
+    let prep_query = "SELECT * FROM resend_policy WHERE identity = :identity LIMIT 1;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Row) => {
+                                    let mut policy: HashMap<String, String> = HashMap::new();
+                                    policy.insert("identity".to_string(), statement.read::<String, _>("identity").unwrap());
+                                    policy.insert("max_retries".to_string(), statement.read::<String, _>("max_retries").unwrap());
+                                    policy.insert("created".to_string(), statement.read::<String, _>("created").unwrap());
+                                    Ok(Some(policy))
+                                },
+                                Ok(State::Done) => Ok(None),
+                                Err(err) => Err(err.to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_resend_policy! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_resend_policy! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn delete_resend_policy(path: &str, identity: &str) -> Result<(), String> {
+    let prep_query = "DELETE FROM resend_policy WHERE identity = :identity;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":identity", identity),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in delete_resend_policy! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in delete_resend_policy! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Failed plain QU transfers from opted in identities that haven't been resent and are still under the retry cap.
+//Only transfers made after opting in are picked up, so enabling the policy doesn't replay old history.
+pub fn fetch_failed_transfers_to_resend(path: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT t.*, COALESCE(r.original_txid, t.txid) AS original_txid, COALESCE(r.attempt, 0) AS attempt FROM transfer t \
+    INNER JOIN resend_policy p ON p.identity = t.source_identity \
+    LEFT JOIN transfer_retry r ON r.txid = t.txid \
+    WHERE t.broadcast = true AND t.status = 1 AND t.created >= p.created \
+    AND COALESCE(r.attempt, 0) < p.max_retries \
+    AND t.txid NOT IN (SELECT previous_txid FROM transfer_retry) \
+    AND t.txid NOT IN (SELECT txid FROM asset_transfer) \
+    AND t.txid NOT IN (SELECT txid FROM qx_order) \
+    AND t.txid NOT IN (SELECT txid FROM payout_recipient) \
+    ORDER BY t.tick ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    let mut response: Vec<HashMap<String, String>> = vec![];
+                    while let Ok(State::Row) = statement.next() {
+                        let mut transfer: HashMap<String, String> = HashMap::new();
+                        transfer.insert("source".to_string(), statement.read::<String, _>("source_identity").unwrap());
+                        transfer.insert("destination".to_string(), statement.read::<String, _>("destination_identity").unwrap());
+                        transfer.insert("amount".to_string(), statement.read::<String, _>("amount").unwrap());
+                        transfer.insert("tick".to_string(), statement.read::<String, _>("tick").unwrap());
+                        transfer.insert("txid".to_string(), statement.read::<String, _>("txid").unwrap());
+                        transfer.insert("original_txid".to_string(), statement.read::<String, _>("original_txid").unwrap());
+                        transfer.insert("attempt".to_string(), statement.read::<String, _>("attempt").unwrap());
+                        response.push(transfer);
+                    }
+                    Ok(response)
+                },
+                Err(err) => {
+                    error!("Error in fetch_failed_transfers_to_resend! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_failed_transfers_to_resend! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+pub fn create_transfer_retry(path: &str, txid: &str, previous_txid: &str, original_txid: &str, attempt: u32) -> Result<(), String> {
+    let prep_query = "INSERT INTO transfer_retry (txid, previous_txid, original_txid, attempt) VALUES (:txid, :previous_txid, :original_txid, :attempt);";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                        (":previous_txid", previous_txid),
+                        (":original_txid", original_txid),
+                        (":attempt", attempt.to_string().as_str()),
+                    ][..]) {
+                        Ok(_) => {
+                            match statement.next() {
+                                Ok(State::Done) => Ok(()),
+                                Err(error) => Err(error.to_string()),
+                                _ => Err("Weird!".to_string())
+                            }
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in create_transfer_retry! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in create_transfer_retry! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+//Every retry made for a transfer, txid may be the original or any retry in the chain
+pub fn fetch_transfer_retries(path: &str, txid: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let prep_query = "SELECT r.*, t.tick, t.status FROM transfer_retry r INNER JOIN transfer t ON t.txid = r.txid \
+    WHERE r.original_txid = (SELECT COALESCE((SELECT original_txid FROM transfer_retry WHERE txid = :txid), :txid)) \
+    ORDER BY r.attempt ASC;";
+    let _lock = get_db_lock().lock().unwrap();
+    match open_database(path, false) {
+        Ok(connection) => {
+            match prepare_crud_statement(&connection, prep_query) {
+                Ok(mut statement) => {
+                    match statement.bind::<&[(&str, &str)]>(&[
+                        (":txid", txid),
+                    ][..]) {
+                        Ok(_) => {
+                            let mut response: Vec<HashMap<String, String>> = vec![];
+                            while let Ok(State::Row) = statement.next() {
+                                let mut retry: HashMap<String, String> = HashMap::new();
+                                retry.insert("txid".to_string(), statement.read::<String, _>("txid").unwrap());
+                                retry.insert("previous_txid".to_string(), statement.read::<String, _>("previous_txid").unwrap());
+                                retry.insert("original_txid".to_string(), statement.read::<String, _>("original_txid").unwrap());
+                                retry.insert("attempt".to_string(), statement.read::<String, _>("attempt").unwrap());
+                                retry.insert("tick".to_string(), statement.read::<String, _>("tick").unwrap());
+                                retry.insert("status".to_string(), statement.read::<String, _>("status").unwrap());
+                                retry.insert("created".to_string(), statement.read::<String, _>("created").unwrap());
+                                response.push(retry);
+                            }
+                            Ok(response)
+                        },
+                        Err(err) => Err(err.to_string())
+                    }
+                },
+                Err(err) => {
+                    error!("Error in fetch_transfer_retries! : {}", &err);
+                    Err(err)
+                }
+            }
+        },
+        Err(err) => {
+            error!("Error in fetch_transfer_retries! : {}", &err);
+            Err(err)
+        }
+    }
+}
+
+
+pub mod test_transfer_retry {
+    #![allow(dead_code, unused)]
+    use serial_test::serial;
+    use std::fs;
+    use protocol::identity::Identity;
+    use crate::sqlite::create::open_database;
+    use crate::sqlite::identity::insert_new_identity;
+    use crate::sqlite::transfer::{create_transfer, fetch_pending_outgoing_amount, set_broadcasted_transfer_as_failure, set_transfer_as_broadcast};
+    use crate::sqlite::transfer_retry::{create_transfer_retry, delete_resend_policy, fetch_failed_transfers_to_resend, fetch_resend_policy, fetch_transfer_retries, set_resend_policy};
+
+    const SOURCE: &str = "EPYWDREDNLHXOFYVGQUKPHJGOMPBSLDDGZDPKVQUMFXAIQYMZGEHPZTAAWON";
+    const DEST: &str = "BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAARMID";
+
+    fn fail(txid: &str) {
+        set_transfer_as_broadcast("test.sqlite", txid).unwrap();
+        set_broadcasted_transfer_as_failure("test.sqlite", txid).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn resend_failed_transfers_up_to_the_retry_cap() {
+        {
+            open_database("test.sqlite", true).unwrap();
+            let id: Identity = Identity::new("lcehvbvddggkjfnokduyjuiyvkklrvrmsaozwbvjlzvgvfipqpnkkuf");
+            insert_new_identity("test.sqlite", &id).unwrap();
+            set_resend_policy("test.sqlite", SOURCE, 1).unwrap();
+            assert_eq!(fetch_resend_policy("test.sqlite", SOURCE).unwrap().unwrap().get("max_retries").unwrap().as_str(), "1");
+
+            create_transfer("test.sqlite", SOURCE, DEST, 100, 1000, "sig", "original").unwrap();
+            assert_eq!(fetch_pending_outgoing_amount("test.sqlite", SOURCE).unwrap(), 100);
+            fail("original");
+            assert_eq!(fetch_pending_outgoing_amount("test.sqlite", SOURCE).unwrap(), 0);
+
+            let to_resend = fetch_failed_transfers_to_resend("test.sqlite").unwrap();
+            assert_eq!(to_resend.len(), 1);
+            assert_eq!(to_resend[0].get("original_txid").unwrap().as_str(), "original");
+            assert_eq!(to_resend[0].get("attempt").unwrap().as_str(), "0");
+
+            create_transfer("test.sqlite", SOURCE, DEST, 100, 1100, "sig", "retry").unwrap();
+            create_transfer_retry("test.sqlite", "retry", "original", "original", 1).unwrap();
+            assert!(fetch_failed_transfers_to_resend("test.sqlite").unwrap().is_empty());
+
+            //The retry failing too doesn't trigger another, the cap is 1
+            fail("retry");
+            assert!(fetch_failed_transfers_to_resend("test.sqlite").unwrap().is_empty());
+            set_resend_policy("test.sqlite", SOURCE, 2).unwrap();
+            assert_eq!(fetch_failed_transfers_to_resend("test.sqlite").unwrap()[0].get("txid").unwrap().as_str(), "retry");
+
+            let retries = fetch_transfer_retries("test.sqlite", "original").unwrap();
+            assert_eq!(retries.len(), 1);
+            assert_eq!(fetch_transfer_retries("test.sqlite", "retry").unwrap().len(), 1);
+
+            delete_resend_policy("test.sqlite", SOURCE).unwrap();
+            assert!(fetch_failed_transfers_to_resend("test.sqlite").unwrap().is_empty());
+        }
+        fs::remove_file("test.sqlite").unwrap();
+    }
+}
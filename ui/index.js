@@ -494,6 +494,7 @@ const getBalance = async identity => {
             } catch(err) {
                 balanceTd.innerHTML = `<b>${balanceArray[0]}</b>`
             }
+            await appendAvailableBalance(identity, balanceTd);
         } else {
             let html = `<span title="${reportedByTitle}"><b>Peer Responded Balance Mismatch: </b></span> [|`
             for(const r of balanceArray) {
@@ -508,6 +509,19 @@ const getBalance = async identity => {
     }
 }
 
+const appendAvailableBalance = async (identity, balanceTd) => {
+    const serverIp = document.getElementById("serverIp").value;
+    try {
+        const result = await makeHttpRequest(`${serverIp}/balance/available/${identity}`);
+        const res = JSON.parse(result);
+        if(res['pending_outgoing'] !== "0") {
+            balanceTd.innerHTML += ` <span title="${res['pending_outgoing']} Pending Outgoing (${res['pending_bid_escrow']} Bid Escrow)">(${res['available']} Available)</span>`
+        }
+    } catch(error) {
+        console.log(error)
+    }
+}
+
 const getAssets = async identity => {
     const serverIp = document.getElementById("serverIp").value;
     const balanceTd = document.getElementById(`assetsDiv`);